@@ -1,6 +1,8 @@
 use anyhow::{anyhow, Result};
 use aws_config::BehaviorVersion;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EnclaveInfo {
@@ -9,16 +11,45 @@ pub struct EnclaveInfo {
     pub memory_mb: u32,
 }
 
+/// Utilization of a client's enclave connection pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolStats {
+    pub size: usize,
+    pub in_use: usize,
+}
+
+impl PoolStats {
+    /// Fraction of connections checked out, from 0.0 to 1.0.
+    pub fn utilization(&self) -> f64 {
+        if self.size == 0 {
+            0.0
+        } else {
+            self.in_use as f64 / self.size as f64
+        }
+    }
+}
+
+/// Simulated pool of enclave connections: each permit stands for one open
+/// channel to the enclave.
+#[derive(Clone)]
+struct ConnectionPool {
+    size: usize,
+    connections: Arc<Semaphore>,
+}
+
+/// Clones share the connection pool, if any.
+#[derive(Clone)]
 pub struct NautilusClient {
     // In a real implementation, these would be actual AWS SDK clients
     // For now, we'll use a mock that can be extended
     config: aws_config::SdkConfig,
+    pool: Option<ConnectionPool>,
 }
 
 impl NautilusClient {
     pub async fn connect() -> Result<Self> {
         let config = aws_config::load_defaults(BehaviorVersion::latest()).await;
-        Ok(Self { config })
+        Ok(Self { config, pool: None })
     }
 
     pub fn connect_sync() -> Result<Self> {
@@ -27,7 +58,32 @@ impl NautilusClient {
         // Note: This is a simplified version - real implementation would use tokio runtime
         let rt = tokio::runtime::Runtime::new()?;
         let config = rt.block_on(aws_config::load_defaults(BehaviorVersion::latest()));
-        Ok(Self { config })
+        Ok(Self { config, pool: None })
+    }
+
+    /// Limits `send_transaction` to `size` concurrent enclave connections;
+    /// further sends wait for one to be returned. Without a pool sends are
+    /// unbounded. The sync variant does not use the pool.
+    pub fn with_pool(mut self, size: usize) -> Self {
+        self.set_pool(size);
+        self
+    }
+
+    /// In-place form of `with_pool`, replacing any existing pool.
+    pub fn set_pool(&mut self, size: usize) {
+        let size = size.max(1);
+        self.pool = Some(ConnectionPool {
+            size,
+            connections: Arc::new(Semaphore::new(size)),
+        });
+    }
+
+    /// Current pool utilization, or `None` if the client is unpooled.
+    pub fn pool_stats(&self) -> Option<PoolStats> {
+        self.pool.as_ref().map(|pool| PoolStats {
+            size: pool.size,
+            in_use: pool.size - pool.connections.available_permits(),
+        })
     }
 
     pub async fn create_enclave(&self, info: &EnclaveInfo) -> Result<String> {
@@ -76,6 +132,17 @@ impl NautilusClient {
             return Err(anyhow!("missing enclave id"));
         }
 
+        // Held until the send completes, returning the connection on drop.
+        let _connection = match &self.pool {
+            Some(pool) => Some(
+                pool.connections
+                    .acquire()
+                    .await
+                    .map_err(|_| anyhow!("enclave connection pool closed"))?,
+            ),
+            None => None,
+        };
+
         // In a real implementation, this would:
         // - Establish secure channel to enclave
         // - Send encrypted transaction data
@@ -92,7 +159,57 @@ impl NautilusClient {
         Ok(format!("submitted:{}", payload))
     }
 
+    pub async fn terminate_enclave(&self, enclave_id: &str) -> Result<()> {
+        if enclave_id.is_empty() {
+            return Err(anyhow!("missing enclave id"));
+        }
+
+        // In a real implementation, this would call TerminateEnclave and wait
+        // for the enclave to report it has stopped.
+
+        Ok(())
+    }
+
     pub fn get_config(&self) -> &aws_config::SdkConfig {
         &self.config
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn sends_wait_for_a_free_connection() {
+        let client = NautilusClient::connect().await.unwrap().with_pool(2);
+        let connections = Arc::clone(&client.pool.as_ref().unwrap().connections);
+        let held = connections.acquire_many(2).await.unwrap();
+        assert_eq!(client.pool_stats(), Some(PoolStats { size: 2, in_use: 2 }));
+
+        let waiting = {
+            let client = client.clone();
+            tokio::spawn(async move { client.send_transaction("enclave-1", serde_json::json!(1)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        drop(held);
+        assert!(waiting.await.unwrap().is_ok());
+        assert_eq!(client.pool_stats().unwrap().in_use, 0);
+    }
+
+    #[tokio::test]
+    async fn more_sends_than_connections_all_complete() {
+        let client = NautilusClient::connect().await.unwrap().with_pool(2);
+
+        let (first, second, third) = tokio::join!(
+            client.send_transaction("enclave-1", serde_json::json!(1)),
+            client.send_transaction("enclave-1", serde_json::json!(2)),
+            client.send_transaction("enclave-1", serde_json::json!(3)),
+        );
+
+        assert!(first.is_ok() && second.is_ok() && third.is_ok());
+        assert_eq!(client.pool_stats().unwrap().utilization(), 0.0);
+    }
+}
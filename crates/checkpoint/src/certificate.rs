@@ -0,0 +1,262 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use sui_core::{
+    aggregate::{AggregateSignature, AggregationError, SignatureAggregator, SignerBitmap},
+    committee::{Committee, RegistryError},
+    crypto::{verify_signature, KeyPair},
+    messages::CheckpointSummary,
+    transaction::TransactionDigest,
+};
+use thiserror::Error;
+
+/// Signatures needed to certify anything in a committee of `size`: more than
+/// two thirds of its members.
+pub fn quorum_threshold(size: usize) -> usize {
+    size * 2 / 3 + 1
+}
+
+/// A checkpoint summary together with validator signatures over it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CertifiedCheckpoint {
+    pub summary: CheckpointSummary,
+    /// `(validator name, hex signature)` pairs.
+    pub signatures: Vec<(String, String)>,
+}
+
+impl CertifiedCheckpoint {
+    pub fn new(summary: CheckpointSummary) -> Self {
+        Self {
+            summary,
+            signatures: Vec::new(),
+        }
+    }
+
+    pub fn sign(mut self, validator: impl Into<String>, keypair: &KeyPair) -> Self {
+        let signature = keypair.sign(&self.summary.signing_bytes());
+        self.signatures.push((validator.into(), signature));
+        self
+    }
+
+    /// Whether a quorum of distinct `committee` members validly signed the
+    /// summary. Signers are resolved by name through the committee's
+    /// `ValidatorRegistry`; signatures from non-members are ignored, and a
+    /// committee with duplicate names certifies nothing.
+    pub fn verify(&self, committee: &Committee) -> bool {
+        let Ok(registry) = committee.registry() else {
+            return false;
+        };
+        let message = self.summary.signing_bytes();
+        let signers: HashSet<&str> = self
+            .signatures
+            .iter()
+            .filter(|(name, signature)| {
+                registry
+                    .public_key(name)
+                    .is_some_and(|public_key| verify_signature(public_key, &message, signature))
+            })
+            .map(|(name, _)| name.as_str())
+            .collect();
+        !registry.is_empty() && signers.len() >= quorum_threshold(registry.len())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CertificateError {
+    #[error(transparent)]
+    Committee(#[from] RegistryError),
+    #[error("{0} is not a committee member")]
+    UnknownSigner(String),
+    #[error("signature by {0} does not verify")]
+    InvalidSignature(String),
+    #[error("{signers} signatures fall short of the quorum of {quorum}")]
+    NoQuorum { signers: usize, quorum: usize },
+    #[error(transparent)]
+    Aggregation(#[from] AggregationError),
+}
+
+/// A transaction digest certified by a committee quorum: one aggregate
+/// signature plus a bitmap of the members who contributed to it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CertifiedTransaction {
+    pub digest: TransactionDigest,
+    pub signature: AggregateSignature,
+    pub signers: SignerBitmap,
+}
+
+impl CertifiedTransaction {
+    /// What each validator signs to certify `digest`.
+    pub fn signing_bytes(digest: &TransactionDigest) -> Vec<u8> {
+        format!("certified-transaction:{}", digest.0).into_bytes()
+    }
+
+    /// Aggregates `(validator name, hex signature)` pairs over `digest`
+    /// into a certificate. Every signature is checked against its signer's
+    /// key first; repeated signers count once.
+    pub fn aggregate(
+        committee: &Committee,
+        aggregator: &dyn SignatureAggregator,
+        digest: TransactionDigest,
+        signatures: &[(String, String)],
+    ) -> Result<Self, CertificateError> {
+        let registry = committee.registry()?;
+        let message = Self::signing_bytes(&digest);
+        let mut by_index = BTreeMap::new();
+        for (name, signature) in signatures {
+            let index = committee
+                .members
+                .iter()
+                .position(|member| &member.name == name)
+                .ok_or_else(|| CertificateError::UnknownSigner(name.clone()))?;
+            let public_key = registry
+                .public_key(name)
+                .ok_or_else(|| CertificateError::UnknownSigner(name.clone()))?;
+            if !verify_signature(public_key, &message, signature) {
+                return Err(CertificateError::InvalidSignature(name.clone()));
+            }
+            by_index.entry(index).or_insert_with(|| signature.clone());
+        }
+
+        let quorum = quorum_threshold(registry.len());
+        if by_index.len() < quorum {
+            return Err(CertificateError::NoQuorum {
+                signers: by_index.len(),
+                quorum,
+            });
+        }
+        let mut signers = SignerBitmap::new(committee.size());
+        for index in by_index.keys() {
+            signers.set(*index);
+        }
+        let ordered: Vec<String> = by_index.into_values().collect();
+        Ok(Self {
+            digest,
+            signature: aggregator.aggregate(&ordered)?,
+            signers,
+        })
+    }
+
+    /// Whether the aggregate verifies for the members in the bitmap and
+    /// they form a quorum of `committee`.
+    pub fn verify(&self, committee: &Committee, aggregator: &dyn SignatureAggregator) -> bool {
+        if committee.registry().is_err() || self.signers.len() != committee.size() {
+            return false;
+        }
+        let signers: Vec<&str> = self
+            .signers
+            .indices()
+            .map(|index| committee.members[index].public_key.as_str())
+            .collect();
+        !signers.is_empty()
+            && signers.len() >= quorum_threshold(committee.size())
+            && aggregator.verify_aggregate(&self.signature, &signers, &Self::signing_bytes(&self.digest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_core::aggregate::MockAggregator;
+    use sui_core::committee::CommitteeMember;
+
+    fn validators() -> Vec<(String, KeyPair)> {
+        (1..=4u8)
+            .map(|i| (format!("v{}", i), KeyPair::from_seed([i; 32])))
+            .collect()
+    }
+
+    fn committee(validators: &[(String, KeyPair)]) -> Committee {
+        let members = validators
+            .iter()
+            .map(|(name, keypair)| CommitteeMember {
+                name: name.clone(),
+                public_key: keypair.public_key(),
+                endpoint: String::new(),
+            })
+            .collect();
+        Committee::new(0, members)
+    }
+
+    fn signatures(validators: &[(String, KeyPair)], digest: &TransactionDigest) -> Vec<(String, String)> {
+        let message = CertifiedTransaction::signing_bytes(digest);
+        validators
+            .iter()
+            .map(|(name, keypair)| (name.clone(), keypair.sign(&message)))
+            .collect()
+    }
+
+    #[test]
+    fn three_of_four_signers_certify_a_transaction() {
+        let validators = validators();
+        let committee = committee(&validators);
+        let digest = TransactionDigest::random();
+        let signatures = signatures(&validators[1..], &digest);
+
+        let certificate = CertifiedTransaction::aggregate(&committee, &MockAggregator, digest, &signatures).unwrap();
+
+        assert_eq!(certificate.signers.indices().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert!(certificate.verify(&committee, &MockAggregator));
+    }
+
+    #[test]
+    fn fewer_than_a_quorum_of_signers_certify_nothing() {
+        let validators = validators();
+        let committee = committee(&validators);
+        let digest = TransactionDigest::random();
+        let mut signatures = signatures(&validators[..2], &digest);
+        signatures.push(signatures[0].clone());
+
+        let result = CertifiedTransaction::aggregate(&committee, &MockAggregator, digest, &signatures);
+
+        assert_eq!(result, Err(CertificateError::NoQuorum { signers: 2, quorum: 3 }));
+    }
+
+    #[test]
+    fn certificate_with_a_bitmap_below_quorum_does_not_verify() {
+        let validators = validators();
+        let committee = committee(&validators);
+        let digest = TransactionDigest::random();
+        let mut certificate =
+            CertifiedTransaction::aggregate(&committee, &MockAggregator, digest.clone(), &signatures(&validators[..3], &digest))
+                .unwrap();
+
+        let mut signers = SignerBitmap::new(4);
+        signers.set(0);
+        signers.set(1);
+        certificate.signers = signers;
+
+        assert!(!certificate.verify(&committee, &MockAggregator));
+    }
+
+    #[test]
+    fn certificate_naming_the_wrong_signers_does_not_verify() {
+        let validators = validators();
+        let committee = committee(&validators);
+        let digest = TransactionDigest::random();
+        let mut certificate =
+            CertifiedTransaction::aggregate(&committee, &MockAggregator, digest.clone(), &signatures(&validators[1..], &digest))
+                .unwrap();
+
+        let mut signers = SignerBitmap::new(4);
+        for index in 0..3 {
+            signers.set(index);
+        }
+        certificate.signers = signers;
+
+        assert!(!certificate.verify(&committee, &MockAggregator));
+    }
+
+    #[test]
+    fn checkpoints_are_certified_through_the_registry() {
+        let validators = validators();
+        let certified = validators[..3]
+            .iter()
+            .fold(CertifiedCheckpoint::new(CheckpointSummary::new(1, "root")), |certified, (name, keypair)| {
+                certified.sign(name.clone(), keypair)
+            });
+        assert!(certified.verify(&committee(&validators)));
+
+        let mut duplicated = committee(&validators);
+        duplicated.members[3].name = "v1".to_string();
+        assert!(!certified.verify(&duplicated));
+    }
+}
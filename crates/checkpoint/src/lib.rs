@@ -1,8 +1,29 @@
-use sui_core::messages::CheckpointSummary;
+mod certificate;
+mod light_client;
+mod merkle;
+
+pub use certificate::{quorum_threshold, CertificateError, CertifiedCheckpoint, CertifiedTransaction};
+pub use light_client::{LightClient, LightClientError, ObjectUpdate};
+pub use merkle::{object_leaf, objects_root, objects_root_with, MerkleProof, MerkleTree};
+
+use std::sync::Arc;
+use sui_core::{
+    hash::{default_hasher, Hasher},
+    messages::CheckpointSummary,
+};
 
-#[derive(Default)]
 pub struct CheckpointAggregator {
     summaries: Vec<CheckpointSummary>,
+    hasher: Arc<dyn Hasher>,
+}
+
+impl Default for CheckpointAggregator {
+    fn default() -> Self {
+        Self {
+            summaries: Vec::new(),
+            hasher: default_hasher(),
+        }
+    }
 }
 
 impl CheckpointAggregator {
@@ -10,6 +31,18 @@ impl CheckpointAggregator {
         Self::default()
     }
 
+    /// Chains checkpoints by their digest under `hasher`; it must match the
+    /// hasher of every validator producing them.
+    pub fn with_hasher(mut self, hasher: Arc<dyn Hasher>) -> Self {
+        self.hasher = hasher;
+        self
+    }
+
+    /// The digest the checkpoint after `summary` references.
+    pub fn digest(&self, summary: &CheckpointSummary) -> String {
+        summary.digest_with(self.hasher.as_ref())
+    }
+
     pub fn record(&mut self, summary: CheckpointSummary) {
         self.summaries.push(summary);
     }
@@ -18,8 +51,54 @@ impl CheckpointAggregator {
         self.summaries.last()
     }
 
+    /// The `previous_digest` the next checkpoint should carry.
+    pub fn next_previous_digest(&self) -> Option<String> {
+        self.latest().map(|latest| self.digest(latest))
+    }
+
+    /// Checks that every recorded checkpoint references the digest of the one
+    /// before it. The first checkpoint may reference anything, since the
+    /// aggregator can start part-way through a chain.
+    pub fn verify_chain(&self) -> bool {
+        self.summaries
+            .windows(2)
+            .all(|pair| pair[1].previous_digest.as_deref() == Some(self.digest(&pair[0]).as_str()))
+    }
+
     pub fn total_transactions(&self) -> usize {
         self.summaries.iter().map(|s| s.transaction_count).sum()
     }
+
+    pub fn total_gas_used(&self) -> u64 {
+        self.summaries.iter().map(|s| s.total_gas_used).sum()
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records `count` checkpoints, each referencing the one before.
+    fn chained(count: u64) -> CheckpointAggregator {
+        let mut aggregator = CheckpointAggregator::new();
+        for sequence in 1..=count {
+            let mut summary = CheckpointSummary::new(sequence, format!("root-{}", sequence));
+            summary.previous_digest = aggregator.next_previous_digest();
+            aggregator.record(summary);
+        }
+        aggregator
+    }
+
+    #[test]
+    fn well_formed_chain_verifies() {
+        assert!(chained(3).verify_chain());
+    }
+
+    #[test]
+    fn tampering_with_a_middle_checkpoint_breaks_the_chain() {
+        let mut aggregator = chained(3);
+        aggregator.summaries[1].total_gas_used += 1;
+
+        assert!(!aggregator.verify_chain());
+    }
+}
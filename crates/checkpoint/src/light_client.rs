@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use sui_core::{
+    committee::Committee,
+    hash::{default_hasher, Hasher},
+    object::{ObjectID, SuiObject},
+};
+use thiserror::Error;
+
+use crate::{merkle::object_leaf, CertifiedCheckpoint, MerkleProof};
+
+#[derive(Debug, Error)]
+pub enum LightClientError {
+    #[error("checkpoint {0} is not certified by a committee quorum")]
+    InvalidCertificate(u64),
+    #[error("checkpoint {received} does not follow verified checkpoint {latest}")]
+    StaleCheckpoint { latest: u64, received: u64 },
+    #[error("inclusion proof for object {0} does not match the checkpoint root")]
+    InvalidProof(String),
+}
+
+/// A new version of an object, with its proof of inclusion in a checkpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectUpdate {
+    pub object: SuiObject,
+    pub proof: MerkleProof,
+}
+
+/// Follows certified checkpoints and keeps a verified copy of the objects it
+/// subscribes to, without replaying transactions.
+pub struct LightClient {
+    committee: Committee,
+    latest_sequence: Option<u64>,
+    tracked: HashMap<ObjectID, Option<SuiObject>>,
+    hasher: Arc<dyn Hasher>,
+}
+
+impl LightClient {
+    pub fn new(committee: Committee) -> Self {
+        Self {
+            committee,
+            latest_sequence: None,
+            tracked: HashMap::new(),
+            hasher: default_hasher(),
+        }
+    }
+
+    /// Verifies proofs with `hasher`; it must match the validators' hasher.
+    pub fn with_hasher(mut self, hasher: Arc<dyn Hasher>) -> Self {
+        self.hasher = hasher;
+        self
+    }
+
+    /// Starts tracking `id`. Its value is unknown until a checkpoint proves one.
+    pub fn subscribe(&mut self, id: ObjectID) {
+        self.tracked.entry(id).or_insert(None);
+    }
+
+    pub fn latest_sequence(&self) -> Option<u64> {
+        self.latest_sequence
+    }
+
+    /// The last verified version of a tracked object.
+    pub fn object(&self, id: &ObjectID) -> Option<&SuiObject> {
+        self.tracked.get(id).and_then(Option::as_ref)
+    }
+
+    /// Verifies `checkpoint` and every proof in `updates` before applying any
+    /// of them. Updates for objects the client does not track are checked but
+    /// otherwise ignored.
+    pub fn apply_checkpoint(
+        &mut self,
+        checkpoint: &CertifiedCheckpoint,
+        updates: &[ObjectUpdate],
+    ) -> Result<(), LightClientError> {
+        let sequence = checkpoint.summary.sequence_number;
+        if let Some(latest) = self.latest_sequence {
+            if sequence <= latest {
+                return Err(LightClientError::StaleCheckpoint { latest, received: sequence });
+            }
+        }
+        if !checkpoint.verify(&self.committee) {
+            return Err(LightClientError::InvalidCertificate(sequence));
+        }
+
+        let root = &checkpoint.summary.objects_root;
+        for update in updates {
+            if !update
+                .proof
+                .verify_with(self.hasher.as_ref(), &object_leaf(&update.object), root)
+            {
+                return Err(LightClientError::InvalidProof(update.object.id.0.clone()));
+            }
+        }
+
+        for update in updates {
+            if let Some(slot) = self.tracked.get_mut(&update.object.id) {
+                *slot = Some(update.object.clone());
+            }
+        }
+        self.latest_sequence = Some(sequence);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{objects_root, MerkleTree};
+    use sui_core::committee::CommitteeMember;
+    use sui_core::crypto::KeyPair;
+    use sui_core::messages::CheckpointSummary;
+    use sui_core::object::{ObjectData, Owner};
+
+    fn coin(id: &str, balance: u64) -> SuiObject {
+        SuiObject::new(ObjectID::new(id), Owner::Address("alice".to_string()), ObjectData::Coin { balance })
+    }
+
+    fn validators() -> Vec<(String, KeyPair)> {
+        (1..=3u8)
+            .map(|i| (format!("v{}", i), KeyPair::from_seed([i; 32])))
+            .collect()
+    }
+
+    fn committee(validators: &[(String, KeyPair)]) -> Committee {
+        let members = validators
+            .iter()
+            .map(|(name, keypair)| CommitteeMember {
+                name: name.clone(),
+                public_key: keypair.public_key(),
+                endpoint: String::new(),
+            })
+            .collect();
+        Committee::new(0, members)
+    }
+
+    /// A checkpoint over `objects`, signed by the first `signers` validators,
+    /// with an inclusion proof for each object.
+    fn checkpoint(
+        sequence: u64,
+        objects: &[SuiObject],
+        validators: &[(String, KeyPair)],
+        signers: usize,
+    ) -> (CertifiedCheckpoint, Vec<ObjectUpdate>) {
+        let mut summary = CheckpointSummary::new(sequence, format!("root-{}", sequence));
+        summary.objects_root = objects_root(objects);
+        let certified = validators[..signers]
+            .iter()
+            .fold(CertifiedCheckpoint::new(summary), |certified, (name, keypair)| {
+                certified.sign(name.clone(), keypair)
+            });
+
+        let leaves: Vec<Vec<u8>> = objects.iter().map(object_leaf).collect();
+        let tree = MerkleTree::new(&leaves);
+        let updates = objects
+            .iter()
+            .enumerate()
+            .map(|(index, object)| ObjectUpdate { object: object.clone(), proof: tree.proof(index).unwrap() })
+            .collect();
+        (certified, updates)
+    }
+
+    #[test]
+    fn tracked_object_follows_valid_checkpoints() {
+        let validators = validators();
+        let mut client = LightClient::new(committee(&validators));
+        client.subscribe(ObjectID::new("coin-a"));
+
+        let (first, updates) = checkpoint(1, &[coin("coin-a", 10), coin("coin-b", 5)], &validators, 3);
+        client.apply_checkpoint(&first, &updates).unwrap();
+        assert!(matches!(client.object(&ObjectID::new("coin-a")).unwrap().data, ObjectData::Coin { balance: 10 }));
+
+        let (second, updates) = checkpoint(2, &[coin("coin-c", 1), coin("coin-a", 7), coin("coin-d", 2)], &validators, 3);
+        client.apply_checkpoint(&second, &updates).unwrap();
+        assert!(matches!(client.object(&ObjectID::new("coin-a")).unwrap().data, ObjectData::Coin { balance: 7 }));
+        assert!(client.object(&ObjectID::new("coin-b")).is_none());
+        assert_eq!(client.latest_sequence(), Some(2));
+    }
+
+    #[test]
+    fn forged_proof_is_rejected() {
+        let validators = validators();
+        let mut client = LightClient::new(committee(&validators));
+        client.subscribe(ObjectID::new("coin-a"));
+        let (certified, mut updates) = checkpoint(1, &[coin("coin-a", 10), coin("coin-b", 5)], &validators, 3);
+        updates[0].object = coin("coin-a", 1_000);
+
+        let result = client.apply_checkpoint(&certified, &updates);
+
+        assert!(matches!(result, Err(LightClientError::InvalidProof(id)) if id == "coin-a"));
+        assert!(client.object(&ObjectID::new("coin-a")).is_none());
+        assert_eq!(client.latest_sequence(), None);
+    }
+
+    #[test]
+    fn checkpoint_without_quorum_is_rejected() {
+        let validators = validators();
+        let mut client = LightClient::new(committee(&validators));
+        let (certified, updates) = checkpoint(1, &[coin("coin-a", 10)], &validators, 3);
+        assert!(client.apply_checkpoint(&certified, &updates).is_ok());
+
+        let (weak, updates) = checkpoint(2, &[coin("coin-a", 9)], &validators, 2);
+        assert!(matches!(
+            client.apply_checkpoint(&weak, &updates),
+            Err(LightClientError::InvalidCertificate(2))
+        ));
+
+        let (replayed, updates) = checkpoint(1, &[coin("coin-a", 10)], &validators, 3);
+        assert!(matches!(
+            client.apply_checkpoint(&replayed, &updates),
+            Err(LightClientError::StaleCheckpoint { latest: 1, received: 1 })
+        ));
+    }
+}
@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use sui_core::{
+    hash::{Hasher, Sha256},
+    object::SuiObject,
+};
+
+fn leaf_hash(hasher: &dyn Hasher, leaf: &[u8]) -> String {
+    let mut bytes = vec![0u8];
+    bytes.extend_from_slice(leaf);
+    hasher.hash(&bytes)
+}
+
+fn node_hash(hasher: &dyn Hasher, left: &str, right: &str) -> String {
+    let mut bytes = vec![1u8];
+    bytes.extend_from_slice(left.as_bytes());
+    bytes.extend_from_slice(right.as_bytes());
+    hasher.hash(&bytes)
+}
+
+/// Binary Merkle tree, SHA-256 unless built with another [`Hasher`]. Leaves
+/// and inner nodes are domain separated, and an odd node at any level is
+/// carried up to the next level unchanged, so that no two leaf counts share
+/// a root.
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    levels: Vec<Vec<String>>,
+    empty_root: String,
+}
+
+impl MerkleTree {
+    pub fn new<T: AsRef<[u8]>>(leaves: &[T]) -> Self {
+        Self::with_hasher(&Sha256, leaves)
+    }
+
+    pub fn with_hasher<T: AsRef<[u8]>>(hasher: &dyn Hasher, leaves: &[T]) -> Self {
+        let mut levels = vec![leaves
+            .iter()
+            .map(|l| leaf_hash(hasher, l.as_ref()))
+            .collect::<Vec<_>>()];
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(hasher, left, right),
+                    [odd] => odd.clone(),
+                    _ => unreachable!("chunks of two"),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self {
+            levels,
+            empty_root: hasher.hash(&[]),
+        }
+    }
+
+    /// The root hash; empty trees have the hash of no bytes.
+    pub fn root(&self) -> String {
+        self.levels
+            .last()
+            .and_then(|level| level.first())
+            .cloned()
+            .unwrap_or_else(|| self.empty_root.clone())
+    }
+
+    pub fn proof(&self, index: usize) -> Option<MerkleProof> {
+        if index >= self.levels[0].len() {
+            return None;
+        }
+        let mut siblings = Vec::new();
+        let mut position = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            siblings.push(level.get(position ^ 1).cloned());
+            position /= 2;
+        }
+        Some(MerkleProof {
+            leaf_index: index,
+            siblings,
+        })
+    }
+}
+
+/// Path from a leaf to the root of a [`MerkleTree`]. A `None` sibling marks
+/// a level where the node was carried up unpaired.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    pub leaf_index: usize,
+    pub siblings: Vec<Option<String>>,
+}
+
+impl MerkleProof {
+    pub fn verify(&self, leaf: &[u8], root: &str) -> bool {
+        self.verify_with(&Sha256, leaf, root)
+    }
+
+    /// Verifies against a tree built with `hasher`. Only the last node of a
+    /// level, which is always a left child, can be carried up unpaired, and
+    /// the path must end at the root's position.
+    pub fn verify_with(&self, hasher: &dyn Hasher, leaf: &[u8], root: &str) -> bool {
+        let mut hash = leaf_hash(hasher, leaf);
+        let mut position = self.leaf_index;
+        for sibling in &self.siblings {
+            let is_left = position.is_multiple_of(2);
+            hash = match sibling {
+                Some(sibling) if is_left => node_hash(hasher, &hash, sibling),
+                Some(sibling) => node_hash(hasher, sibling, &hash),
+                None if is_left => hash,
+                None => return false,
+            };
+            position /= 2;
+        }
+        position == 0 && hash == root
+    }
+}
+
+/// Canonical leaf encoding of an object in a checkpoint's `objects_root`.
+pub fn object_leaf(object: &SuiObject) -> Vec<u8> {
+    serde_json::to_vec(object).expect("objects always serialize")
+}
+
+/// SHA-256 Merkle root over `objects` in the given order.
+pub fn objects_root(objects: &[SuiObject]) -> String {
+    objects_root_with(&Sha256, objects)
+}
+
+pub fn objects_root_with(hasher: &dyn Hasher, objects: &[SuiObject]) -> String {
+    let leaves: Vec<Vec<u8>> = objects.iter().map(object_leaf).collect();
+    MerkleTree::with_hasher(hasher, &leaves).root()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_leaf_proves_against_the_root() {
+        for count in 1..=7 {
+            let leaves: Vec<String> = (0..count).map(|i| format!("leaf-{}", i)).collect();
+            let tree = MerkleTree::new(&leaves);
+            let root = tree.root();
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = tree.proof(index).unwrap();
+                assert!(proof.verify(leaf.as_bytes(), &root), "leaf {} of {}", index, count);
+                assert!(!proof.verify(b"forged", &root));
+            }
+            assert!(tree.proof(count).is_none());
+        }
+    }
+
+    #[test]
+    fn duplicating_the_odd_leaf_changes_the_root() {
+        let three = MerkleTree::new(&["a", "b", "c"]);
+        let four = MerkleTree::new(&["a", "b", "c", "c"]);
+
+        assert_ne!(three.root(), four.root());
+        let phantom = four.proof(3).unwrap();
+        assert!(!phantom.verify(b"c", &three.root()));
+    }
+
+    #[test]
+    fn proofs_do_not_verify_at_another_index() {
+        let tree = MerkleTree::new(&["a", "b", "c"]);
+        let root = tree.root();
+        let proof = tree.proof(2).unwrap();
+
+        for leaf_index in [3, 6] {
+            let moved = MerkleProof { leaf_index, ..proof.clone() };
+            assert!(!moved.verify(b"c", &root), "index {}", leaf_index);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::crypto::verify_signature;
+
+/// Signatures by several validators over one message, combined into one
+/// hex-encoded value by a [`SignatureAggregator`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggregateSignature(pub String);
+
+/// Which committee members contributed to an aggregate, by their index in
+/// `Committee::members`.
+///
+/// Bitmaps arrive from peers inside certificates, so deserializing one
+/// rejects a `len` that does not match the bits it carries.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawSignerBitmap")]
+pub struct SignerBitmap {
+    bits: Vec<u8>,
+    len: usize,
+}
+
+/// A [`SignerBitmap`] as received, before its length is checked.
+#[derive(Deserialize)]
+struct RawSignerBitmap {
+    bits: Vec<u8>,
+    len: usize,
+}
+
+impl TryFrom<RawSignerBitmap> for SignerBitmap {
+    type Error = String;
+
+    fn try_from(raw: RawSignerBitmap) -> Result<Self, Self::Error> {
+        if raw.bits.len() != raw.len.div_ceil(8) {
+            return Err(format!(
+                "signer bitmap of {} members needs {} bytes, got {}",
+                raw.len,
+                raw.len.div_ceil(8),
+                raw.bits.len()
+            ));
+        }
+        Ok(Self { bits: raw.bits, len: raw.len })
+    }
+}
+
+impl SignerBitmap {
+    /// An empty bitmap over a committee of `len` members.
+    pub fn new(len: usize) -> Self {
+        Self {
+            bits: vec![0; len.div_ceil(8)],
+            len,
+        }
+    }
+
+    /// Marks member `index`. Returns false if it is out of range.
+    pub fn set(&mut self, index: usize) -> bool {
+        if index >= self.len {
+            return false;
+        }
+        self.bits[index / 8] |= 1 << (index % 8);
+        true
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        index < self.len && self.bits[index / 8] & (1 << (index % 8)) != 0
+    }
+
+    /// Marked indices, ascending.
+    pub fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len).filter(|&index| self.contains(index))
+    }
+
+    pub fn count(&self) -> usize {
+        self.indices().count()
+    }
+
+    /// Size of the committee the bitmap was made for.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AggregationError {
+    #[error("no signatures to aggregate")]
+    Empty,
+    #[error("signature {0} is malformed")]
+    Malformed(usize),
+}
+
+/// Combines signatures over a single message so that a certificate carries
+/// one signature however many validators signed.
+///
+/// Signatures are aggregated, and public keys passed for verification, in
+/// the same order: ascending committee index.
+pub trait SignatureAggregator: Send + Sync {
+    fn aggregate(&self, signatures: &[String]) -> Result<AggregateSignature, AggregationError>;
+
+    /// Whether `aggregate` combines a valid signature over `message` by
+    /// each of `signers`, given as hex public keys.
+    fn verify_aggregate(&self, aggregate: &AggregateSignature, signers: &[&str], message: &[u8]) -> bool;
+}
+
+/// Length of a hex-encoded Ed25519 signature.
+const SIGNATURE_HEX_LEN: usize = 128;
+
+/// Stand-in for a real aggregation scheme such as BLS: the aggregate is the
+/// Ed25519 signatures concatenated in signer order. It grows with the
+/// number of signers, but verifies with the keys validators already have.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MockAggregator;
+
+impl SignatureAggregator for MockAggregator {
+    fn aggregate(&self, signatures: &[String]) -> Result<AggregateSignature, AggregationError> {
+        if signatures.is_empty() {
+            return Err(AggregationError::Empty);
+        }
+        if let Some(index) = signatures
+            .iter()
+            .position(|signature| signature.len() != SIGNATURE_HEX_LEN)
+        {
+            return Err(AggregationError::Malformed(index));
+        }
+        Ok(AggregateSignature(signatures.concat()))
+    }
+
+    fn verify_aggregate(&self, aggregate: &AggregateSignature, signers: &[&str], message: &[u8]) -> bool {
+        let aggregate = &aggregate.0;
+        if signers.is_empty() || !aggregate.is_ascii() || aggregate.len() != signers.len() * SIGNATURE_HEX_LEN {
+            return false;
+        }
+        signers.iter().enumerate().all(|(index, public_key)| {
+            let signature = &aggregate[index * SIGNATURE_HEX_LEN..(index + 1) * SIGNATURE_HEX_LEN];
+            verify_signature(public_key, message, signature)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::KeyPair;
+
+    #[test]
+    fn bitmap_round_trips_through_serde() {
+        let mut bitmap = SignerBitmap::new(10);
+        bitmap.set(0);
+        bitmap.set(9);
+
+        let decoded: SignerBitmap = serde_json::from_str(&serde_json::to_string(&bitmap).unwrap()).unwrap();
+
+        assert_eq!(decoded, bitmap);
+        assert_eq!(decoded.indices().collect::<Vec<_>>(), vec![0, 9]);
+    }
+
+    #[test]
+    fn bitmap_longer_than_its_bits_is_rejected() {
+        for json in [r#"{"bits":[1],"len":64}"#, r#"{"bits":[],"len":1}"#, r#"{"bits":[0,0],"len":8}"#] {
+            assert!(serde_json::from_str::<SignerBitmap>(json).is_err(), "{json}");
+        }
+    }
+
+    #[test]
+    fn out_of_range_indices_are_not_set() {
+        let mut bitmap = SignerBitmap::new(4);
+
+        assert!(!bitmap.set(4));
+        assert!(!bitmap.contains(4));
+        assert_eq!(bitmap.count(), 0);
+    }
+
+    fn signed(message: &[u8]) -> (Vec<KeyPair>, Vec<String>) {
+        let keypairs: Vec<KeyPair> = (1..=3u8).map(|seed| KeyPair::from_seed([seed; 32])).collect();
+        let signatures = keypairs.iter().map(|keypair| keypair.sign(message)).collect();
+        (keypairs, signatures)
+    }
+
+    #[test]
+    fn mock_aggregate_verifies_against_its_signers_in_order() {
+        let (keypairs, signatures) = signed(b"checkpoint");
+        let keys: Vec<String> = keypairs.iter().map(KeyPair::public_key).collect();
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+
+        let aggregate = MockAggregator.aggregate(&signatures).unwrap();
+
+        assert!(MockAggregator.verify_aggregate(&aggregate, &keys, b"checkpoint"));
+        assert!(!MockAggregator.verify_aggregate(&aggregate, &keys, b"other"));
+        assert!(!MockAggregator.verify_aggregate(&aggregate, &[keys[1], keys[0], keys[2]], b"checkpoint"));
+        assert!(!MockAggregator.verify_aggregate(&aggregate, &keys[..2], b"checkpoint"));
+    }
+
+    #[test]
+    fn empty_or_malformed_signatures_do_not_aggregate() {
+        let (_, mut signatures) = signed(b"checkpoint");
+        signatures[1].pop();
+
+        assert_eq!(MockAggregator.aggregate(&[]), Err(AggregationError::Empty));
+        assert_eq!(MockAggregator.aggregate(&signatures), Err(AggregationError::Malformed(1)));
+    }
+}
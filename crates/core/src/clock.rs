@@ -0,0 +1,56 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of time for anything that expires, times out, or is timestamped.
+/// Readings are milliseconds since the Unix epoch.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> u64;
+}
+
+/// Whether `deadline` has passed at `now`, forgiving up to `tolerance_ms` of
+/// clock skew between whoever set the deadline and the local clock.
+pub fn is_past(now: u64, deadline: u64, tolerance_ms: u64) -> bool {
+    now > deadline.saturating_add(tolerance_ms)
+}
+
+/// Reads the host's wall clock.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+/// Clock that only moves when told to, so time-based behaviour can be
+/// exercised deterministically.
+#[derive(Debug, Default)]
+pub struct MockClock {
+    now: AtomicU64,
+}
+
+impl MockClock {
+    pub fn new(start_millis: u64) -> Self {
+        Self {
+            now: AtomicU64::new(start_millis),
+        }
+    }
+
+    pub fn advance(&self, millis: u64) {
+        self.now.fetch_add(millis, Ordering::SeqCst);
+    }
+
+    pub fn set(&self, millis: u64) {
+        self.now.store(millis, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitteeMember {
+    pub name: String,
+    pub public_key: String,
+    pub endpoint: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RegistryError {
+    #[error("a validator named {0} is already registered")]
+    DuplicateName(String),
+}
+
+/// Committee members by name. Names are unique, so a name always resolves
+/// to one public key and endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidatorRegistry {
+    members: BTreeMap<String, CommitteeMember>,
+}
+
+impl ValidatorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a registry from `members`, failing on the first repeated name.
+    pub fn from_members<'a>(members: impl IntoIterator<Item = &'a CommitteeMember>) -> Result<Self, RegistryError> {
+        let mut registry = Self::new();
+        for member in members {
+            registry.register(member.clone())?;
+        }
+        Ok(registry)
+    }
+
+    /// Adds `member`, rejecting it if its name is taken.
+    pub fn register(&mut self, member: CommitteeMember) -> Result<(), RegistryError> {
+        if self.members.contains_key(&member.name) {
+            return Err(RegistryError::DuplicateName(member.name));
+        }
+        self.members.insert(member.name.clone(), member);
+        Ok(())
+    }
+
+    pub fn resolve(&self, name: &str) -> Option<&CommitteeMember> {
+        self.members.get(name)
+    }
+
+    pub fn public_key(&self, name: &str) -> Option<&str> {
+        self.resolve(name).map(|member| member.public_key.as_str())
+    }
+
+    pub fn endpoint(&self, name: &str) -> Option<&str> {
+        self.resolve(name).map(|member| member.endpoint.as_str())
+    }
+
+    /// Members ordered by name.
+    pub fn members(&self) -> impl Iterator<Item = &CommitteeMember> {
+        self.members.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+/// The validator set for an epoch.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Committee {
+    pub epoch: u64,
+    pub members: Vec<CommitteeMember>,
+}
+
+impl Committee {
+    pub fn new(epoch: u64, members: Vec<CommitteeMember>) -> Self {
+        Self { epoch, members }
+    }
+
+    /// Like [`Committee::new`], but rejects members sharing a name.
+    pub fn try_new(epoch: u64, members: Vec<CommitteeMember>) -> Result<Self, RegistryError> {
+        ValidatorRegistry::from_members(&members)?;
+        Ok(Self::new(epoch, members))
+    }
+
+    /// Adds a validator to the committee unless its name is already taken.
+    pub fn join(&mut self, member: CommitteeMember) -> Result<(), RegistryError> {
+        if self.member(&member.name).is_some() {
+            return Err(RegistryError::DuplicateName(member.name));
+        }
+        self.members.push(member);
+        Ok(())
+    }
+
+    /// The members by name, or the first name used twice.
+    pub fn registry(&self) -> Result<ValidatorRegistry, RegistryError> {
+        ValidatorRegistry::from_members(&self.members)
+    }
+
+    pub fn member(&self, name: &str) -> Option<&CommitteeMember> {
+        self.members.iter().find(|m| m.name == name)
+    }
+
+    pub fn size(&self) -> usize {
+        self.members.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(name: &str, public_key: &str) -> CommitteeMember {
+        CommitteeMember {
+            name: name.to_string(),
+            public_key: public_key.to_string(),
+            endpoint: format!("http://{}.local", name),
+        }
+    }
+
+    #[test]
+    fn registering_a_taken_name_is_rejected() {
+        let mut registry = ValidatorRegistry::new();
+        registry.register(member("v1", "key-1")).unwrap();
+
+        let duplicate = registry.register(member("v1", "key-2"));
+
+        assert_eq!(duplicate, Err(RegistryError::DuplicateName("v1".to_string())));
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.public_key("v1"), Some("key-1"));
+    }
+
+    #[test]
+    fn names_resolve_to_their_key_and_endpoint() {
+        let registry = ValidatorRegistry::from_members(&[member("v2", "key-2"), member("v1", "key-1")]).unwrap();
+
+        assert_eq!(registry.public_key("v2"), Some("key-2"));
+        assert_eq!(registry.endpoint("v1"), Some("http://v1.local"));
+        assert_eq!(registry.resolve("v3"), None);
+        let names: Vec<&str> = registry.members().map(|member| member.name.as_str()).collect();
+        assert_eq!(names, vec!["v1", "v2"]);
+    }
+
+    #[test]
+    fn committees_refuse_duplicate_names() {
+        let members = vec![member("v1", "key-1"), member("v1", "key-2")];
+        assert!(Committee::try_new(0, members.clone()).is_err());
+        assert!(Committee::new(0, members).registry().is_err());
+
+        let mut committee = Committee::try_new(0, vec![member("v1", "key-1")]).unwrap();
+        assert!(committee.join(member("v1", "key-2")).is_err());
+        committee.join(member("v2", "key-2")).unwrap();
+        assert_eq!(committee.registry().unwrap().public_key("v2"), Some("key-2"));
+    }
+}
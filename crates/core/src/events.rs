@@ -0,0 +1,12 @@
+use crate::object::{ObjectID, Owner};
+use serde::{Deserialize, Serialize};
+
+/// Structured events emitted during execution, intended for indexers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ObjectEvent {
+    OwnershipChanged {
+        object: ObjectID,
+        from: Owner,
+        to: Owner,
+    },
+}
@@ -0,0 +1,91 @@
+use sha2::Digest;
+use std::sync::Arc;
+
+use crate::object::SuiObject;
+use crate::transaction::{SignedTransaction, TransactionDigest};
+
+/// Hash function used for digests and Merkle roots. Outputs are hex strings.
+pub trait Hasher: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn hash(&self, bytes: &[u8]) -> String;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sha256;
+
+impl Hasher for Sha256 {
+    fn name(&self) -> &'static str {
+        "sha256"
+    }
+
+    fn hash(&self, bytes: &[u8]) -> String {
+        hex::encode(sha2::Sha256::digest(bytes))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Blake2b;
+
+impl Hasher for Blake2b {
+    fn name(&self) -> &'static str {
+        "blake2b"
+    }
+
+    fn hash(&self, bytes: &[u8]) -> String {
+        hex::encode(blake2::Blake2b512::digest(bytes))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Blake3;
+
+impl Hasher for Blake3 {
+    fn name(&self) -> &'static str {
+        "blake3"
+    }
+
+    fn hash(&self, bytes: &[u8]) -> String {
+        blake3::hash(bytes).to_hex().to_string()
+    }
+}
+
+/// The hasher used wherever none is configured.
+pub fn default_hasher() -> Arc<dyn Hasher> {
+    Arc::new(Sha256)
+}
+
+/// Content digest of an object's canonical JSON encoding.
+pub fn object_digest(hasher: &dyn Hasher, object: &SuiObject) -> String {
+    hasher.hash(&serde_json::to_vec(object).expect("objects always serialize"))
+}
+
+/// Digest of a signed transaction's canonical JSON encoding.
+pub fn transaction_digest(hasher: &dyn Hasher, tx: &SignedTransaction) -> TransactionDigest {
+    let bytes = serde_json::to_vec(tx).expect("transactions always serialize");
+    TransactionDigest(format!("tx-{}", hasher.hash(&bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashers_produce_distinct_stable_digests() {
+        let hashers: [&dyn Hasher; 3] = [&Sha256, &Blake2b, &Blake3];
+        let digests: Vec<String> = hashers.iter().map(|hasher| hasher.hash(b"abc")).collect();
+
+        assert_eq!(digests[0], "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(digests[2], "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85");
+        assert_eq!(digests[1].len(), 128);
+        assert_ne!(digests[0], digests[1]);
+        assert_ne!(digests[1], digests[2]);
+        for (hasher, digest) in hashers.iter().zip(&digests) {
+            assert_eq!(&hasher.hash(b"abc"), digest, "{} is not stable", hasher.name());
+        }
+    }
+
+    #[test]
+    fn default_hasher_is_sha256() {
+        assert_eq!(default_hasher().name(), "sha256");
+    }
+}
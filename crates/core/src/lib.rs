@@ -2,13 +2,30 @@
 //! The goal is to provide strongly typed building blocks that other crates
 //! can compose when simulating validator and Nautilus behaviour.
 
+pub mod aggregate;
+pub mod clock;
+pub mod committee;
+pub mod crypto;
+pub mod events;
+pub mod hash;
 pub mod object;
 pub mod transaction;
 pub mod messages;
 
+pub use aggregate::{AggregateSignature, AggregationError, MockAggregator, SignatureAggregator, SignerBitmap};
+pub use clock::{Clock, MockClock, SystemClock};
+pub use committee::{Committee, CommitteeMember, RegistryError, ValidatorRegistry};
+pub use crypto::KeyPair;
+pub use events::ObjectEvent;
+pub use hash::Hasher;
 pub use object::{ObjectData, ObjectID, Owner, SuiObject};
-pub use transaction::{GasObject, SignedTransaction, TransactionDigest, TransactionKind, TransactionPayload};
-pub use messages::{ConsensusMessage, CheckpointSummary, ExecutionRequest};
+pub use transaction::{
+    Argument, GasObject, InputObject, SignedTransaction, StateCondition, TransactionDigest, TransactionKind,
+    TransactionPayload,
+};
+pub use messages::{
+    verify_receipt, BatchResult, CheckpointSummary, CommandResult, CommandStatus, ConsensusMessage, ExecutionRequest, ExecutionStatus, TransactionReceipt,
+};
 
 /// Helper used by examples and tests to fabricate a signed transaction without
 /// implementing full cryptography.
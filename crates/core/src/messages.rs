@@ -1,4 +1,6 @@
-use crate::transaction::{SignedTransaction, TransactionDigest};
+use crate::crypto::{verify_signature, KeyPair};
+use crate::hash::{Hasher, Sha256};
+use crate::transaction::{SignedTransaction, TransactionDigest, TransactionKind};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,6 +9,191 @@ pub struct ExecutionRequest {
     pub digest: TransactionDigest,
 }
 
+/// Weights of [`ExecutionRequest::complexity_score`].
+pub mod complexity {
+    /// Serialized command bytes worth one point.
+    pub const BYTES_PER_POINT: u64 = 64;
+    pub const POINTS_PER_COMMAND: u64 = 10;
+    pub const POINTS_PER_INPUT: u64 = 5;
+    /// Gas budget worth one point.
+    pub const GAS_PER_POINT: u64 = 1_000;
+}
+
+impl ExecutionRequest {
+    /// Rough cost of admitting the transaction, for prioritizing and
+    /// rejecting work before it runs. Combines the serialized size of the
+    /// command and its arguments, the number of commands (counting each
+    /// batched or conditional one), the declared inputs and the gas budget,
+    /// weighted by the constants in [`complexity`]. A plain transfer scores
+    /// in the teens.
+    pub fn complexity_score(&self) -> u64 {
+        fn commands(kind: &TransactionKind) -> u64 {
+            match kind {
+                TransactionKind::Batch { commands: batched } => batched.iter().map(commands).sum(),
+                TransactionKind::Conditional { then, .. } => 1 + commands(then),
+                _ => 1,
+            }
+        }
+
+        let payload = &self.tx.payload;
+        let bytes = serde_json::to_vec(&payload.kind).map_or(0, |bytes| bytes.len() as u64);
+        (bytes / complexity::BYTES_PER_POINT)
+            .saturating_add(commands(&payload.kind).saturating_mul(complexity::POINTS_PER_COMMAND))
+            .saturating_add((payload.input_objects.len() as u64).saturating_mul(complexity::POINTS_PER_INPUT))
+            .saturating_add(payload.gas_budget / complexity::GAS_PER_POINT)
+    }
+}
+
+/// Outcome of executing a transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecutionStatus {
+    #[default]
+    Success,
+    Failure { code: u64, message: String },
+}
+
+impl ExecutionStatus {
+    pub fn is_success(&self) -> bool {
+        matches!(self, ExecutionStatus::Success)
+    }
+}
+
+/// Outcome class used to filter the transaction log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxLogStatus {
+    Success,
+    Failure,
+}
+
+/// One transaction a validator processed, as recorded in its log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxLogEntry {
+    pub digest: TransactionDigest,
+    pub signer: String,
+    pub status: ExecutionStatus,
+    /// Checkpoint the transaction was committed in.
+    pub sequence: u64,
+    pub timestamp_ms: u64,
+}
+
+/// Criteria for querying the transaction log; unset fields match anything.
+/// The time range is inclusive at both ends.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxLogFilter {
+    #[serde(default)]
+    pub signer: Option<String>,
+    #[serde(default)]
+    pub from_ms: Option<u64>,
+    #[serde(default)]
+    pub to_ms: Option<u64>,
+    #[serde(default)]
+    pub status: Option<TxLogStatus>,
+}
+
+impl TxLogFilter {
+    pub fn matches(&self, entry: &TxLogEntry) -> bool {
+        let status = if entry.status.is_success() {
+            TxLogStatus::Success
+        } else {
+            TxLogStatus::Failure
+        };
+        if self.signer.as_ref().is_some_and(|signer| signer != &entry.signer) {
+            return false;
+        }
+        if self.from_ms.is_some_and(|from| entry.timestamp_ms < from) {
+            return false;
+        }
+        if self.to_ms.is_some_and(|to| entry.timestamp_ms > to) {
+            return false;
+        }
+        !self.status.is_some_and(|wanted| wanted != status)
+    }
+}
+
+/// What happened to one command of a batch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CommandStatus {
+    Success,
+    /// This command aborted, failing the batch.
+    Aborted { code: u64, message: String },
+    /// Undone because another command aborted.
+    RolledBack,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandResult {
+    pub index: usize,
+    /// Whether the command ran at all; commands after an abort do not.
+    pub executed: bool,
+    pub status: CommandStatus,
+}
+
+/// Per-command outcome of a `Batch` transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub commands: Vec<CommandResult>,
+}
+
+impl BatchResult {
+    /// The command that aborted the batch, if any.
+    pub fn failed_command(&self) -> Option<&CommandResult> {
+        self.commands
+            .iter()
+            .find(|command| matches!(command.status, CommandStatus::Aborted { .. }))
+    }
+}
+
+/// A validator's signed acknowledgement that it committed a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionReceipt {
+    pub digest: TransactionDigest,
+    pub validator: String,
+    pub checkpoint_sequence: u64,
+    pub success: bool,
+    pub timestamp_ms: u64,
+    pub validator_signature: String,
+}
+
+impl TransactionReceipt {
+    pub fn new_signed(
+        digest: TransactionDigest,
+        validator: impl Into<String>,
+        checkpoint_sequence: u64,
+        success: bool,
+        timestamp_ms: u64,
+        keypair: &KeyPair,
+    ) -> Self {
+        let mut receipt = Self {
+            digest,
+            validator: validator.into(),
+            checkpoint_sequence,
+            success,
+            timestamp_ms,
+            validator_signature: String::new(),
+        };
+        receipt.validator_signature = keypair.sign(&receipt.canonical_bytes());
+        receipt
+    }
+
+    /// The bytes covered by the signature: every field except the signature.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(&(
+            &self.digest.0,
+            &self.validator,
+            self.checkpoint_sequence,
+            self.success,
+            self.timestamp_ms,
+        ))
+        .expect("receipt fields always serialize")
+    }
+}
+
+/// Checks that `receipt` was signed by the holder of `public_key`.
+pub fn verify_receipt(receipt: &TransactionReceipt, public_key: &str) -> bool {
+    verify_signature(public_key, &receipt.canonical_bytes(), &receipt.validator_signature)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ConsensusMessage {
     SubmitTransaction(ExecutionRequest),
@@ -19,5 +206,186 @@ pub struct CheckpointSummary {
     pub sequence_number: u64,
     pub transaction_count: usize,
     pub root_digest: String,
+    #[serde(default)]
+    pub total_gas_used: u64,
+    #[serde(default)]
+    pub objects_created: usize,
+    #[serde(default)]
+    pub objects_mutated: usize,
+    #[serde(default)]
+    pub objects_deleted: usize,
+    #[serde(default)]
+    pub timestamp_ms: u64,
+    /// Merkle root over the objects written in this checkpoint, against
+    /// which light clients check inclusion proofs.
+    #[serde(default)]
+    pub objects_root: String,
+    /// Digest of the preceding checkpoint, chaining checkpoints together.
+    #[serde(default)]
+    pub previous_digest: Option<String>,
+    /// Consensus position of the transaction, when a consensus engine
+    /// ordered it. Omitted otherwise so existing digests are unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consensus_position: Option<u64>,
 }
 
+impl CheckpointSummary {
+    /// Starts an empty checkpoint that transactions are folded into via
+    /// [`CheckpointSummary::record_transaction`].
+    pub fn new(sequence_number: u64, root_digest: impl Into<String>) -> Self {
+        Self {
+            sequence_number,
+            transaction_count: 0,
+            root_digest: root_digest.into(),
+            total_gas_used: 0,
+            objects_created: 0,
+            objects_mutated: 0,
+            objects_deleted: 0,
+            timestamp_ms: 0,
+            objects_root: String::new(),
+            previous_digest: None,
+            consensus_position: None,
+        }
+    }
+
+    /// Accumulates the gas and object counts of one executed transaction.
+    pub fn record_transaction(&mut self, gas_used: u64, created: usize, mutated: usize, deleted: usize) {
+        self.transaction_count += 1;
+        self.total_gas_used += gas_used;
+        self.objects_created += created;
+        self.objects_mutated += mutated;
+        self.objects_deleted += deleted;
+    }
+
+    /// The bytes validators sign when certifying this checkpoint.
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("checkpoint summaries always serialize")
+    }
+
+    /// Content digest under sha256, referenced by the next checkpoint's
+    /// `previous_digest`.
+    pub fn digest(&self) -> String {
+        self.digest_with(&Sha256)
+    }
+
+    /// Like [`digest`](Self::digest), under the validators' configured hasher.
+    pub fn digest_with(&self, hasher: &dyn Hasher) -> String {
+        hasher.hash(&self.signing_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::ObjectID;
+    use crate::transaction::{InputObject, TransactionPayload};
+
+    #[test]
+    fn checkpoint_sums_gas_and_object_counts() {
+        let mut checkpoint = CheckpointSummary::new(1, "root");
+        checkpoint.record_transaction(300, 1, 2, 0);
+        checkpoint.record_transaction(500, 2, 1, 1);
+
+        assert_eq!(checkpoint.transaction_count, 2);
+        assert_eq!(checkpoint.total_gas_used, 800);
+        assert_eq!(checkpoint.objects_created, 3);
+        assert_eq!(checkpoint.objects_mutated, 3);
+        assert_eq!(checkpoint.objects_deleted, 1);
+    }
+
+    #[test]
+    fn checkpoint_without_counts_deserializes_with_defaults() {
+        let json = r#"{"sequence_number":4,"transaction_count":1,"root_digest":"root"}"#;
+        let checkpoint: CheckpointSummary = serde_json::from_str(json).unwrap();
+
+        assert_eq!(checkpoint.sequence_number, 4);
+        assert_eq!(checkpoint.total_gas_used, 0);
+        assert_eq!(checkpoint.objects_created, 0);
+        assert_eq!(checkpoint.objects_mutated, 0);
+        assert_eq!(checkpoint.objects_deleted, 0);
+    }
+
+    #[test]
+    fn checkpoint_digest_follows_the_hasher() {
+        let checkpoint = CheckpointSummary::new(1, "root");
+
+        assert_eq!(checkpoint.digest(), checkpoint.digest_with(&Sha256));
+        assert_eq!(
+            checkpoint.digest_with(&crate::hash::Blake3),
+            crate::hash::Blake3.hash(&checkpoint.signing_bytes())
+        );
+        assert_ne!(checkpoint.digest(), checkpoint.digest_with(&crate::hash::Blake3));
+    }
+
+    fn scored(kind: TransactionKind, inputs: usize) -> u64 {
+        let input_objects = (0..inputs)
+            .map(|index| InputObject { id: ObjectID::new(format!("coin-{}", index)), mutable: true, version: None })
+            .collect();
+        let payload = TransactionPayload {
+            kind,
+            gas_budget: 1_000,
+            input_objects,
+            sequence_number: 0,
+            gas_price: 0,
+            read_only: false,
+            gas_payment: None,
+        };
+        let request = ExecutionRequest {
+            tx: SignedTransaction::new("alice".to_string(), payload),
+            digest: TransactionDigest("tx-1".to_string()),
+        };
+        request.complexity_score()
+    }
+
+    fn transfer(index: usize) -> TransactionKind {
+        TransactionKind::Transfer {
+            object: ObjectID::new(format!("coin-{}", index)),
+            recipient: "bob".to_string(),
+        }
+    }
+
+    #[test]
+    fn simple_transfer_scores_low() {
+        let score = scored(transfer(0), 1);
+
+        assert!((10..20).contains(&score), "transfer scored {}", score);
+    }
+
+    #[test]
+    fn large_batch_scores_high() {
+        let batch = TransactionKind::Batch { commands: (0..32).map(transfer).collect() };
+
+        let score = scored(batch, 32);
+
+        assert!(score >= 32 * (complexity::POINTS_PER_COMMAND + complexity::POINTS_PER_INPUT), "batch scored {}", score);
+        assert!(score > 20 * scored(transfer(0), 1));
+    }
+
+    fn signed_receipt(keypair: &KeyPair) -> TransactionReceipt {
+        TransactionReceipt::new_signed(TransactionDigest("tx-1".to_string()), "v1", 7, true, 1_000, keypair)
+    }
+
+    #[test]
+    fn genuine_receipt_verifies() {
+        let keypair = KeyPair::from_seed([1; 32]);
+
+        assert!(verify_receipt(&signed_receipt(&keypair), &keypair.public_key()));
+    }
+
+    #[test]
+    fn tampered_receipt_fails_verification() {
+        let keypair = KeyPair::from_seed([1; 32]);
+        let mut receipt = signed_receipt(&keypair);
+        receipt.checkpoint_sequence = 8;
+
+        assert!(!verify_receipt(&receipt, &keypair.public_key()));
+    }
+
+    #[test]
+    fn receipt_from_another_key_fails_verification() {
+        let keypair = KeyPair::from_seed([1; 32]);
+        let other = KeyPair::from_seed([2; 32]);
+
+        assert!(!verify_receipt(&signed_receipt(&keypair), &other.public_key()));
+    }
+}
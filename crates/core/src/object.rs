@@ -1,4 +1,8 @@
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::hash::{Hasher, Sha256};
+use crate::transaction::TransactionDigest;
 
 /// Unique identifier for any on-chain object.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -13,16 +17,51 @@ impl ObjectID {
         use rand::Rng;
         Self(format!("obj-{:x}", rand::thread_rng().gen::<u128>()))
     }
+
+    /// Id of the `creation`-th object created by transaction `digest`.
+    /// Every validator executing the transaction derives the same ids.
+    pub fn derive(digest: &TransactionDigest, creation: u64) -> Self {
+        let hash = Sha256.hash(format!("{}:{}", digest.0, creation).as_bytes());
+        Self(format!("obj-{}", &hash[..32]))
+    }
 }
 
 /// Ownership model simplified from Sui.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Owner {
     Address(String),
+    /// Owned by another object, forming a parent/child relationship.
+    Object(ObjectID),
     Shared,
     Immutable,
 }
 
+/// Canonical form of an address for comparisons: trimmed, lowercased and
+/// without a `0x` prefix.
+pub fn normalize_address(addr: &str) -> String {
+    let addr = addr.trim().to_lowercase();
+    match addr.strip_prefix("0x") {
+        Some(stripped) => stripped.to_string(),
+        None => addr,
+    }
+}
+
+impl Owner {
+    pub fn as_address(&self) -> Option<&str> {
+        match self {
+            Owner::Address(addr) => Some(addr),
+            _ => None,
+        }
+    }
+
+    /// Whether this is an address owner equal to `addr` after normalization.
+    /// Shared, immutable and object owners never match.
+    pub fn matches_address(&self, addr: &str) -> bool {
+        self.as_address()
+            .is_some_and(|owner| normalize_address(owner) == normalize_address(addr))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ObjectData {
     Coin { balance: u64 },
@@ -30,17 +69,55 @@ pub enum ObjectData {
     MoveStruct { type_name: String, fields: serde_json::Value },
 }
 
+/// A broken invariant found by `SuiObject::validate`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ObjectValidationError {
+    #[error("object id is empty")]
+    EmptyId,
+    #[error("object {0} has version 0; versions start at 1")]
+    ZeroVersion(String),
+    #[error("object {0} has an empty owner")]
+    EmptyOwner(String),
+    #[error("object {0} owns itself")]
+    SelfOwned(String),
+    #[error("object {0} is a Move struct with no type name")]
+    MissingTypeName(String),
+    #[error("object {id} was shared at version {initial}, after its current version {version}")]
+    SharedVersionAhead { id: String, initial: u64, version: u64 },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SuiObject {
     pub id: ObjectID,
     pub version: u64,
     pub owner: Owner,
     pub data: ObjectData,
+    /// The object this one is wrapped inside, if any. A wrapped object can
+    /// only be unwrapped; every other use is rejected.
+    #[serde(default)]
+    pub wrapped_by: Option<ObjectID>,
+    /// Version at which the object became shared: its creation version if
+    /// it was created shared. Mutations of a shared object are sequenced
+    /// from here on.
+    #[serde(default)]
+    pub initial_shared_version: Option<u64>,
 }
 
 impl SuiObject {
     pub fn new(id: ObjectID, owner: Owner, data: ObjectData) -> Self {
-        Self { id, version: 1, owner, data }
+        let initial_shared_version = matches!(owner, Owner::Shared).then_some(1);
+        Self {
+            id,
+            version: 1,
+            owner,
+            data,
+            wrapped_by: None,
+            initial_shared_version,
+        }
+    }
+
+    pub fn is_wrapped(&self) -> bool {
+        self.wrapped_by.is_some()
     }
 
     pub fn lock_key(&self) -> String {
@@ -50,5 +127,98 @@ impl SuiObject {
     pub fn is_shared(&self) -> bool {
         matches!(self.owner, Owner::Shared)
     }
+
+    /// Mutations since the object became shared, or `None` if it never was.
+    pub fn shared_sequence(&self) -> Option<u64> {
+        self.initial_shared_version
+            .map(|initial| self.version.saturating_sub(initial))
+    }
+
+    /// Checks the invariants every stored object must hold. Stores run this
+    /// on objects they deserialize so corrupt entries surface as errors.
+    pub fn validate(&self) -> Result<(), ObjectValidationError> {
+        let id = &self.id.0;
+        if id.trim().is_empty() {
+            return Err(ObjectValidationError::EmptyId);
+        }
+        if self.version == 0 {
+            return Err(ObjectValidationError::ZeroVersion(id.clone()));
+        }
+        match &self.owner {
+            Owner::Address(addr) if addr.trim().is_empty() => {
+                return Err(ObjectValidationError::EmptyOwner(id.clone()));
+            }
+            Owner::Object(parent) if parent.0.trim().is_empty() => {
+                return Err(ObjectValidationError::EmptyOwner(id.clone()));
+            }
+            Owner::Object(parent) if parent == &self.id => {
+                return Err(ObjectValidationError::SelfOwned(id.clone()));
+            }
+            _ => {}
+        }
+        if let ObjectData::MoveStruct { type_name, .. } = &self.data {
+            if type_name.trim().is_empty() {
+                return Err(ObjectValidationError::MissingTypeName(id.clone()));
+            }
+        }
+        if let Some(initial) = self.initial_shared_version {
+            if initial > self.version {
+                return Err(ObjectValidationError::SharedVersionAhead {
+                    id: id.clone(),
+                    initial,
+                    version: self.version,
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_ids_depend_only_on_digest_and_creation_index() {
+        let digest = TransactionDigest("tx-1".to_string());
+
+        assert_eq!(ObjectID::derive(&digest, 0), ObjectID::derive(&digest, 0));
+        assert_ne!(ObjectID::derive(&digest, 0), ObjectID::derive(&digest, 1));
+        assert_ne!(ObjectID::derive(&digest, 0), ObjectID::derive(&TransactionDigest("tx-2".to_string()), 0));
+    }
+
+    #[test]
+    fn address_owner_matches_after_normalization() {
+        let owner = Owner::Address("0xAbc".to_string());
+
+        assert!(owner.matches_address("0xabc"));
+        assert!(owner.matches_address(" abc "));
+        assert!(owner.matches_address("0XABC"));
+        assert!(!owner.matches_address("0xabd"));
+        assert_eq!(owner.as_address(), Some("0xAbc"));
+    }
+
+    #[test]
+    fn non_address_owners_never_match() {
+        for owner in [Owner::Shared, Owner::Immutable, Owner::Object(ObjectID::new("abc"))] {
+            assert!(!owner.matches_address("abc"));
+            assert_eq!(owner.as_address(), None);
+        }
+    }
+
+    #[test]
+    fn shared_sequence_counts_mutations_since_sharing() {
+        let mut pool = SuiObject::new(ObjectID::new("pool"), Owner::Shared, ObjectData::Coin { balance: 0 });
+        assert_eq!((pool.initial_shared_version, pool.shared_sequence()), (Some(1), Some(0)));
+
+        pool.version += 1;
+        assert_eq!(pool.shared_sequence(), Some(1));
+        assert!(pool.validate().is_ok());
+
+        pool.initial_shared_version = Some(3);
+        assert_eq!(
+            pool.validate(),
+            Err(ObjectValidationError::SharedVersionAhead { id: "pool".to_string(), initial: 3, version: 2 })
+        );
+    }
+}
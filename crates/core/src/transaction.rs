@@ -1,4 +1,4 @@
-use crate::object::ObjectID;
+use crate::object::{ObjectID, Owner};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
@@ -12,14 +12,128 @@ pub enum TransactionKind {
         package: ObjectID,
         module: String,
         function: String,
-        arguments: Vec<serde_json::Value>,
+        arguments: Vec<Argument>,
     },
+    /// Replaces the fields of a `MoveStruct` object, e.g. after a package upgrade.
+    Migrate {
+        object: ObjectID,
+        new_fields: serde_json::Value,
+    },
+    /// Moves an object to any kind of owner: an address, another object,
+    /// shared or immutable.
+    ChangeOwner { object: ObjectID, new_owner: Owner },
+    /// Splits a coin owned by the signer into one new coin per recipient,
+    /// holding the listed amount.
+    Airdrop {
+        source: ObjectID,
+        distribution: Vec<(String, u64)>,
+    },
+    /// Places an object inside `wrapper`. Both must be owned by the signer.
+    Wrap { object: ObjectID, wrapper: ObjectID },
+    /// Takes a wrapped object back out of its wrapper and gives it to the
+    /// signer, who must own the wrapper.
+    Unwrap { object: ObjectID },
+    /// Hands an object to the transaction's signer. The object may come from
+    /// an earlier command in the same batch via `Argument::Result`.
+    TransferToSender { object: Argument },
+    /// Runs several commands atomically: if any aborts, none take effect.
+    Batch { commands: Vec<TransactionKind> },
+    /// Runs `then` only if `condition` holds against the current object
+    /// state, aborting otherwise. `then` may not be a batch, but a
+    /// conditional may be one of a batch's commands.
+    Conditional {
+        condition: StateCondition,
+        then: Box<TransactionKind>,
+    },
+}
+
+/// An assertion about current object state, checked by
+/// `TransactionKind::Conditional`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum StateCondition {
+    Exists { object: ObjectID },
+    Absent { object: ObjectID },
+    OwnedBy { object: ObjectID, owner: Owner },
+    /// The object is a coin holding at least `amount`.
+    BalanceAtLeast { object: ObjectID, amount: u64 },
+    /// Every condition holds.
+    All(Vec<StateCondition>),
+}
+
+impl StateCondition {
+    /// Every object the condition inspects.
+    pub fn objects(&self) -> Vec<&ObjectID> {
+        match self {
+            StateCondition::Exists { object }
+            | StateCondition::Absent { object }
+            | StateCondition::OwnedBy { object, .. }
+            | StateCondition::BalanceAtLeast { object, .. } => vec![object],
+            StateCondition::All(conditions) => conditions.iter().flat_map(StateCondition::objects).collect(),
+        }
+    }
+}
+
+/// An argument to a Move call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Argument {
+    /// A literal value.
+    Value(serde_json::Value),
+    /// An object passed by reference, loaded from the store at execution time.
+    Object(ObjectID),
+    /// The output of an earlier command in the same batch, by index.
+    Result(usize),
+}
+
+/// An object the transaction declares up front, and whether it will be mutated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputObject {
+    pub id: ObjectID,
+    pub mutable: bool,
+    /// Version the transaction was built against. When set, the transaction
+    /// is rejected at admission if the object has since moved on.
+    #[serde(default)]
+    pub version: Option<u64>,
+}
+
+impl InputObject {
+    pub fn mutable(id: ObjectID) -> Self {
+        Self { id, mutable: true, version: None }
+    }
+
+    pub fn read_only(id: ObjectID) -> Self {
+        Self { id, mutable: false, version: None }
+    }
+
+    /// Pins the input to `version`.
+    pub fn at_version(mut self, version: u64) -> Self {
+        self.version = Some(version);
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionPayload {
     pub kind: TransactionKind,
     pub gas_budget: u64,
+    #[serde(default)]
+    pub input_objects: Vec<InputObject>,
+    /// Per-signer counter for replay protection, starting at 1. Only checked
+    /// by validators that track sequence numbers.
+    #[serde(default)]
+    pub sequence_number: u64,
+    /// Price per unit of gas the sender is willing to pay. Only checked by
+    /// validators running a fee model.
+    #[serde(default)]
+    pub gas_price: u64,
+    /// Marks a transaction that only inspects objects. It needs no gas
+    /// budget, and the VM aborts it if it attempts any write.
+    #[serde(default)]
+    pub read_only: bool,
+    /// Coin that pays for gas. `gas_budget * gas_price` must be available in
+    /// it; the gas actually used is charged and the rest of the budget
+    /// refunded. Declare it as a mutable input so that it is locked.
+    #[serde(default)]
+    pub gas_payment: Option<ObjectID>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,12 +145,15 @@ pub struct SignedTransaction {
 
 impl SignedTransaction {
     pub fn new_transfer(sender: String, recipient: String, object: String) -> Self {
+        let object = ObjectID(object);
         let payload = TransactionPayload {
-            kind: TransactionKind::Transfer {
-                object: ObjectID(object),
-                recipient,
-            },
+            input_objects: vec![InputObject::mutable(object.clone())],
+            kind: TransactionKind::Transfer { object, recipient },
             gas_budget: 1_000,
+            sequence_number: 0,
+            gas_price: 0,
+            read_only: false,
+            gas_payment: None,
         };
         Self::new(sender, payload)
     }
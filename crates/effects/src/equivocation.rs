@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use sui_core::transaction::TransactionDigest;
+
+use crate::TransactionEffects;
+
+/// Two validators reported different effects for the same transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Equivocation {
+    pub digest: TransactionDigest,
+    /// `(validator, effects digest)` of the first report seen.
+    pub first: (String, String),
+    /// `(validator, effects digest)` of the conflicting report.
+    pub second: (String, String),
+}
+
+/// Collects effects digests reported by validators and flags transactions
+/// for which they disagree.
+#[derive(Debug, Default)]
+pub struct EquivocationDetector {
+    reports: HashMap<TransactionDigest, Vec<(String, String)>>,
+    equivocations: Vec<Equivocation>,
+}
+
+impl EquivocationDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `validator`'s effects and returns the conflict if they differ
+    /// from an earlier report for the same transaction. Repeated reports from
+    /// the same validator are ignored.
+    pub fn record(&mut self, validator: &str, effects: &TransactionEffects) -> Option<Equivocation> {
+        self.record_digest(validator, effects.digest.clone(), effects.effects_digest())
+    }
+
+    pub fn record_digest(
+        &mut self,
+        validator: &str,
+        digest: TransactionDigest,
+        effects_digest: String,
+    ) -> Option<Equivocation> {
+        let reports = self.reports.entry(digest.clone()).or_default();
+        if reports.iter().any(|(name, _)| name == validator) {
+            return None;
+        }
+
+        let conflict = reports
+            .iter()
+            .find(|(_, reported)| *reported != effects_digest)
+            .map(|first| Equivocation {
+                digest,
+                first: first.clone(),
+                second: (validator.to_string(), effects_digest.clone()),
+            });
+        reports.push((validator.to_string(), effects_digest));
+
+        if let Some(conflict) = &conflict {
+            self.equivocations.push(conflict.clone());
+        }
+        conflict
+    }
+
+    /// Every conflict detected so far, in detection order.
+    pub fn equivocations(&self) -> &[Equivocation] {
+        &self.equivocations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_core::object::{ObjectData, ObjectID, Owner, SuiObject};
+
+    fn effects_creating(object: &str) -> TransactionEffects {
+        let mut effects = TransactionEffects::new(TransactionDigest("tx-1".to_string()));
+        effects.created.push(SuiObject::new(
+            ObjectID::new(object),
+            Owner::Address("alice".to_string()),
+            ObjectData::Coin { balance: 5 },
+        ));
+        effects
+    }
+
+    #[test]
+    fn identical_effects_are_not_flagged() {
+        let mut detector = EquivocationDetector::new();
+
+        assert!(detector.record("v1", &effects_creating("coin-a")).is_none());
+        assert!(detector.record("v2", &effects_creating("coin-a")).is_none());
+        assert!(detector.equivocations().is_empty());
+    }
+
+    #[test]
+    fn differing_effects_are_flagged_with_both_validators() {
+        let mut detector = EquivocationDetector::new();
+        detector.record("v1", &effects_creating("coin-a"));
+
+        let conflict = detector.record("v2", &effects_creating("coin-b")).unwrap();
+
+        assert_eq!(conflict.digest, TransactionDigest("tx-1".to_string()));
+        assert_eq!(conflict.first, ("v1".to_string(), effects_creating("coin-a").effects_digest()));
+        assert_eq!(conflict.second, ("v2".to_string(), effects_creating("coin-b").effects_digest()));
+        assert_eq!(detector.equivocations(), &[conflict]);
+    }
+
+    #[test]
+    fn repeated_report_from_one_validator_is_ignored() {
+        let mut detector = EquivocationDetector::new();
+        detector.record("v1", &effects_creating("coin-a"));
+
+        assert!(detector.record("v1", &effects_creating("coin-b")).is_none());
+        assert!(detector.equivocations().is_empty());
+    }
+}
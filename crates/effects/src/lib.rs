@@ -1,23 +1,183 @@
-use serde::Serialize;
-use sui_core::{object::SuiObject, transaction::TransactionDigest};
+mod equivocation;
 
-#[derive(Debug, Serialize)]
+pub use equivocation::{Equivocation, EquivocationDetector};
+
+use anyhow::{anyhow, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use sui_core::{
+    hash::{Hasher, Sha256},
+    events::ObjectEvent,
+    messages::{BatchResult, ExecutionStatus},
+    object::{ObjectData, ObjectID, Owner, SuiObject},
+    transaction::TransactionDigest,
+};
+
+/// Layout version written into every serialized `TransactionEffects`.
+///
+/// * `0` - unversioned effects without a `deleted` list.
+/// * `1` - adds `schema_version` and `deleted`.
+pub const EFFECTS_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionEffects {
+    pub schema_version: u32,
     pub digest: TransactionDigest,
+    #[serde(default)]
+    pub status: ExecutionStatus,
     pub created: Vec<SuiObject>,
     pub mutated: Vec<SuiObject>,
+    pub deleted: Vec<ObjectID>,
     pub events: Vec<String>,
+    #[serde(default)]
+    pub object_events: Vec<ObjectEvent>,
+    #[serde(default)]
+    pub return_values: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub object_reads: u64,
+    #[serde(default)]
+    pub object_writes: u64,
+    #[serde(default)]
+    pub batch: Option<BatchResult>,
+    /// Version of every object the transaction read from the store, as it
+    /// was before execution, sorted by id.
+    #[serde(default)]
+    pub input_versions: Vec<(ObjectID, u64)>,
+    /// Unused gas budget, at the gas price, credited back to the gas coin.
+    #[serde(default)]
+    pub gas_refund: u64,
+    /// The gas coin and its balance after gas was charged and refunded.
+    #[serde(default)]
+    pub gas_coin: Option<(ObjectID, u64)>,
+    /// Owner and balance, before execution, of every coin the transaction
+    /// read, sorted by id.
+    #[serde(default)]
+    pub input_coins: Vec<(ObjectID, Owner, u64)>,
 }
 
 impl TransactionEffects {
     pub fn new(digest: TransactionDigest) -> Self {
         Self {
+            schema_version: EFFECTS_SCHEMA_VERSION,
             digest,
+            status: ExecutionStatus::Success,
             created: Vec::new(),
             mutated: Vec::new(),
+            deleted: Vec::new(),
             events: Vec::new(),
+            object_events: Vec::new(),
+            return_values: Vec::new(),
+            object_reads: 0,
+            object_writes: 0,
+            batch: None,
+            input_versions: Vec::new(),
+            gas_refund: 0,
+            gas_coin: None,
+            input_coins: Vec::new(),
         }
     }
+
+    /// Hex SHA-256 of [`canonical_bytes`](Self::canonical_bytes), the BCS
+    /// encoding. Validators that executed the same transaction identically
+    /// produce the same digest.
+    pub fn effects_digest(&self) -> String {
+        self.effects_digest_with(&Sha256)
+    }
+
+    pub fn effects_digest_with(&self, hasher: &dyn Hasher) -> String {
+        hasher.hash(&self.canonical_bytes())
+    }
+
+    /// BCS encoding of the effects in a fixed layout, with created, mutated
+    /// and deleted objects sorted by id and events sorted, so that identical
+    /// effects encode identically however they were assembled. All effects
+    /// hashing and signing goes through these bytes.
+    ///
+    /// Objects, statuses and other JSON-shaped values are embedded as
+    /// canonical JSON strings, since BCS cannot encode arbitrary JSON.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        fn json<T: Serialize + ?Sized>(value: &T) -> String {
+            serde_json::to_string(value).expect("effects are always serializable")
+        }
+        let sorted_objects = |objects: &[SuiObject]| {
+            let mut encoded: Vec<(String, u64, String)> = objects
+                .iter()
+                .map(|object| (object.id.0.clone(), object.version, json(object)))
+                .collect();
+            encoded.sort();
+            encoded
+        };
+
+        let mut deleted: Vec<&str> = self.deleted.iter().map(|id| id.0.as_str()).collect();
+        deleted.sort_unstable();
+        let mut events = self.events.clone();
+        events.sort();
+        let mut object_events: Vec<String> = self.object_events.iter().map(json).collect();
+        object_events.sort();
+        let mut input_versions: Vec<(&str, u64)> = self
+            .input_versions
+            .iter()
+            .map(|(id, version)| (id.0.as_str(), *version))
+            .collect();
+        input_versions.sort_unstable();
+        let mut input_coins: Vec<(&str, String, u64)> = self
+            .input_coins
+            .iter()
+            .map(|(id, owner, balance)| (id.0.as_str(), json(owner), *balance))
+            .collect();
+        input_coins.sort_unstable();
+
+        bcs::to_bytes(&(
+            self.schema_version,
+            &self.digest.0,
+            json(&self.status),
+            sorted_objects(&self.created),
+            sorted_objects(&self.mutated),
+            deleted,
+            events,
+            object_events,
+            json(&self.return_values),
+            self.object_reads,
+            self.object_writes,
+            json(&self.batch),
+            input_versions,
+            self.gas_refund,
+            self.gas_coin.as_ref().map(|(id, balance)| (id.0.as_str(), *balance)),
+            input_coins,
+        ))
+        .expect("canonical effects layout is BCS encodable")
+    }
+
+    /// Net change in coin balance per address owner: coins created or
+    /// mutated count towards their new owner, and coins mutated or deleted
+    /// count against their owner before execution, as recorded in
+    /// `input_coins`. The gas coin is a mutated coin, so the result is net
+    /// of gas. Addresses whose balance did not change are left out.
+    pub fn balance_changes(&self) -> HashMap<String, i128> {
+        let written: HashSet<&ObjectID> = self
+            .mutated
+            .iter()
+            .map(|object| &object.id)
+            .chain(&self.deleted)
+            .collect();
+        let mut changes: HashMap<String, i128> = HashMap::new();
+        for (id, owner, balance) in &self.input_coins {
+            if !written.contains(id) {
+                continue;
+            }
+            if let Some(address) = owner.as_address() {
+                *changes.entry(address.to_string()).or_default() -= i128::from(*balance);
+            }
+        }
+        for object in self.created.iter().chain(&self.mutated) {
+            if let (ObjectData::Coin { balance }, Some(address)) = (&object.data, object.owner.as_address()) {
+                *changes.entry(address.to_string()).or_default() += i128::from(*balance);
+            }
+        }
+        changes.retain(|_, delta| *delta != 0);
+        changes
+    }
 }
 
 pub struct EffectsBuilder {
@@ -31,6 +191,11 @@ impl EffectsBuilder {
         }
     }
 
+    pub fn with_status(mut self, status: ExecutionStatus) -> Self {
+        self.effects.status = status;
+        self
+    }
+
     pub fn record_created(mut self, object: SuiObject) -> Self {
         self.effects.created.push(object);
         self
@@ -41,13 +206,217 @@ impl EffectsBuilder {
         self
     }
 
+    pub fn record_deleted(mut self, id: ObjectID) -> Self {
+        self.effects.deleted.push(id);
+        self
+    }
+
     pub fn record_event(mut self, event: impl Into<String>) -> Self {
         self.effects.events.push(event.into());
         self
     }
 
+    pub fn record_object_event(mut self, event: ObjectEvent) -> Self {
+        self.effects.object_events.push(event);
+        self
+    }
+
+    pub fn record_return_values(mut self, values: Vec<serde_json::Value>) -> Self {
+        self.effects.return_values.extend(values);
+        self
+    }
+
+    pub fn record_metering(mut self, reads: u64, writes: u64) -> Self {
+        self.effects.object_reads = reads;
+        self.effects.object_writes = writes;
+        self
+    }
+
+    pub fn record_input_versions(mut self, versions: Vec<(ObjectID, u64)>) -> Self {
+        self.effects.input_versions = versions;
+        self
+    }
+
+    pub fn record_input_coins(mut self, coins: Vec<(ObjectID, Owner, u64)>) -> Self {
+        self.effects.input_coins = coins;
+        self
+    }
+
+    pub fn record_gas_refund(mut self, refund: u64, gas_coin: Option<(ObjectID, u64)>) -> Self {
+        self.effects.gas_refund = refund;
+        self.effects.gas_coin = gas_coin;
+        self
+    }
+
+    pub fn record_batch_result(mut self, batch: BatchResult) -> Self {
+        self.effects.batch = Some(batch);
+        self
+    }
+
     pub fn build(self) -> TransactionEffects {
         self.effects
     }
 }
 
+/// Parses stored effects JSON of any known schema version, upgrading it to
+/// the current `TransactionEffects` layout.
+pub fn migrate_effects_json(raw: &str) -> anyhow::Result<TransactionEffects> {
+    let mut value: serde_json::Value = serde_json::from_str(raw)?;
+    let fields = value
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("effects JSON must be an object"))?;
+
+    let version = fields
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    if version > u64::from(EFFECTS_SCHEMA_VERSION) {
+        bail!("unsupported effects schema version {version}");
+    }
+
+    if version < 1 {
+        fields.entry("deleted").or_insert_with(|| json!([]));
+    }
+    fields.insert("schema_version".to_string(), json!(EFFECTS_SCHEMA_VERSION));
+
+    Ok(serde_json::from_value(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_core::object::{ObjectData, Owner};
+
+    #[test]
+    fn version_zero_json_migrates_with_deleted_defaulted() {
+        let raw = r#"{"digest":"tx-1","created":[],"mutated":[],"events":["done"]}"#;
+
+        let effects = migrate_effects_json(raw).unwrap();
+
+        assert_eq!(effects.schema_version, EFFECTS_SCHEMA_VERSION);
+        assert_eq!(effects.digest, TransactionDigest("tx-1".to_string()));
+        assert!(effects.deleted.is_empty());
+        assert_eq!(effects.events, vec!["done".to_string()]);
+    }
+
+    #[test]
+    fn current_version_round_trips() {
+        let effects = EffectsBuilder::new(TransactionDigest("tx-1".to_string()))
+            .record_created(SuiObject::new(
+                ObjectID::new("coin"),
+                Owner::Address("alice".to_string()),
+                ObjectData::Coin { balance: 5 },
+            ))
+            .record_deleted(ObjectID::new("old"))
+            .record_event("done")
+            .build();
+        let raw = serde_json::to_string(&effects).unwrap();
+
+        let migrated = migrate_effects_json(&raw).unwrap();
+
+        assert_eq!(serde_json::to_string(&migrated).unwrap(), raw);
+    }
+
+    #[test]
+    fn newer_schema_version_is_rejected() {
+        let raw = format!(
+            r#"{{"schema_version":{},"digest":"tx-1","created":[],"mutated":[],"deleted":[],"events":[]}}"#,
+            EFFECTS_SCHEMA_VERSION + 1
+        );
+
+        assert!(migrate_effects_json(&raw).is_err());
+    }
+
+    fn coin(id: &str, balance: u64) -> SuiObject {
+        SuiObject::new(ObjectID::new(id), Owner::Address("alice".to_string()), ObjectData::Coin { balance })
+    }
+
+    #[test]
+    fn canonical_bytes_ignore_recording_order() {
+        let forward = EffectsBuilder::new(TransactionDigest("tx-1".to_string()))
+            .record_created(coin("a", 1))
+            .record_created(coin("b", 2))
+            .record_mutated(coin("c", 3))
+            .record_mutated(coin("d", 4))
+            .record_deleted(ObjectID::new("e"))
+            .record_deleted(ObjectID::new("f"))
+            .record_event("first")
+            .record_event("second")
+            .build();
+        let backward = EffectsBuilder::new(TransactionDigest("tx-1".to_string()))
+            .record_event("second")
+            .record_event("first")
+            .record_deleted(ObjectID::new("f"))
+            .record_deleted(ObjectID::new("e"))
+            .record_mutated(coin("d", 4))
+            .record_mutated(coin("c", 3))
+            .record_created(coin("b", 2))
+            .record_created(coin("a", 1))
+            .build();
+
+        assert_eq!(forward.canonical_bytes(), backward.canonical_bytes());
+    }
+
+    #[test]
+    fn canonical_bytes_change_with_any_field() {
+        let base = || {
+            EffectsBuilder::new(TransactionDigest("tx-1".to_string()))
+                .record_created(coin("a", 1))
+                .record_mutated(coin("c", 3))
+                .record_deleted(ObjectID::new("e"))
+                .record_event("done")
+        };
+        let bytes = base().build().canonical_bytes();
+        let variants = [
+            EffectsBuilder::new(TransactionDigest("tx-2".to_string()))
+                .record_created(coin("a", 1))
+                .record_mutated(coin("c", 3))
+                .record_deleted(ObjectID::new("e"))
+                .record_event("done")
+                .build(),
+            base().record_created(coin("b", 2)).build(),
+            base().record_mutated(coin("d", 4)).build(),
+            base().record_deleted(ObjectID::new("f")).build(),
+            base().record_event("again").build(),
+        ];
+
+        for variant in variants {
+            assert_ne!(variant.canonical_bytes(), bytes);
+        }
+    }
+
+    fn owned_by(id: &str, owner: &str, balance: u64) -> SuiObject {
+        SuiObject { owner: Owner::Address(owner.to_string()), ..coin(id, balance) }
+    }
+
+    fn alice(id: &str, balance: u64) -> (ObjectID, Owner, u64) {
+        (ObjectID::new(id), Owner::Address("alice".to_string()), balance)
+    }
+
+    #[test]
+    fn transfer_moves_its_balance_net_of_gas() {
+        let effects = EffectsBuilder::new(TransactionDigest("tx-1".to_string()))
+            .record_mutated(owned_by("coin", "bob", 1_000))
+            .record_mutated(coin("gas", 9_990))
+            .record_input_coins(vec![alice("coin", 1_000), alice("gas", 10_000)])
+            .build();
+
+        let changes = effects.balance_changes();
+
+        assert_eq!(changes, HashMap::from([("alice".to_string(), -1_010), ("bob".to_string(), 1_000)]));
+    }
+
+    #[test]
+    fn created_and_deleted_coins_count_and_unchanged_owners_are_left_out() {
+        let effects = EffectsBuilder::new(TransactionDigest("tx-1".to_string()))
+            .record_created(owned_by("minted", "carol", 50))
+            .record_mutated(coin("kept", 20))
+            .record_deleted(ObjectID::new("burned"))
+            .record_input_coins(vec![alice("kept", 20), alice("burned", 30), alice("read", 99)])
+            .build();
+
+        let changes = effects.balance_changes();
+
+        assert_eq!(changes, HashMap::from([("alice".to_string(), -30), ("carol".to_string(), 50)]));
+    }
+}
@@ -1,7 +1,13 @@
+mod screener;
+
+pub use screener::ConflictScreener;
+
 use parking_lot::Mutex;
 use std::collections::HashMap;
+use std::sync::Arc;
 
-use sui_core::object::SuiObject;
+use sui_core::clock::{Clock, SystemClock};
+use sui_core::object::{ObjectID, SuiObject};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LockMode {
@@ -13,44 +19,140 @@ pub enum LockMode {
 struct LockState {
     shared_count: usize,
     exclusive: bool,
+    /// When the lease of the most recent holder runs out, if leases are on.
+    expires_at: Option<u64>,
+}
+
+impl LockState {
+    fn admits(&self, mode: LockMode) -> bool {
+        match mode {
+            LockMode::Shared => !self.exclusive,
+            LockMode::Exclusive => !self.exclusive && self.shared_count == 0,
+        }
+    }
+}
+
+/// The set of object locks a transaction needs, acquired all-or-nothing.
+#[derive(Debug, Clone, Default)]
+pub struct LockPlan {
+    entries: Vec<(SuiObject, LockMode)>,
+}
+
+impl LockPlan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an object to the plan. Declaring the same object twice keeps the
+    /// stronger of the two modes so a transaction never deadlocks on itself.
+    pub fn add(&mut self, object: SuiObject, mode: LockMode) {
+        let key = object.lock_key();
+        match self.entries.iter_mut().find(|(existing, _)| existing.lock_key() == key) {
+            Some((_, existing_mode)) => {
+                if mode == LockMode::Exclusive {
+                    *existing_mode = LockMode::Exclusive;
+                }
+            }
+            None => self.entries.push((object, mode)),
+        }
+    }
+
+    pub fn entries(&self) -> &[(SuiObject, LockMode)] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Point-in-time view of lock usage and contention.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LockStats {
+    /// Shared holds currently outstanding, summed over all objects.
+    pub shared_held: usize,
+    /// Objects currently locked exclusively.
+    pub exclusive_held: usize,
+    pub acquire_attempts: u64,
+    /// Acquires refused because of a conflicting lock.
+    pub contention_failures: u64,
+    /// Contention failures per lock key.
+    pub contention_by_object: HashMap<String, u64>,
 }
 
 #[derive(Default)]
+struct LockTable {
+    locks: HashMap<String, LockState>,
+    acquire_attempts: u64,
+    contention_failures: u64,
+    contention_by_object: HashMap<String, u64>,
+}
+
 pub struct LockManager {
-    inner: Mutex<HashMap<String, LockState>>,
+    inner: Mutex<LockTable>,
+    clock: Arc<dyn Clock>,
+    lease_ms: Option<u64>,
+}
+
+impl Default for LockManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl LockManager {
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            inner: Mutex::new(LockTable::default()),
+            clock: Arc::new(SystemClock),
+            lease_ms: None,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Gives every acquired lock a lease of `lease_ms`. A lock whose lease
+    /// has run out is still held until `reclaim_expired` removes it.
+    pub fn with_lease(mut self, lease_ms: u64) -> Self {
+        self.lease_ms = Some(lease_ms);
+        self
+    }
+
+    pub fn lease_ms(&self) -> Option<u64> {
+        self.lease_ms
     }
 
     pub fn acquire(&self, object: &SuiObject, mode: LockMode) -> bool {
-        let mut map = self.inner.lock();
-        let state = map.entry(object.lock_key()).or_default();
+        let mut table = self.inner.lock();
+        table.acquire_attempts += 1;
+        let key = object.lock_key();
+        let state = table.locks.entry(key.clone()).or_default();
 
-        match mode {
-            LockMode::Shared => {
-                if state.exclusive {
-                    false
-                } else {
-                    state.shared_count += 1;
-                    true
-                }
+        let acquired = state.admits(mode);
+        if acquired {
+            match mode {
+                LockMode::Shared => state.shared_count += 1,
+                LockMode::Exclusive => state.exclusive = true,
             }
-            LockMode::Exclusive => {
-                if state.exclusive || state.shared_count > 0 {
-                    false
-                } else {
-                    state.exclusive = true;
-                    true
-                }
+            // A shared lock lives as long as its newest holder's lease.
+            if let Some(lease_ms) = self.lease_ms {
+                state.expires_at = Some(self.clock.now().saturating_add(lease_ms));
             }
         }
+
+        if !acquired {
+            table.contention_failures += 1;
+            *table.contention_by_object.entry(key).or_default() += 1;
+        }
+        acquired
     }
 
     pub fn release(&self, object: &SuiObject, mode: LockMode) {
-        let mut map = self.inner.lock();
+        let mut table = self.inner.lock();
+        let map = &mut table.locks;
         if let Some(state) = map.get_mut(&object.lock_key()) {
             match mode {
                 LockMode::Shared => state.shared_count = state.shared_count.saturating_sub(1),
@@ -62,5 +164,202 @@ impl LockManager {
             }
         }
     }
+
+    /// Turns the caller's shared lock into an exclusive one. Only succeeds
+    /// when the caller is the sole shared holder: two holders both waiting
+    /// to upgrade would otherwise deadlock. On failure the shared lock is
+    /// kept, and the attempt counts as contention.
+    pub fn upgrade(&self, object: &SuiObject) -> bool {
+        let mut table = self.inner.lock();
+        table.acquire_attempts += 1;
+        let key = object.lock_key();
+        let upgraded = match table.locks.get_mut(&key) {
+            Some(state) if !state.exclusive && state.shared_count == 1 => {
+                state.shared_count = 0;
+                state.exclusive = true;
+                if let Some(lease_ms) = self.lease_ms {
+                    state.expires_at = Some(self.clock.now().saturating_add(lease_ms));
+                }
+                true
+            }
+            _ => false,
+        };
+
+        if !upgraded {
+            table.contention_failures += 1;
+            *table.contention_by_object.entry(key).or_default() += 1;
+        }
+        upgraded
+    }
+
+    /// Turns the caller's exclusive lock into a shared one, letting other
+    /// readers in. Returns false if the object was not locked exclusively.
+    pub fn downgrade(&self, object: &SuiObject) -> bool {
+        let mut table = self.inner.lock();
+        match table.locks.get_mut(&object.lock_key()) {
+            Some(state) if state.exclusive => {
+                state.exclusive = false;
+                state.shared_count = 1;
+                if let Some(lease_ms) = self.lease_ms {
+                    state.expires_at = Some(self.clock.now().saturating_add(lease_ms));
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Acquires every lock in the plan, releasing any already taken if one of
+    /// them is unavailable.
+    pub fn acquire_plan(&self, plan: &LockPlan) -> bool {
+        for (idx, (object, mode)) in plan.entries.iter().enumerate() {
+            if !self.acquire(object, *mode) {
+                for (held, held_mode) in &plan.entries[..idx] {
+                    self.release(held, *held_mode);
+                }
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn release_plan(&self, plan: &LockPlan) {
+        for (object, mode) in &plan.entries {
+            self.release(object, *mode);
+        }
+    }
+
+    /// Forcibly releases every lock whose lease ended at or before `now`,
+    /// returning the reclaimed lock keys. Meant for holders presumed dead:
+    /// a holder that later releases a reclaimed lock may release whoever
+    /// acquired it since.
+    pub fn reclaim_expired(&self, now: u64) -> Vec<String> {
+        let mut table = self.inner.lock();
+        let expired: Vec<String> = table
+            .locks
+            .iter()
+            .filter(|(_, state)| state.expires_at.is_some_and(|expires_at| expires_at <= now))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &expired {
+            table.locks.remove(key);
+        }
+        expired
+    }
+
+    /// Objects in the plan whose locks are currently held in a conflicting
+    /// mode. Does not touch the lock table or its counters.
+    pub fn conflicts(&self, plan: &LockPlan) -> Vec<ObjectID> {
+        let table = self.inner.lock();
+        plan.entries
+            .iter()
+            .filter(|(object, mode)| {
+                table
+                    .locks
+                    .get(&object.lock_key())
+                    .is_some_and(|state| !state.admits(*mode))
+            })
+            .map(|(object, _)| object.id.clone())
+            .collect()
+    }
+
+    pub fn stats(&self) -> LockStats {
+        let table = self.inner.lock();
+        LockStats {
+            shared_held: table.locks.values().map(|state| state.shared_count).sum(),
+            exclusive_held: table.locks.values().filter(|state| state.exclusive).count(),
+            acquire_attempts: table.acquire_attempts,
+            contention_failures: table.contention_failures,
+            contention_by_object: table.contention_by_object.clone(),
+        }
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_core::clock::MockClock;
+    use sui_core::object::{ObjectData, Owner};
+
+    fn coin(id: &str) -> SuiObject {
+        SuiObject::new(ObjectID::new(id), Owner::Address("alice".to_string()), ObjectData::Coin { balance: 1 })
+    }
+
+    #[test]
+    fn stats_report_held_locks_and_contention() {
+        let manager = LockManager::new();
+        let (a, b, c) = (coin("a"), coin("b"), coin("c"));
+
+        assert!(manager.acquire(&a, LockMode::Exclusive));
+        assert!(manager.acquire(&b, LockMode::Shared));
+        assert!(manager.acquire(&b, LockMode::Shared));
+        assert!(!manager.acquire(&a, LockMode::Shared));
+        assert!(!manager.acquire(&a, LockMode::Exclusive));
+        assert!(!manager.acquire(&b, LockMode::Exclusive));
+        assert!(manager.acquire(&c, LockMode::Exclusive));
+        manager.release(&c, LockMode::Exclusive);
+
+        let stats = manager.stats();
+        assert_eq!(stats.shared_held, 2);
+        assert_eq!(stats.exclusive_held, 1);
+        assert_eq!(stats.acquire_attempts, 7);
+        assert_eq!(stats.contention_failures, 3);
+        assert_eq!(stats.contention_by_object[&a.lock_key()], 2);
+        assert_eq!(stats.contention_by_object[&b.lock_key()], 1);
+        assert!(!stats.contention_by_object.contains_key(&c.lock_key()));
+    }
+
+    #[test]
+    fn failed_plan_releases_its_partial_locks() {
+        let manager = LockManager::new();
+        let (a, b) = (coin("a"), coin("b"));
+        assert!(manager.acquire(&b, LockMode::Exclusive));
+        let mut plan = LockPlan::new();
+        plan.add(a.clone(), LockMode::Exclusive);
+        plan.add(b.clone(), LockMode::Shared);
+
+        assert!(!manager.acquire_plan(&plan));
+
+        assert_eq!(manager.stats().exclusive_held, 1);
+        assert!(manager.acquire(&a, LockMode::Exclusive));
+    }
+
+    #[test]
+    fn lock_is_reclaimable_once_its_lease_runs_out() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let manager = LockManager::new().with_clock(clock.clone()).with_lease(100);
+        let (a, b) = (coin("a"), coin("b"));
+        assert!(manager.acquire(&a, LockMode::Exclusive));
+        clock.advance(50);
+        assert!(manager.acquire(&b, LockMode::Exclusive));
+
+        assert!(manager.reclaim_expired(1_099).is_empty());
+        assert!(!manager.acquire(&a, LockMode::Exclusive));
+
+        assert_eq!(manager.reclaim_expired(1_100), vec![a.lock_key()]);
+        assert!(manager.acquire(&a, LockMode::Exclusive));
+        assert!(!manager.acquire(&b, LockMode::Exclusive));
+    }
+
+    #[test]
+    fn locks_without_a_lease_are_never_reclaimed() {
+        let manager = LockManager::new();
+        assert!(manager.acquire(&coin("a"), LockMode::Exclusive));
+
+        assert!(manager.reclaim_expired(u64::MAX).is_empty());
+    }
+
+    #[test]
+    fn downgrade_renews_the_lease() {
+        let clock = Arc::new(MockClock::new(0));
+        let manager = LockManager::new().with_clock(clock.clone()).with_lease(100);
+        let a = coin("a");
+        assert!(manager.acquire(&a, LockMode::Exclusive));
+
+        clock.set(90);
+        assert!(manager.downgrade(&a));
+
+        assert!(manager.reclaim_expired(150).is_empty());
+        assert_eq!(manager.reclaim_expired(190), vec![a.lock_key()]);
+    }
+}
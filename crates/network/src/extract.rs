@@ -0,0 +1,64 @@
+use axum::{
+    extract::{rejection::JsonRejection, FromRequest, Request},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+/// Body returned when a request cannot be parsed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub details: String,
+}
+
+/// Rejection for [`ApiJson`]: the status axum would have used, with an
+/// [`ErrorResponse`] body instead of plain text.
+pub struct ApiJsonRejection {
+    status: StatusCode,
+    body: ErrorResponse,
+}
+
+impl From<JsonRejection> for ApiJsonRejection {
+    fn from(rejection: JsonRejection) -> Self {
+        let error = match &rejection {
+            JsonRejection::JsonDataError(_) => "invalid_request_body",
+            JsonRejection::JsonSyntaxError(_) => "malformed_json",
+            JsonRejection::MissingJsonContentType(_) => "missing_json_content_type",
+            _ => "unreadable_request_body",
+        };
+        Self {
+            status: rejection.status(),
+            body: ErrorResponse {
+                error: error.to_string(),
+                // serde's message names the offending field or variant, e.g.
+                // "missing field `transaction`".
+                details: rejection.body_text(),
+            },
+        }
+    }
+}
+
+impl IntoResponse for ApiJsonRejection {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body)).into_response()
+    }
+}
+
+/// `Json` extractor whose rejections explain what failed to parse.
+pub struct ApiJson<T>(pub T);
+
+#[async_trait::async_trait]
+impl<T, S> FromRequest<S> for ApiJson<T>
+where
+    Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = ApiJsonRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state).await?;
+        Ok(Self(value))
+    }
+}
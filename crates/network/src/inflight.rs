@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use sui_core::transaction::TransactionDigest;
+use tokio::sync::watch;
+
+use crate::SubmitTransactionResponse;
+
+type Pending = Arc<Mutex<HashMap<TransactionDigest, watch::Receiver<Option<SubmitTransactionResponse>>>>>;
+
+/// Submissions currently executing, keyed by digest.
+///
+/// The first submission of a digest executes it; duplicates that arrive
+/// while it is running wait for that execution and get the same response.
+#[derive(Default)]
+pub(crate) struct InFlight {
+    pending: Pending,
+}
+
+enum Role {
+    Leader(watch::Sender<Option<SubmitTransactionResponse>>),
+    Follower(watch::Receiver<Option<SubmitTransactionResponse>>),
+}
+
+/// Removes the leader's entry once it finishes or is cancelled, so a
+/// dropped request cannot leave later duplicates waiting forever.
+struct Remove {
+    pending: Pending,
+    digest: TransactionDigest,
+}
+
+impl Drop for Remove {
+    fn drop(&mut self) {
+        self.pending.lock().unwrap().remove(&self.digest);
+    }
+}
+
+impl InFlight {
+    pub(crate) async fn run<F, Fut>(&self, digest: TransactionDigest, execute: F) -> SubmitTransactionResponse
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = SubmitTransactionResponse>,
+    {
+        loop {
+            let role = {
+                let mut pending = self.pending.lock().unwrap();
+                match pending.get(&digest) {
+                    Some(receiver) => Role::Follower(receiver.clone()),
+                    None => {
+                        let (sender, receiver) = watch::channel(None);
+                        pending.insert(digest.clone(), receiver);
+                        Role::Leader(sender)
+                    }
+                }
+            };
+
+            match role {
+                Role::Leader(sender) => {
+                    let _remove = Remove {
+                        pending: Arc::clone(&self.pending),
+                        digest,
+                    };
+                    let response = execute().await;
+                    let _ = sender.send(Some(response.clone()));
+                    return response;
+                }
+                Role::Follower(mut receiver) => {
+                    if let Ok(response) = receiver.wait_for(Option::is_some).await {
+                        return response.clone().expect("waited for a response");
+                    }
+                    // The leader was cancelled before answering; take over.
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Runs `digest` through `in_flight`, counting executions and answering
+    /// with the execution's number after `delay`.
+    async fn submit(
+        in_flight: &InFlight,
+        executions: &AtomicUsize,
+        digest: &str,
+        delay: Duration,
+    ) -> SubmitTransactionResponse {
+        in_flight
+            .run(TransactionDigest(digest.to_string()), || async {
+                let execution = executions.fetch_add(1, Ordering::SeqCst) + 1;
+                tokio::time::sleep(delay).await;
+                SubmitTransactionResponse { accepted: true, message: format!("execution {}", execution), receipt: None }
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn duplicates_wait_for_the_running_execution() {
+        let (in_flight, executions) = (InFlight::default(), AtomicUsize::new(0));
+        let delay = Duration::from_millis(20);
+
+        let (first, second, third) = tokio::join!(
+            submit(&in_flight, &executions, "tx", delay),
+            submit(&in_flight, &executions, "tx", delay),
+            submit(&in_flight, &executions, "tx", delay)
+        );
+        let responses = [first, second, third];
+
+        assert_eq!(executions.load(Ordering::SeqCst), 1);
+        assert!(responses.iter().all(|response| response.message == "execution 1"));
+        assert!(in_flight.pending.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn distinct_digests_execute_separately() {
+        let (in_flight, executions) = (InFlight::default(), AtomicUsize::new(0));
+        let delay = Duration::from_millis(20);
+
+        tokio::join!(
+            submit(&in_flight, &executions, "tx-1", delay),
+            submit(&in_flight, &executions, "tx-2", delay)
+        );
+
+        assert_eq!(executions.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn a_duplicate_takes_over_from_a_cancelled_leader() {
+        let (in_flight, executions) = (InFlight::default(), AtomicUsize::new(0));
+
+        let cancelled = tokio::time::timeout(
+            Duration::from_millis(10),
+            submit(&in_flight, &executions, "tx", Duration::from_secs(60)),
+        );
+        let (cancelled, follower) = tokio::join!(cancelled, async {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            submit(&in_flight, &executions, "tx", Duration::ZERO).await
+        });
+
+        assert!(cancelled.is_err());
+        assert_eq!(follower.message, "execution 2");
+        assert!(in_flight.pending.lock().unwrap().is_empty());
+    }
+}
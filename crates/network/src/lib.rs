@@ -1,14 +1,32 @@
+mod extract;
+mod inflight;
+
+pub use extract::{ApiJson, ApiJsonRejection, ErrorResponse};
+
+use inflight::InFlight;
+
 use anyhow::Result;
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::Json,
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
     routing::{get, post},
     Router,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use sui_core::messages::ExecutionRequest;
+use tower_http::compression::CompressionLayer;
+use sui_core::{
+    committee::Committee,
+    messages::{ExecutionRequest, ExecutionStatus, TransactionReceipt, TxLogEntry, TxLogFilter},
+    transaction::TransactionDigest,
+};
+
+/// Most digests accepted by a single `/get_effects_batch` request.
+pub const MAX_EFFECTS_BATCH_SIZE: usize = 100;
+
+/// Most transactions accepted by a single `/submit_atomic_batch` request.
+pub const MAX_ATOMIC_BATCH_SIZE: usize = 100;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubmitTransactionRequest {
@@ -19,6 +37,43 @@ pub struct SubmitTransactionRequest {
 pub struct SubmitTransactionResponse {
     pub accepted: bool,
     pub message: String,
+    #[serde(default)]
+    pub receipt: Option<TransactionReceipt>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitAtomicBatchRequest {
+    pub transactions: Vec<ExecutionRequest>,
+}
+
+/// Either every transaction of the batch was committed, with one receipt
+/// each in batch order, or none was.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitAtomicBatchResponse {
+    pub committed: bool,
+    pub message: String,
+    #[serde(default)]
+    pub receipts: Vec<TransactionReceipt>,
+}
+
+/// Query parameters of `/dry_run`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct DryRunParams {
+    /// Return an instruction-level execution trace.
+    #[serde(default)]
+    pub trace: bool,
+}
+
+/// What a transaction would do, computed without committing anything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DryRunResponse {
+    pub status: ExecutionStatus,
+    pub gas_used: u64,
+    pub logs: Vec<String>,
+    pub return_values: Vec<serde_json::Value>,
+    /// Only present when a trace was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,13 +87,47 @@ pub struct GetObjectResponse {
     pub object: Option<serde_json::Value>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetEffectsBatchRequest {
+    pub digests: Vec<TransactionDigest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectsLookup {
+    pub digest: TransactionDigest,
+    pub found: bool,
+    pub effects: Option<serde_json::Value>,
+}
+
+/// One lookup per requested digest, in request order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetEffectsBatchResponse {
+    pub results: Vec<EffectsLookup>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryTransactionsResponse {
+    pub entries: Vec<TxLogEntry>,
+}
+
 pub struct NetworkServer {
     port: u16,
+    compression: bool,
 }
 
 impl NetworkServer {
     pub fn new(port: u16) -> Self {
-        Self { port }
+        Self {
+            port,
+            compression: false,
+        }
+    }
+
+    /// Compresses responses with gzip or brotli when the client's
+    /// `Accept-Encoding` allows it.
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.compression = enabled;
+        self
     }
 
     pub async fn start<H>(self, handler: H) -> Result<()>
@@ -47,13 +136,25 @@ impl NetworkServer {
     {
         let app_state = AppState {
             handler: Arc::new(handler),
+            in_flight: Arc::new(InFlight::default()),
         };
 
         let app = Router::new()
             .route("/health", get(health_check))
+            .route("/ready", get(readiness_check))
             .route("/submit_transaction", post(submit_transaction))
+            .route("/submit_atomic_batch", post(submit_atomic_batch))
+            .route("/dry_run", post(dry_run))
             .route("/get_object", post(get_object))
+            .route("/get_effects_batch", post(get_effects_batch))
+            .route("/committee", get(get_committee))
+            .route("/transactions", get(query_transactions))
             .with_state(app_state);
+        let app = if self.compression {
+            app.layer(CompressionLayer::new())
+        } else {
+            app
+        };
 
         let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", self.port)).await?;
         println!("Network server listening on port {}", self.port);
@@ -66,36 +167,142 @@ impl NetworkServer {
 pub trait TransactionHandler: Send + Sync {
     async fn handle_transaction(&self, request: ExecutionRequest) -> Result<SubmitTransactionResponse>;
     async fn get_object(&self, object_id: &str) -> Result<Option<serde_json::Value>>;
+    async fn get_committee(&self) -> Result<Committee>;
+    async fn get_effects(&self, digest: &TransactionDigest) -> Result<Option<serde_json::Value>>;
+
+    /// Processed transactions matching `filter`. Handlers that keep no
+    /// transaction log return nothing.
+    async fn query_transactions(&self, _filter: &TxLogFilter) -> Result<Vec<TxLogEntry>> {
+        Ok(Vec::new())
+    }
+
+    /// Commits every transaction of `requests` or none of them. Handlers
+    /// without atomic batches fail.
+    async fn handle_atomic_batch(&self, _requests: Vec<ExecutionRequest>) -> Result<SubmitAtomicBatchResponse> {
+        Err(anyhow::anyhow!("atomic batches are not supported"))
+    }
+
+    /// Executes `request` without committing it, tracing every instruction
+    /// when `trace` is set. Handlers that cannot dry-run fail.
+    async fn dry_run(&self, _request: ExecutionRequest, _trace: bool) -> Result<DryRunResponse> {
+        Err(anyhow::anyhow!("dry runs are not supported"))
+    }
+
+    /// Whether the node has finished starting up and may take traffic.
+    fn is_ready(&self) -> bool {
+        true
+    }
+
+    /// When the node is too busy to admit more transactions, how many seconds
+    /// clients should wait before retrying.
+    fn retry_after_secs(&self) -> Option<u64> {
+        None
+    }
 }
 
 #[derive(Clone)]
 struct AppState {
     handler: Arc<dyn TransactionHandler>,
+    in_flight: Arc<InFlight>,
 }
 
 async fn health_check() -> Json<serde_json::Value> {
     Json(serde_json::json!({ "status": "ok" }))
 }
 
+/// Unlike `/health`, reports 503 until the handler has finished warming up.
+async fn readiness_check(State(state): State<AppState>) -> (StatusCode, Json<serde_json::Value>) {
+    if state.handler.is_ready() {
+        (StatusCode::OK, Json(serde_json::json!({ "status": "ready" })))
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "status": "warming_up" })),
+        )
+    }
+}
+
 async fn submit_transaction(
     State(state): State<AppState>,
-    Json(payload): Json<SubmitTransactionRequest>,
-) -> Result<Json<SubmitTransactionResponse>, StatusCode> {
-    match state.handler.handle_transaction(payload.transaction).await {
-        Ok(response) => Ok(Json(response)),
+    ApiJson(payload): ApiJson<SubmitTransactionRequest>,
+) -> Result<Json<SubmitTransactionResponse>, Response> {
+    if !state.handler.is_ready() {
+        return Err(StatusCode::SERVICE_UNAVAILABLE.into_response());
+    }
+    if let Some(secs) = state.handler.retry_after_secs() {
+        return Err((StatusCode::SERVICE_UNAVAILABLE, [(header::RETRY_AFTER, secs.to_string())]).into_response());
+    }
+
+    // Concurrent retries of the same digest share a single execution.
+    let digest = payload.transaction.digest.clone();
+    let handler = Arc::clone(&state.handler);
+    let response = state
+        .in_flight
+        .run(digest, || async move {
+            match handler.handle_transaction(payload.transaction).await {
+                Ok(response) => response,
+                Err(e) => {
+                    eprintln!("Error handling transaction: {}", e);
+                    SubmitTransactionResponse {
+                        accepted: false,
+                        message: format!("Error: {}", e),
+                        receipt: None,
+                    }
+                }
+            }
+        })
+        .await;
+    Ok(Json(response))
+}
+
+/// Unlike `/submit_transaction`, the batch is not deduplicated against
+/// concurrent retries; its transactions' digests guard against replays.
+async fn submit_atomic_batch(
+    State(state): State<AppState>,
+    ApiJson(payload): ApiJson<SubmitAtomicBatchRequest>,
+) -> Result<Json<SubmitAtomicBatchResponse>, Response> {
+    if payload.transactions.len() > MAX_ATOMIC_BATCH_SIZE {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE.into_response());
+    }
+    if !state.handler.is_ready() {
+        return Err(StatusCode::SERVICE_UNAVAILABLE.into_response());
+    }
+    if let Some(secs) = state.handler.retry_after_secs() {
+        return Err((StatusCode::SERVICE_UNAVAILABLE, [(header::RETRY_AFTER, secs.to_string())]).into_response());
+    }
+
+    let response = match state.handler.handle_atomic_batch(payload.transactions).await {
+        Ok(response) => response,
         Err(e) => {
-            eprintln!("Error handling transaction: {}", e);
-            Ok(Json(SubmitTransactionResponse {
-                accepted: false,
+            eprintln!("Error handling atomic batch: {}", e);
+            SubmitAtomicBatchResponse {
+                committed: false,
                 message: format!("Error: {}", e),
-            }))
+                receipts: Vec::new(),
+            }
+        }
+    };
+    Ok(Json(response))
+}
+
+/// Pass `?trace=true` for an instruction-level trace of Move calls.
+async fn dry_run(
+    State(state): State<AppState>,
+    Query(params): Query<DryRunParams>,
+    ApiJson(payload): ApiJson<SubmitTransactionRequest>,
+) -> Result<Json<DryRunResponse>, StatusCode> {
+    match state.handler.dry_run(payload.transaction, params.trace).await {
+        Ok(response) => Ok(Json(response)),
+        Err(e) => {
+            eprintln!("Error dry-running transaction: {}", e);
+            Err(StatusCode::UNPROCESSABLE_ENTITY)
         }
     }
 }
 
 async fn get_object(
     State(state): State<AppState>,
-    Json(payload): Json<GetObjectRequest>,
+    ApiJson(payload): ApiJson<GetObjectRequest>,
 ) -> Result<Json<GetObjectResponse>, StatusCode> {
     match state.handler.get_object(&payload.object_id).await {
         Ok(Some(obj)) => Ok(Json(GetObjectResponse {
@@ -113,6 +320,56 @@ async fn get_object(
     }
 }
 
+async fn get_effects_batch(
+    State(state): State<AppState>,
+    ApiJson(payload): ApiJson<GetEffectsBatchRequest>,
+) -> Result<Json<GetEffectsBatchResponse>, StatusCode> {
+    if payload.digests.len() > MAX_EFFECTS_BATCH_SIZE {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    let mut results = Vec::with_capacity(payload.digests.len());
+    for digest in payload.digests {
+        match state.handler.get_effects(&digest).await {
+            Ok(effects) => results.push(EffectsLookup {
+                digest,
+                found: effects.is_some(),
+                effects,
+            }),
+            Err(e) => {
+                eprintln!("Error getting effects: {}", e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+    }
+    Ok(Json(GetEffectsBatchResponse { results }))
+}
+
+async fn get_committee(State(state): State<AppState>) -> Result<Json<Committee>, StatusCode> {
+    match state.handler.get_committee().await {
+        Ok(committee) => Ok(Json(committee)),
+        Err(e) => {
+            eprintln!("Error getting committee: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Filters are passed as query parameters, e.g.
+/// `/transactions?signer=alice&status=failure`.
+async fn query_transactions(
+    State(state): State<AppState>,
+    Query(filter): Query<TxLogFilter>,
+) -> Result<Json<QueryTransactionsResponse>, StatusCode> {
+    match state.handler.query_transactions(&filter).await {
+        Ok(entries) => Ok(Json(QueryTransactionsResponse { entries })),
+        Err(e) => {
+            eprintln!("Error querying transactions: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 pub struct NetworkClient {
     base_url: String,
     client: reqwest::Client,
@@ -122,18 +379,96 @@ impl NetworkClient {
     pub fn new(base_url: impl Into<String>) -> Self {
         Self {
             base_url: base_url.into(),
-            client: reqwest::Client::new(),
+            // Advertises gzip/brotli in `Accept-Encoding` and decodes
+            // compressed responses transparently.
+            client: reqwest::Client::builder()
+                .gzip(true)
+                .brotli(true)
+                .build()
+                .expect("static client configuration is valid"),
         }
     }
 
     pub async fn submit_transaction(&self, request: ExecutionRequest) -> Result<SubmitTransactionResponse> {
         let url = format!("{}/submit_transaction", self.base_url);
         let payload = SubmitTransactionRequest { transaction: request };
-        let response = self.client.post(&url).json(&payload).send().await?;
+        let response = self
+            .client
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
         let result: SubmitTransactionResponse = response.json().await?;
         Ok(result)
     }
 
+    /// Like [`NetworkClient::submit_transaction`], but when the server
+    /// answers 503 waits for its `Retry-After` (1s if absent) and tries
+    /// again, up to `max_attempts` attempts in total.
+    pub async fn submit_transaction_with_retry(
+        &self,
+        request: ExecutionRequest,
+        max_attempts: usize,
+    ) -> Result<SubmitTransactionResponse> {
+        let url = format!("{}/submit_transaction", self.base_url);
+        let payload = SubmitTransactionRequest { transaction: request };
+        let mut attempt = 1;
+        loop {
+            let response = self.client.post(&url).json(&payload).send().await?;
+            if response.status() == reqwest::StatusCode::SERVICE_UNAVAILABLE && attempt < max_attempts {
+                let wait_secs = response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.parse::<u64>().ok())
+                    .unwrap_or(1);
+                tokio::time::sleep(std::time::Duration::from_secs(wait_secs)).await;
+                attempt += 1;
+                continue;
+            }
+            let result: SubmitTransactionResponse = response.error_for_status()?.json().await?;
+            return Ok(result);
+        }
+    }
+
+    /// Submits up to [`MAX_ATOMIC_BATCH_SIZE`] transactions to be committed
+    /// all together or not at all.
+    pub async fn submit_atomic_batch(&self, requests: Vec<ExecutionRequest>) -> Result<SubmitAtomicBatchResponse> {
+        let url = format!("{}/submit_atomic_batch", self.base_url);
+        let payload = SubmitAtomicBatchRequest { transactions: requests };
+        let response = self
+            .client
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
+        let result: SubmitAtomicBatchResponse = response.json().await?;
+        Ok(result)
+    }
+
+    pub async fn dry_run(&self, request: ExecutionRequest, trace: bool) -> Result<DryRunResponse> {
+        let url = format!("{}/dry_run", self.base_url);
+        let payload = SubmitTransactionRequest { transaction: request };
+        let response = self
+            .client
+            .post(&url)
+            .query(&DryRunParams { trace })
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
+        let result: DryRunResponse = response.json().await?;
+        Ok(result)
+    }
+
+    pub async fn is_ready(&self) -> Result<bool> {
+        let url = format!("{}/ready", self.base_url);
+        let response = self.client.get(&url).send().await?;
+        Ok(response.status().is_success())
+    }
+
     pub async fn get_object(&self, object_id: &str) -> Result<GetObjectResponse> {
         let url = format!("{}/get_object", self.base_url);
         let payload = GetObjectRequest {
@@ -143,5 +478,243 @@ impl NetworkClient {
         let result: GetObjectResponse = response.json().await?;
         Ok(result)
     }
+
+    /// Fetches effects for up to [`MAX_EFFECTS_BATCH_SIZE`] digests in one
+    /// round trip. Results are in the order of `digests`.
+    pub async fn get_effects_batch(&self, digests: Vec<TransactionDigest>) -> Result<Vec<EffectsLookup>> {
+        let url = format!("{}/get_effects_batch", self.base_url);
+        let payload = GetEffectsBatchRequest { digests };
+        let response = self
+            .client
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
+        let result: GetEffectsBatchResponse = response.json().await?;
+        Ok(result.results)
+    }
+
+    pub async fn query_transactions(&self, filter: &TxLogFilter) -> Result<Vec<TxLogEntry>> {
+        let url = format!("{}/transactions", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .query(filter)
+            .send()
+            .await?
+            .error_for_status()?;
+        let result: QueryTransactionsResponse = response.json().await?;
+        Ok(result.entries)
+    }
+
+    pub async fn get_committee(&self) -> Result<Committee> {
+        let url = format!("{}/committee", self.base_url);
+        let response = self.client.get(&url).send().await?.error_for_status()?;
+        let result: Committee = response.json().await?;
+        Ok(result)
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Serves one large object and nothing else, and accepts every
+    /// transaction after a short delay, counting executions.
+    #[derive(Clone, Default)]
+    struct StaticHandler {
+        ready: Arc<AtomicBool>,
+        executions: Arc<AtomicUsize>,
+    }
+
+    fn large_object() -> serde_json::Value {
+        let entries: Vec<_> = (0..500)
+            .map(|i| serde_json::json!({ "id": format!("child-{}", i), "owner": "alice", "balance": 100 }))
+            .collect();
+        serde_json::json!({ "id": "listing", "children": entries })
+    }
+
+    #[async_trait::async_trait]
+    impl TransactionHandler for StaticHandler {
+        async fn handle_transaction(&self, request: ExecutionRequest) -> Result<SubmitTransactionResponse> {
+            let execution = self.executions.fetch_add(1, Ordering::SeqCst) + 1;
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok(SubmitTransactionResponse {
+                accepted: true,
+                message: format!("accepted {} in execution {}", request.digest.0, execution),
+                receipt: None,
+            })
+        }
+
+        async fn get_object(&self, object_id: &str) -> Result<Option<serde_json::Value>> {
+            Ok((object_id == "listing").then(large_object))
+        }
+
+        async fn get_committee(&self) -> Result<Committee> {
+            Ok(Committee::new(0, Vec::new()))
+        }
+
+        async fn get_effects(&self, _digest: &TransactionDigest) -> Result<Option<serde_json::Value>> {
+            Ok(None)
+        }
+
+        fn is_ready(&self) -> bool {
+            self.ready.load(Ordering::SeqCst)
+        }
+    }
+
+    /// Starts `server` on a free local port and returns its base URL once it
+    /// answers health checks.
+    async fn serve(server: NetworkServer, handler: StaticHandler) -> String {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .unwrap()
+            .port();
+        let server = NetworkServer { port, ..server };
+        tokio::spawn(server.start(handler));
+
+        let base_url = format!("http://127.0.0.1:{}", port);
+        for _ in 0..100 {
+            if reqwest::get(format!("{}/health", base_url)).await.is_ok() {
+                return base_url;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("server on port {} never came up", port);
+    }
+
+    #[tokio::test]
+    async fn large_response_is_compressed_when_accepted() {
+        let base_url = serve(NetworkServer::new(0).with_compression(true), StaticHandler::default()).await;
+        let raw = reqwest::Client::builder().no_gzip().no_brotli().build().unwrap();
+        let fetch = |encoding: &'static str| {
+            raw.post(format!("{}/get_object", base_url))
+                .header(reqwest::header::ACCEPT_ENCODING, encoding)
+                .json(&GetObjectRequest { object_id: "listing".to_string() })
+                .send()
+        };
+
+        let plain = fetch("identity").await.unwrap();
+        assert!(plain.headers().get(reqwest::header::CONTENT_ENCODING).is_none());
+        let plain_len = plain.bytes().await.unwrap().len();
+        let gzipped = fetch("gzip").await.unwrap();
+        assert_eq!(gzipped.headers()[reqwest::header::CONTENT_ENCODING], "gzip");
+        assert!(gzipped.bytes().await.unwrap().len() < plain_len);
+
+        let response = NetworkClient::new(base_url).get_object("listing").await.unwrap();
+        assert_eq!(response.object, Some(large_object()));
+    }
+
+    #[tokio::test]
+    async fn responses_are_not_compressed_unless_enabled() {
+        let base_url = serve(NetworkServer::new(0), StaticHandler::default()).await;
+        let raw = reqwest::Client::builder().no_gzip().no_brotli().build().unwrap();
+
+        let response = raw
+            .post(format!("{}/get_object", base_url))
+            .header(reqwest::header::ACCEPT_ENCODING, "gzip, br")
+            .json(&GetObjectRequest { object_id: "listing".to_string() })
+            .send()
+            .await
+            .unwrap();
+
+        assert!(response.headers().get(reqwest::header::CONTENT_ENCODING).is_none());
+    }
+
+    #[tokio::test]
+    async fn readiness_gates_submissions() {
+        let handler = StaticHandler::default();
+        let base_url = serve(NetworkServer::new(0), handler.clone()).await;
+        let client = NetworkClient::new(base_url.clone());
+        let request = || ExecutionRequest {
+            tx: sui_core::transaction::SignedTransaction::new_transfer("alice".to_string(), "bob".to_string(), "coin".to_string()),
+            digest: TransactionDigest::random(),
+        };
+
+        assert!(!client.is_ready().await.unwrap());
+        let refused = reqwest::Client::new()
+            .post(format!("{}/submit_transaction", base_url))
+            .json(&SubmitTransactionRequest { transaction: request() })
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(refused.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+
+        handler.ready.store(true, Ordering::SeqCst);
+
+        assert!(client.is_ready().await.unwrap());
+        assert!(client.submit_transaction(request()).await.unwrap().accepted);
+    }
+
+    #[tokio::test]
+    async fn malformed_bodies_are_rejected_with_the_offending_field() {
+        let handler = StaticHandler::default();
+        handler.ready.store(true, Ordering::SeqCst);
+        let base_url = serve(NetworkServer::new(0), handler).await;
+        let transaction = ExecutionRequest {
+            tx: sui_core::transaction::SignedTransaction::new_transfer("alice".to_string(), "bob".to_string(), "coin".to_string()),
+            digest: TransactionDigest::random(),
+        };
+        let mut unknown_kind = serde_json::to_value(SubmitTransactionRequest { transaction }).unwrap();
+        unknown_kind["transaction"]["tx"]["payload"]["kind"] = serde_json::json!({ "Teleport": {} });
+
+        for (body, mentions) in [(serde_json::json!({}), "transaction"), (unknown_kind, "Teleport")] {
+            let response = reqwest::Client::new()
+                .post(format!("{}/submit_transaction", base_url))
+                .json(&body)
+                .send()
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), reqwest::StatusCode::UNPROCESSABLE_ENTITY);
+            let error: ErrorResponse = response.json().await.unwrap();
+            assert_eq!(error.error, "invalid_request_body");
+            assert!(error.details.contains(mentions), "{}", error.details);
+        }
+    }
+
+    #[tokio::test]
+    async fn invalid_json_is_reported_as_malformed() {
+        let base_url = serve(NetworkServer::new(0), StaticHandler::default()).await;
+
+        let response = reqwest::Client::new()
+            .post(format!("{}/get_object", base_url))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body("{\"object_id\": ")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(error.error, "malformed_json");
+    }
+
+    #[tokio::test]
+    async fn concurrent_duplicate_submissions_share_one_execution() {
+        let handler = StaticHandler::default();
+        handler.ready.store(true, Ordering::SeqCst);
+        let client = NetworkClient::new(serve(NetworkServer::new(0), handler.clone()).await);
+        let request = ExecutionRequest {
+            tx: sui_core::transaction::SignedTransaction::new_transfer("alice".to_string(), "bob".to_string(), "coin".to_string()),
+            digest: TransactionDigest::random(),
+        };
+
+        let (first, second) = tokio::join!(
+            client.submit_transaction(request.clone()),
+            client.submit_transaction(request.clone())
+        );
+
+        assert_eq!(handler.executions.load(Ordering::SeqCst), 1);
+        let (first, second) = (first.unwrap(), second.unwrap());
+        assert_eq!(first.message, second.message);
+        assert_eq!(first.accepted, second.accepted);
+
+        // Once the first execution has finished, a resubmission runs again.
+        client.submit_transaction(request).await.unwrap();
+        assert_eq!(handler.executions.load(Ordering::SeqCst), 2);
+    }
+}
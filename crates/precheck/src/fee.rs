@@ -0,0 +1,51 @@
+use std::sync::Mutex;
+
+/// Largest per-checkpoint base fee change, as a fraction of the fee: 1/8.
+const BASE_FEE_CHANGE_DENOMINATOR: u64 = 8;
+
+/// EIP-1559-style base fee. After every checkpoint the fee moves towards
+/// keeping checkpoints at `target_transactions`: up when fuller, down when
+/// emptier, by at most an eighth per checkpoint, never below `min_base_fee`.
+#[derive(Debug)]
+pub struct FeeModel {
+    target_transactions: usize,
+    min_base_fee: u64,
+    base_fee: Mutex<u64>,
+}
+
+impl FeeModel {
+    pub fn new(target_transactions: usize, min_base_fee: u64) -> Self {
+        Self {
+            target_transactions: target_transactions.max(1),
+            min_base_fee,
+            base_fee: Mutex::new(min_base_fee),
+        }
+    }
+
+    /// Lowest `gas_price` currently accepted.
+    pub fn base_fee(&self) -> u64 {
+        *self.base_fee.lock().unwrap()
+    }
+
+    pub fn target_transactions(&self) -> usize {
+        self.target_transactions
+    }
+
+    /// Adjusts the base fee for a checkpoint holding `transaction_count`
+    /// transactions and returns the new fee.
+    pub fn record_checkpoint(&self, transaction_count: usize) -> u64 {
+        let target = self.target_transactions as u64;
+        let count = transaction_count as u64;
+        let mut base_fee = self.base_fee.lock().unwrap();
+
+        *base_fee = if count > target {
+            // Always move by at least 1 so a zero or tiny fee can still rise.
+            let delta = (*base_fee * (count - target) / target / BASE_FEE_CHANGE_DENOMINATOR).max(1);
+            base_fee.saturating_add(delta)
+        } else {
+            let delta = *base_fee * (target - count) / target / BASE_FEE_CHANGE_DENOMINATOR;
+            base_fee.saturating_sub(delta).max(self.min_base_fee)
+        };
+        *base_fee
+    }
+}
@@ -1,4 +1,15 @@
-use sui_core::{messages::ExecutionRequest, transaction::TransactionKind};
+mod fee;
+mod sequence;
+
+pub use fee::FeeModel;
+pub use sequence::{InMemorySequenceStore, SequenceStore};
+
+use std::sync::Arc;
+use sui_core::{
+    messages::ExecutionRequest,
+    object::{ObjectData, SuiObject},
+    transaction::TransactionKind,
+};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -9,6 +20,24 @@ pub enum PreCheckError {
     MissingRecipient,
     #[error("move call is missing target module or function")]
     InvalidCall,
+    #[error("object {0} is not a Move struct and cannot be migrated")]
+    NotMigratable(String),
+    #[error("batch must contain at least one command and cannot be nested")]
+    InvalidBatch,
+    #[error("airdrop needs at least one recipient, each with a non-empty address and a positive amount")]
+    InvalidAirdrop,
+    #[error("object data is {size} bytes, over the {max}-byte limit")]
+    ObjectTooLarge { size: usize, max: usize },
+    #[error("input {object} is stale: expected version {expected}, current version is {current}")]
+    StaleInput { object: String, expected: u64, current: u64 },
+    #[error("bad sequence number: expected {expected}, got {got}")]
+    BadSequence { expected: u64, got: u64 },
+    #[error("gas price {offered} is below the current base fee {base_fee}")]
+    GasPriceTooLow { offered: u64, base_fee: u64 },
+    #[error("complexity score {score} is over the limit of {max}")]
+    TooComplex { score: u64, max: u64 },
+    #[error("gas coin {coin} cannot cover the budget: {required} required, {available} available")]
+    InsufficientGas { coin: String, required: u64, available: u64 },
 }
 
 #[derive(Debug, Clone)]
@@ -17,33 +46,474 @@ pub struct PreCheckReport {
     pub requires_shared_lock: bool,
 }
 
-#[derive(Default)]
-pub struct PreCheckPipeline;
+#[derive(Clone, Default)]
+pub struct PreCheckPipeline {
+    sequences: Option<Arc<dyn SequenceStore>>,
+    fee_model: Option<Arc<FeeModel>>,
+    max_object_size: Option<usize>,
+    max_complexity: Option<u64>,
+}
 
 impl PreCheckPipeline {
+    /// Enforces per-signer sequence numbers: each signer's transactions must
+    /// carry 1, 2, 3, ... in order, so replays and gaps are rejected. The
+    /// pipeline only checks the number; it is consumed by whoever commits
+    /// the transaction, calling `SequenceStore::advance`.
+    pub fn with_sequence_store(mut self, store: Arc<dyn SequenceStore>) -> Self {
+        self.sequences = Some(store);
+        self
+    }
+
+    /// Rejects transactions whose gas price is below the model's base fee.
+    pub fn with_fee_model(mut self, fee_model: Arc<FeeModel>) -> Self {
+        self.fee_model = Some(fee_model);
+        self
+    }
+
+    /// Rejects transactions that would write object data larger than
+    /// `max_bytes` once serialized. Should match the store's own limit.
+    pub fn with_max_object_size(mut self, max_bytes: usize) -> Self {
+        self.max_object_size = Some(max_bytes);
+        self
+    }
+
+    /// Rejects transactions whose `ExecutionRequest::complexity_score` is
+    /// above `max`.
+    pub fn with_max_complexity(mut self, max: u64) -> Self {
+        self.max_complexity = Some(max);
+        self
+    }
+
     pub fn run(&self, request: &ExecutionRequest) -> Result<PreCheckReport, PreCheckError> {
+        self.run_after(request, &[])
+    }
+
+    /// Like [`run`](Self::run), for a transaction that will commit right
+    /// after `preceding`, which have passed their own checks but not yet
+    /// committed: each of them from the same signer takes up one sequence
+    /// number first.
+    pub fn run_after(
+        &self,
+        request: &ExecutionRequest,
+        preceding: &[ExecutionRequest],
+    ) -> Result<PreCheckReport, PreCheckError> {
         let payload = &request.tx.payload;
 
-        if payload.gas_budget == 0 {
+        // Read-only transactions are never charged, so neither the budget
+        // nor the price matters.
+        if payload.gas_budget == 0 && !payload.read_only {
             return Err(PreCheckError::InvalidGasBudget);
         }
 
+        if let Some(fee_model) = self.fee_model.as_ref().filter(|_| !payload.read_only) {
+            let base_fee = fee_model.base_fee();
+            if payload.gas_price < base_fee {
+                return Err(PreCheckError::GasPriceTooLow {
+                    offered: payload.gas_price,
+                    base_fee,
+                });
+            }
+        }
+
         let (is_move_call, requires_shared_lock) = match &payload.kind {
+            TransactionKind::Batch { commands } => {
+                if commands.is_empty() {
+                    return Err(PreCheckError::InvalidBatch);
+                }
+                let mut flags = (false, false);
+                for command in commands {
+                    let (is_call, shared) = Self::check_command(command)?;
+                    flags = (flags.0 || is_call, flags.1 || shared);
+                }
+                flags
+            }
+            command => Self::check_command(command)?,
+        };
+
+        if let Some(max) = self.max_object_size {
+            Self::check_object_sizes(&payload.kind, max)?;
+        }
+        if let Some(max) = self.max_complexity {
+            let score = request.complexity_score();
+            if score > max {
+                return Err(PreCheckError::TooComplex { score, max });
+            }
+        }
+
+        if let Some(sequences) = &self.sequences {
+            let signer = &request.tx.signer;
+            let pending = preceding.iter().filter(|earlier| earlier.tx.signer == *signer).count() as u64;
+            let expected = sequences.next_sequence(signer) + pending;
+            let got = payload.sequence_number;
+            if got != expected {
+                return Err(PreCheckError::BadSequence { expected, got });
+            }
+        }
+
+        Ok(PreCheckReport { is_move_call, requires_shared_lock })
+    }
+
+    /// Object data supplied in the transaction itself, i.e. migrated struct
+    /// fields, must fit within `max` bytes.
+    fn check_object_sizes(kind: &TransactionKind, max: usize) -> Result<(), PreCheckError> {
+        match kind {
+            TransactionKind::Migrate { new_fields, .. } => {
+                let size = serde_json::to_vec(new_fields).map(|bytes| bytes.len()).unwrap_or(0);
+                if size > max {
+                    return Err(PreCheckError::ObjectTooLarge { size, max });
+                }
+                Ok(())
+            }
+            TransactionKind::Batch { commands } => commands
+                .iter()
+                .try_for_each(|command| Self::check_object_sizes(command, max)),
+            TransactionKind::Conditional { then, .. } => Self::check_object_sizes(then, max),
+            _ => Ok(()),
+        }
+    }
+
+    /// Validates a single non-batch command, returning whether it is a Move
+    /// call and whether it touches shared state.
+    fn check_command(kind: &TransactionKind) -> Result<(bool, bool), PreCheckError> {
+        match kind {
             TransactionKind::Transfer { recipient, .. } => {
                 if recipient.trim().is_empty() {
                     return Err(PreCheckError::MissingRecipient);
                 }
-                (false, false)
+                Ok((false, false))
             }
             TransactionKind::Call { module, function, .. } => {
                 if module.is_empty() || function.is_empty() {
                     return Err(PreCheckError::InvalidCall);
                 }
-                (true, true)
+                Ok((true, true))
             }
-        };
+            TransactionKind::Airdrop { distribution, .. } => {
+                let invalid = distribution.is_empty()
+                    || distribution
+                        .iter()
+                        .any(|(recipient, amount)| recipient.trim().is_empty() || *amount == 0);
+                if invalid {
+                    return Err(PreCheckError::InvalidAirdrop);
+                }
+                Ok((false, false))
+            }
+            TransactionKind::Migrate { .. }
+            | TransactionKind::TransferToSender { .. }
+            | TransactionKind::ChangeOwner { .. }
+            | TransactionKind::Wrap { .. }
+            | TransactionKind::Unwrap { .. } => Ok((false, false)),
+            TransactionKind::Conditional { then, .. } => Self::check_command(then),
+            TransactionKind::Batch { .. } => Err(PreCheckError::InvalidBatch),
+        }
+    }
 
-        Ok(PreCheckReport { is_move_call, requires_shared_lock })
+    /// Checks that need the current state of the transaction's declared
+    /// inputs, run once the validator has loaded them.
+    pub fn check_input_objects(
+        &self,
+        request: &ExecutionRequest,
+        inputs: &[SuiObject],
+    ) -> Result<(), PreCheckError> {
+        for declared in &request.tx.payload.input_objects {
+            let Some(expected) = declared.version else {
+                continue;
+            };
+            if let Some(current) = inputs.iter().find(|input| input.id == declared.id) {
+                if current.version != expected {
+                    return Err(PreCheckError::StaleInput {
+                        object: declared.id.0.clone(),
+                        expected,
+                        current: current.version,
+                    });
+                }
+            }
+        }
+
+        let payload = &request.tx.payload;
+        if let Some(coin) = &payload.gas_payment {
+            if let Some(current) = inputs.iter().find(|input| &input.id == coin) {
+                let required = payload.gas_budget.saturating_mul(payload.gas_price);
+                let available = match current.data {
+                    ObjectData::Coin { balance } => balance,
+                    _ => 0,
+                };
+                if available < required {
+                    return Err(PreCheckError::InsufficientGas {
+                        coin: coin.0.clone(),
+                        required,
+                        available,
+                    });
+                }
+            }
+        }
+
+        if let TransactionKind::Migrate { object, .. } = &request.tx.payload.kind {
+            if let Some(target) = inputs.iter().find(|input| &input.id == object) {
+                if !matches!(target.data, ObjectData::MoveStruct { .. }) {
+                    return Err(PreCheckError::NotMigratable(object.0.clone()));
+                }
+            }
+        }
+        Ok(())
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use sui_core::object::{ObjectID, Owner};
+    use sui_core::transaction::{InputObject, SignedTransaction, TransactionDigest, TransactionPayload};
+
+    fn request(kind: TransactionKind) -> ExecutionRequest {
+        let payload = TransactionPayload {
+            kind,
+            gas_budget: 1_000,
+            input_objects: Vec::new(),
+            sequence_number: 0,
+            gas_price: 0,
+            read_only: false,
+            gas_payment: None,
+        };
+        ExecutionRequest {
+            tx: SignedTransaction::new("alice".to_string(), payload),
+            digest: TransactionDigest::random(),
+        }
+    }
+
+    fn object(id: &str, data: ObjectData) -> SuiObject {
+        SuiObject::new(ObjectID::new(id), Owner::Address("alice".to_string()), data)
+    }
+
+    fn migrate(id: &str) -> ExecutionRequest {
+        request(TransactionKind::Migrate { object: ObjectID::new(id), new_fields: json!({ "level": 2 }) })
+    }
+
+    #[test]
+    fn migrating_a_coin_is_rejected() {
+        let pipeline = PreCheckPipeline::default();
+        let coin = object("coin", ObjectData::Coin { balance: 5 });
+
+        let result = pipeline.check_input_objects(&migrate("coin"), &[coin]);
+
+        assert!(matches!(result, Err(PreCheckError::NotMigratable(id)) if id == "coin"));
+    }
+
+    #[test]
+    fn migrating_a_struct_passes() {
+        let pipeline = PreCheckPipeline::default();
+        let profile = object(
+            "hero",
+            ObjectData::MoveStruct { type_name: "game::Profile".to_string(), fields: json!({ "level": 1 }) },
+        );
+
+        assert!(pipeline.run(&migrate("hero")).is_ok());
+        assert!(pipeline.check_input_objects(&migrate("hero"), &[profile]).is_ok());
+    }
+
+    fn migrate_with(fields: serde_json::Value) -> ExecutionRequest {
+        request(TransactionKind::Migrate { object: ObjectID::new("hero"), new_fields: fields })
+    }
+
+    #[test]
+    fn migrated_fields_within_the_size_limit_pass() {
+        let pipeline = PreCheckPipeline::default().with_max_object_size(1_024);
+
+        assert!(pipeline.run(&migrate_with(json!({ "level": 2 }))).is_ok());
+    }
+
+    #[test]
+    fn oversized_migrated_fields_are_rejected() {
+        let pipeline = PreCheckPipeline::default().with_max_object_size(1_024);
+        let oversized = json!({ "bio": "x".repeat(2_000) });
+        let batch = request(TransactionKind::Batch { commands: vec![migrate_with(oversized.clone()).tx.payload.kind] });
+
+        let direct = pipeline.run(&migrate_with(oversized));
+
+        assert!(matches!(direct, Err(PreCheckError::ObjectTooLarge { size, max: 1_024 }) if size > 2_000));
+        assert!(matches!(pipeline.run(&batch), Err(PreCheckError::ObjectTooLarge { .. })));
+    }
+
+    #[test]
+    fn transactions_over_the_complexity_limit_are_rejected() {
+        let pipeline = PreCheckPipeline::default().with_max_complexity(50);
+        let transfer = || TransactionKind::Transfer { object: ObjectID::new("coin"), recipient: "bob".to_string() };
+        let simple = request(transfer());
+        let batch = request(TransactionKind::Batch { commands: (0..10).map(|_| transfer()).collect() });
+
+        assert!(pipeline.run(&simple).is_ok());
+        let score = batch.complexity_score();
+        assert!(matches!(pipeline.run(&batch), Err(PreCheckError::TooComplex { score: s, max: 50 }) if s == score));
+        assert!(PreCheckPipeline::default().run(&batch).is_ok());
+    }
+
+    fn pinned(version: u64) -> ExecutionRequest {
+        let mut request = request(TransactionKind::Transfer { object: ObjectID::new("coin"), recipient: "bob".to_string() });
+        let input = InputObject { id: ObjectID::new("coin"), mutable: true, version: Some(version) };
+        request.tx.payload.input_objects = vec![input];
+        request
+    }
+
+    #[test]
+    fn input_pinned_at_its_current_version_passes() {
+        let mut coin = object("coin", ObjectData::Coin { balance: 5 });
+        coin.version = 3;
+
+        assert!(PreCheckPipeline::default().check_input_objects(&pinned(3), &[coin]).is_ok());
+    }
+
+    #[test]
+    fn input_pinned_at_an_outdated_version_is_rejected() {
+        let mut coin = object("coin", ObjectData::Coin { balance: 5 });
+        coin.version = 3;
+
+        let result = PreCheckPipeline::default().check_input_objects(&pinned(2), &[coin]);
+
+        assert!(matches!(
+            result,
+            Err(PreCheckError::StaleInput { object, expected: 2, current: 3 }) if object == "coin"
+        ));
+    }
+
+    fn sequenced(sequence_number: u64) -> ExecutionRequest {
+        let mut request = request(TransactionKind::Transfer { object: ObjectID::new("coin"), recipient: "bob".to_string() });
+        request.tx.payload.sequence_number = sequence_number;
+        request
+    }
+
+    fn sequenced_pipeline() -> (PreCheckPipeline, Arc<InMemorySequenceStore>) {
+        let store = Arc::new(InMemorySequenceStore::new());
+        (PreCheckPipeline::default().with_sequence_store(store.clone()), store)
+    }
+
+    /// Checks `request` and commits it, as the validator would.
+    fn commit(pipeline: &PreCheckPipeline, store: &InMemorySequenceStore, request: &ExecutionRequest) {
+        pipeline.run(request).unwrap();
+        store
+            .advance(&request.tx.signer, request.tx.payload.sequence_number)
+            .unwrap();
+    }
+
+    #[test]
+    fn sequences_in_order_are_accepted() {
+        let (pipeline, store) = sequenced_pipeline();
+
+        for sequence in 1..=3 {
+            commit(&pipeline, &store, &sequenced(sequence));
+        }
+    }
+
+    #[test]
+    fn sequence_gap_is_rejected() {
+        let (pipeline, store) = sequenced_pipeline();
+        commit(&pipeline, &store, &sequenced(1));
+
+        let result = pipeline.run(&sequenced(3));
+
+        assert!(matches!(result, Err(PreCheckError::BadSequence { expected: 2, got: 3 })));
+        assert!(pipeline.run(&sequenced(2)).is_ok());
+    }
+
+    #[test]
+    fn replayed_sequence_is_rejected() {
+        let (pipeline, store) = sequenced_pipeline();
+        commit(&pipeline, &store, &sequenced(1));
+
+        let result = pipeline.run(&sequenced(1));
+
+        assert!(matches!(result, Err(PreCheckError::BadSequence { expected: 2, got: 1 })));
+    }
+
+    #[test]
+    fn rejected_transaction_keeps_its_sequence_number() {
+        let (pipeline, _store) = sequenced_pipeline();
+        let mut unfunded = sequenced(1);
+        unfunded.tx.payload.gas_budget = 0;
+
+        assert!(matches!(pipeline.run(&unfunded), Err(PreCheckError::InvalidGasBudget)));
+        assert!(pipeline.run(&sequenced(1)).is_ok());
+    }
+
+    #[test]
+    fn checking_does_not_consume_the_sequence_number() {
+        let (pipeline, store) = sequenced_pipeline();
+
+        assert!(pipeline.run(&sequenced(1)).is_ok());
+        assert!(pipeline.run(&sequenced(1)).is_ok());
+        assert_eq!(store.last_sequence("alice"), None);
+    }
+
+    #[test]
+    fn preceding_transactions_take_their_sequence_numbers_first() {
+        let (pipeline, _store) = sequenced_pipeline();
+        let first = sequenced(1);
+
+        assert!(pipeline.run_after(&sequenced(2), std::slice::from_ref(&first)).is_ok());
+        assert!(matches!(
+            pipeline.run_after(&sequenced(1), std::slice::from_ref(&first)),
+            Err(PreCheckError::BadSequence { expected: 2, got: 1 })
+        ));
+    }
+
+    fn priced(gas_price: u64) -> ExecutionRequest {
+        let mut request = request(TransactionKind::Transfer { object: ObjectID::new("coin"), recipient: "bob".to_string() });
+        request.tx.payload.gas_price = gas_price;
+        request
+    }
+
+    #[test]
+    fn base_fee_follows_load() {
+        let fee_model = Arc::new(FeeModel::new(10, 100));
+        let pipeline = PreCheckPipeline::default().with_fee_model(Arc::clone(&fee_model));
+        assert!(pipeline.run(&priced(100)).is_ok());
+
+        for _ in 0..3 {
+            fee_model.record_checkpoint(20);
+        }
+        let raised = fee_model.base_fee();
+        assert!(raised > 100);
+        assert!(matches!(
+            pipeline.run(&priced(100)),
+            Err(PreCheckError::GasPriceTooLow { offered: 100, base_fee }) if base_fee == raised
+        ));
+        assert!(pipeline.run(&priced(raised)).is_ok());
+
+        while fee_model.base_fee() > 100 {
+            fee_model.record_checkpoint(0);
+        }
+        assert!(pipeline.run(&priced(100)).is_ok());
+    }
+
+    #[test]
+    fn base_fee_never_falls_below_the_minimum() {
+        let fee_model = FeeModel::new(10, 100);
+
+        assert_eq!(fee_model.record_checkpoint(0), 100);
+        assert_eq!(fee_model.record_checkpoint(10), 100);
+        assert_eq!(fee_model.record_checkpoint(20), 112);
+    }
+
+    #[test]
+    fn read_only_transaction_needs_no_gas_budget() {
+        let pipeline = PreCheckPipeline::default();
+        let mut read_only = migrate("hero");
+        read_only.tx.payload.gas_budget = 0;
+        read_only.tx.payload.read_only = true;
+        let mut unbudgeted = migrate("hero");
+        unbudgeted.tx.payload.gas_budget = 0;
+
+        assert!(pipeline.run(&read_only).is_ok());
+        assert!(matches!(pipeline.run(&unbudgeted), Err(PreCheckError::InvalidGasBudget)));
+    }
+
+    #[test]
+    fn read_only_transactions_ignore_the_base_fee() {
+        let fee_model = Arc::new(FeeModel::new(1, 50));
+        let pipeline = PreCheckPipeline::default().with_fee_model(fee_model);
+        let mut read_only = priced(0);
+        read_only.tx.payload.read_only = true;
+
+        assert!(pipeline.run(&read_only).is_ok());
+    }
+}
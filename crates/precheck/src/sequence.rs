@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Remembers the last accepted sequence number of each signer.
+pub trait SequenceStore: Send + Sync {
+    /// Accepts `sequence` for `signer` if it directly follows the last
+    /// accepted one (signers start at 1), recording it. Otherwise returns
+    /// the sequence number that was expected.
+    fn advance(&self, signer: &str, sequence: u64) -> Result<(), u64>;
+
+    fn last_sequence(&self, signer: &str) -> Option<u64>;
+
+    /// The sequence number `advance` would accept next for `signer`,
+    /// without recording anything.
+    fn next_sequence(&self, signer: &str) -> u64 {
+        self.last_sequence(signer).unwrap_or(0) + 1
+    }
+}
+
+#[derive(Default)]
+pub struct InMemorySequenceStore {
+    last: Mutex<HashMap<String, u64>>,
+}
+
+impl InMemorySequenceStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SequenceStore for InMemorySequenceStore {
+    fn advance(&self, signer: &str, sequence: u64) -> Result<(), u64> {
+        let mut last = self.last.lock().unwrap();
+        let expected = last.get(signer).copied().unwrap_or(0) + 1;
+        if sequence != expected {
+            return Err(expected);
+        }
+        last.insert(signer.to_string(), sequence);
+        Ok(())
+    }
+
+    fn last_sequence(&self, signer: &str) -> Option<u64> {
+        self.last.lock().unwrap().get(signer).copied()
+    }
+}
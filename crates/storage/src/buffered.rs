@@ -0,0 +1,217 @@
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+use crate::CheckpointStore;
+
+/// Checkpoints buffered before a write-out unless configured otherwise.
+pub const DEFAULT_CHECKPOINT_FLUSH_COUNT: usize = 16;
+
+/// Buffers checkpoint writes in memory and hands them to the inner store in
+/// batches, once `flush_count` are pending or when `flush` is called.
+///
+/// Buffered checkpoints are readable straight away but are lost if the
+/// process dies before they are flushed; call `flush` at durability points.
+pub struct BufferedCheckpointStore {
+    inner: Arc<dyn CheckpointStore>,
+    /// Entries stay here until the inner store has accepted them, so reads
+    /// never miss a checkpoint that is mid-flush.
+    pending: RwLock<BTreeMap<u64, String>>,
+    flush_count: usize,
+    /// Serializes flushes so that batches reach the inner store in order.
+    flushing: Mutex<()>,
+}
+
+impl BufferedCheckpointStore {
+    pub fn new(inner: Arc<dyn CheckpointStore>) -> Self {
+        Self::with_flush_count(inner, DEFAULT_CHECKPOINT_FLUSH_COUNT)
+    }
+
+    pub fn with_flush_count(inner: Arc<dyn CheckpointStore>, flush_count: usize) -> Self {
+        Self {
+            inner,
+            pending: RwLock::new(BTreeMap::new()),
+            flush_count: flush_count.max(1),
+            flushing: Mutex::new(()),
+        }
+    }
+
+    pub fn flush_count(&self) -> usize {
+        self.flush_count
+    }
+
+    /// Checkpoints written here but not yet to the inner store.
+    pub fn pending_len(&self) -> usize {
+        self.pending.read().len()
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for BufferedCheckpointStore {
+    async fn save_checkpoint(&self, sequence: u64, checkpoint_json: &str) -> anyhow::Result<()> {
+        let pending = {
+            let mut pending = self.pending.write();
+            pending.insert(sequence, checkpoint_json.to_string());
+            pending.len()
+        };
+        if pending >= self.flush_count {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    async fn get_checkpoint(&self, sequence: u64) -> anyhow::Result<Option<String>> {
+        if let Some(checkpoint) = self.pending.read().get(&sequence).cloned() {
+            return Ok(Some(checkpoint));
+        }
+        self.inner.get_checkpoint(sequence).await
+    }
+
+    async fn get_latest_sequence(&self) -> anyhow::Result<Option<u64>> {
+        let buffered = self.pending.read().keys().next_back().copied();
+        let stored = self.inner.get_latest_sequence().await?;
+        Ok(buffered.max(stored))
+    }
+
+    async fn save_checkpoints(&self, checkpoints: &[(u64, String)]) -> anyhow::Result<()> {
+        {
+            let mut pending = self.pending.write();
+            for (sequence, checkpoint) in checkpoints {
+                pending.insert(*sequence, checkpoint.clone());
+            }
+        }
+        if self.pending_len() >= self.flush_count {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Writes every pending checkpoint to the inner store as one batch.
+    async fn flush(&self) -> anyhow::Result<()> {
+        let _flushing = self.flushing.lock().await;
+        let batch: Vec<(u64, String)> = self
+            .pending
+            .read()
+            .iter()
+            .map(|(sequence, checkpoint)| (*sequence, checkpoint.clone()))
+            .collect();
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        self.inner.save_checkpoints(&batch).await?;
+        self.inner.flush().await?;
+
+        let mut pending = self.pending.write();
+        for (sequence, checkpoint) in &batch {
+            // Leave entries that were overwritten while the batch was out.
+            if pending.get(sequence) == Some(checkpoint) {
+                pending.remove(sequence);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Calls [`CheckpointStore::flush`] every `interval` until the task is
+/// aborted, bounding how long a checkpoint can sit in a buffer.
+pub fn spawn_checkpoint_flusher(store: Arc<dyn CheckpointStore>, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let _ = store.flush().await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryCheckpointStore;
+
+    /// Records the size of every batch it is handed.
+    #[derive(Default)]
+    struct BatchLog {
+        inner: InMemoryCheckpointStore,
+        batches: parking_lot::Mutex<Vec<usize>>,
+    }
+
+    #[async_trait]
+    impl CheckpointStore for BatchLog {
+        async fn save_checkpoint(&self, sequence: u64, checkpoint_json: &str) -> anyhow::Result<()> {
+            self.save_checkpoints(&[(sequence, checkpoint_json.to_string())]).await
+        }
+
+        async fn get_checkpoint(&self, sequence: u64) -> anyhow::Result<Option<String>> {
+            self.inner.get_checkpoint(sequence).await
+        }
+
+        async fn get_latest_sequence(&self) -> anyhow::Result<Option<u64>> {
+            self.inner.get_latest_sequence().await
+        }
+
+        async fn save_checkpoints(&self, checkpoints: &[(u64, String)]) -> anyhow::Result<()> {
+            self.batches.lock().push(checkpoints.len());
+            self.inner.save_checkpoints(checkpoints).await
+        }
+    }
+
+    async fn save(store: &dyn CheckpointStore, sequences: std::ops::RangeInclusive<u64>) {
+        for sequence in sequences {
+            store.save_checkpoint(sequence, &format!("checkpoint-{}", sequence)).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn checkpoints_reach_the_inner_store_in_batches_of_the_flush_count() {
+        let inner = Arc::new(BatchLog::default());
+        let store = BufferedCheckpointStore::with_flush_count(inner.clone(), 5);
+
+        save(&store, 1..=12).await;
+
+        assert_eq!(*inner.batches.lock(), vec![5, 5]);
+        assert_eq!(store.pending_len(), 2);
+        assert_eq!(inner.get_latest_sequence().await.unwrap(), Some(10));
+        assert_eq!(store.get_latest_sequence().await.unwrap(), Some(12));
+        assert_eq!(store.get_checkpoint(12).await.unwrap().as_deref(), Some("checkpoint-12"));
+    }
+
+    #[tokio::test]
+    async fn flush_forces_pending_checkpoints_to_the_inner_store() {
+        let inner = Arc::new(BatchLog::default());
+        let store = BufferedCheckpointStore::with_flush_count(inner.clone(), 5);
+        save(&store, 1..=3).await;
+        assert!(inner.batches.lock().is_empty());
+
+        store.flush().await.unwrap();
+        store.flush().await.unwrap();
+
+        assert_eq!(*inner.batches.lock(), vec![3]);
+        assert_eq!(store.pending_len(), 0);
+        assert_eq!(inner.get_checkpoint(3).await.unwrap().as_deref(), Some("checkpoint-3"));
+    }
+
+    #[tokio::test]
+    async fn flusher_task_writes_out_checkpoints_on_its_interval() {
+        let inner = Arc::new(BatchLog::default());
+        let store = Arc::new(BufferedCheckpointStore::with_flush_count(inner.clone(), 5));
+        save(store.as_ref(), 1..=2).await;
+
+        let flusher = spawn_checkpoint_flusher(store.clone(), Duration::from_millis(10));
+        for _ in 0..100 {
+            if store.pending_len() == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        flusher.abort();
+
+        assert_eq!(store.pending_len(), 0);
+        assert_eq!(inner.get_latest_sequence().await.unwrap(), Some(2));
+    }
+}
@@ -0,0 +1,207 @@
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use sui_core::object::SuiObject;
+
+use crate::ObjectStore;
+
+/// Objects kept by a cache unless configured otherwise.
+pub const DEFAULT_OBJECT_CACHE_CAPACITY: usize = 1024;
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, SuiObject>,
+    /// Least recently used first.
+    order: VecDeque<String>,
+    /// Bumped on every write, so a miss that raced with a write does not
+    /// cache what it read.
+    generation: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl CacheState {
+    fn touch(&mut self, id: &str) {
+        if let Some(pos) = self.order.iter().position(|cached| cached == id) {
+            let id = self.order.remove(pos).expect("position is in range");
+            self.order.push_back(id);
+        }
+    }
+
+    fn insert(&mut self, object: SuiObject, capacity: usize) {
+        let id = object.id.0.clone();
+        if self.entries.insert(id.clone(), object).is_some() {
+            self.touch(&id);
+        } else {
+            self.order.push_back(id);
+        }
+        while self.entries.len() > capacity {
+            match self.order.pop_front() {
+                Some(evicted) => {
+                    self.entries.remove(&evicted);
+                }
+                None => break,
+            }
+        }
+    }
+
+    fn remove(&mut self, id: &str) {
+        if self.entries.remove(id).is_some() {
+            self.order.retain(|cached| cached != id);
+        }
+    }
+}
+
+/// Write-through LRU cache in front of an object store.
+///
+/// Every write goes to the inner store first and then updates (or, for
+/// deletes, evicts) the cached copy, so reads through the cache never see an
+/// object older than the last write made through it. Writes that bypass the
+/// cache and go straight to the inner store are not seen.
+pub struct CachedObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    state: Mutex<CacheState>,
+    capacity: usize,
+}
+
+impl CachedObjectStore {
+    pub fn new(inner: Arc<dyn ObjectStore>) -> Self {
+        Self::with_capacity(inner, DEFAULT_OBJECT_CACHE_CAPACITY)
+    }
+
+    pub fn with_capacity(inner: Arc<dyn ObjectStore>, capacity: usize) -> Self {
+        Self {
+            inner,
+            state: Mutex::new(CacheState::default()),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// `(hits, misses)` since the cache was created.
+    pub fn hit_stats(&self) -> (u64, u64) {
+        let state = self.state.lock();
+        (state.hits, state.misses)
+    }
+
+    /// Records a completed write: bumps the generation and applies `update`
+    /// to the cached entries.
+    fn after_write(&self, update: impl FnOnce(&mut CacheState)) {
+        let mut state = self.state.lock();
+        state.generation += 1;
+        update(&mut state);
+    }
+}
+
+#[async_trait]
+impl ObjectStore for CachedObjectStore {
+    async fn get_object(&self, id: &str) -> anyhow::Result<Option<SuiObject>> {
+        let generation = {
+            let mut state = self.state.lock();
+            if let Some(object) = state.entries.get(id).cloned() {
+                state.hits += 1;
+                state.touch(id);
+                return Ok(Some(object));
+            }
+            state.misses += 1;
+            state.generation
+        };
+
+        let object = self.inner.get_object(id).await?;
+        if let Some(object) = &object {
+            let mut state = self.state.lock();
+            if state.generation == generation && self.capacity > 0 {
+                state.insert(object.clone(), self.capacity);
+            }
+        }
+        Ok(object)
+    }
+
+    async fn put_object(&self, object: SuiObject) -> anyhow::Result<()> {
+        self.inner.put_object(object.clone()).await?;
+        self.after_write(|state| state.insert(object, self.capacity));
+        Ok(())
+    }
+
+    async fn create_object(&self, object: SuiObject) -> anyhow::Result<()> {
+        self.inner.create_object(object.clone()).await?;
+        self.after_write(|state| state.insert(object, self.capacity));
+        Ok(())
+    }
+
+    async fn delete_object(&self, id: &str) -> anyhow::Result<()> {
+        self.inner.delete_object(id).await?;
+        self.after_write(|state| state.remove(id));
+        Ok(())
+    }
+
+    async fn list_objects(&self, owner: Option<&str>) -> anyhow::Result<Vec<SuiObject>> {
+        self.inner.list_objects(owner).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryObjectStore;
+    use sui_core::object::{ObjectData, ObjectID, Owner};
+
+    fn coin(id: &str, owner: &str, version: u64) -> SuiObject {
+        let owner = Owner::Address(owner.to_string());
+        let mut coin = SuiObject::new(ObjectID::new(id), owner, ObjectData::Coin { balance: 1 });
+        coin.version = version;
+        coin
+    }
+
+    #[tokio::test]
+    async fn read_after_a_write_sees_the_new_version() {
+        let cache = CachedObjectStore::new(Arc::new(InMemoryObjectStore::new()));
+        cache.put_object(coin("coin-a", "alice", 1)).await.unwrap();
+        cache.get_object("coin-a").await.unwrap();
+
+        cache.put_object(coin("coin-a", "bob", 2)).await.unwrap();
+
+        let read = cache.get_object("coin-a").await.unwrap().unwrap();
+        assert_eq!(read.version, 2);
+        assert_eq!(read.owner, Owner::Address("bob".to_string()));
+        assert_eq!(cache.hit_stats(), (2, 0));
+    }
+
+    #[tokio::test]
+    async fn deleted_object_is_not_served_from_the_cache() {
+        let cache = CachedObjectStore::new(Arc::new(InMemoryObjectStore::new()));
+        cache.put_object(coin("coin-a", "alice", 1)).await.unwrap();
+
+        cache.delete_object("coin-a").await.unwrap();
+
+        assert!(cache.get_object("coin-a").await.unwrap().is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn least_recently_used_entry_is_evicted_at_capacity() {
+        let inner = Arc::new(InMemoryObjectStore::new());
+        let cache = CachedObjectStore::with_capacity(inner, 2);
+        cache.put_object(coin("coin-a", "alice", 1)).await.unwrap();
+        cache.put_object(coin("coin-b", "alice", 1)).await.unwrap();
+        cache.get_object("coin-a").await.unwrap();
+
+        cache.put_object(coin("coin-c", "alice", 1)).await.unwrap();
+
+        assert_eq!(cache.len(), 2);
+        cache.get_object("coin-b").await.unwrap().unwrap();
+        assert_eq!(cache.hit_stats(), (1, 1));
+    }
+}
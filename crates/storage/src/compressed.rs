@@ -0,0 +1,135 @@
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::sync::Arc;
+use sui_core::transaction::TransactionDigest;
+
+use crate::EffectsStore;
+
+/// zstd level used unless configured otherwise.
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = 3;
+
+/// Marks an entry written by [`CompressedEffectsStore`]; anything else is
+/// read back as plain JSON.
+const COMPRESSED_PREFIX: &str = "zstd:";
+
+/// Compresses effects JSON with zstd before handing it to the inner store,
+/// and decompresses on the way out, so callers only ever see plain JSON.
+///
+/// The inner store holds strings, so compressed bytes are base64-encoded.
+/// Entries the inner store already held uncompressed are still readable.
+pub struct CompressedEffectsStore {
+    inner: Arc<dyn EffectsStore>,
+    level: i32,
+}
+
+impl CompressedEffectsStore {
+    pub fn new(inner: Arc<dyn EffectsStore>) -> Self {
+        Self::with_level(inner, DEFAULT_COMPRESSION_LEVEL)
+    }
+
+    pub fn with_level(inner: Arc<dyn EffectsStore>, level: i32) -> Self {
+        Self { inner, level }
+    }
+
+    /// The form `effects_json` takes in the inner store.
+    pub fn compress(&self, effects_json: &str) -> anyhow::Result<String> {
+        let compressed = zstd::encode_all(effects_json.as_bytes(), self.level)?;
+        Ok(format!("{}{}", COMPRESSED_PREFIX, STANDARD.encode(compressed)))
+    }
+
+    pub fn decompress(stored: &str) -> anyhow::Result<String> {
+        let Some(encoded) = stored.strip_prefix(COMPRESSED_PREFIX) else {
+            return Ok(stored.to_string());
+        };
+        let compressed = STANDARD.decode(encoded)?;
+        Ok(String::from_utf8(zstd::decode_all(compressed.as_slice())?)?)
+    }
+}
+
+#[async_trait]
+impl EffectsStore for CompressedEffectsStore {
+    async fn save_effects(&self, digest: &TransactionDigest, effects_json: &str) -> anyhow::Result<()> {
+        let stored = self.compress(effects_json)?;
+        self.inner.save_effects(digest, &stored).await
+    }
+
+    async fn get_effects(&self, digest: &TransactionDigest) -> anyhow::Result<Option<String>> {
+        match self.inner.get_effects(digest).await? {
+            Some(stored) => Ok(Some(Self::decompress(&stored)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn list_effects(&self) -> anyhow::Result<Vec<(TransactionDigest, String)>> {
+        self.inner
+            .list_effects()
+            .await?
+            .into_iter()
+            .map(|(digest, stored)| Ok((digest, Self::decompress(&stored)?)))
+            .collect()
+    }
+
+    async fn sync(&self) -> anyhow::Result<()> {
+        self.inner.sync().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryEffectsStore;
+    use sui_core::object::{ObjectData, ObjectID, Owner, SuiObject};
+    use sui_effects::TransactionEffects;
+
+    /// Effects of a transaction that created eight coins and mutated two.
+    fn typical_effects() -> String {
+        let coin = |index: usize| {
+            SuiObject::new(
+                ObjectID::new(format!("coin-{}", index)),
+                Owner::Address("alice".to_string()),
+                ObjectData::Coin { balance: 100 },
+            )
+        };
+        let mut effects = TransactionEffects::new(TransactionDigest("tx-1".to_string()));
+        effects.created = (0..8).map(coin).collect();
+        effects.mutated = (8..10).map(coin).collect();
+        serde_json::to_string(&effects).unwrap()
+    }
+
+    #[tokio::test]
+    async fn effects_round_trip_unchanged() {
+        let store = CompressedEffectsStore::new(Arc::new(InMemoryEffectsStore::new()));
+        let digest = TransactionDigest("tx-1".to_string());
+        let raw = typical_effects();
+
+        store.save_effects(&digest, &raw).await.unwrap();
+
+        assert_eq!(store.get_effects(&digest).await.unwrap(), Some(raw.clone()));
+        assert_eq!(store.list_effects().await.unwrap(), vec![(digest, raw)]);
+    }
+
+    #[tokio::test]
+    async fn stored_effects_are_smaller_than_the_json() {
+        let inner = Arc::new(InMemoryEffectsStore::new());
+        let store = CompressedEffectsStore::new(inner.clone());
+        let digest = TransactionDigest("tx-1".to_string());
+        let raw = typical_effects();
+
+        store.save_effects(&digest, &raw).await.unwrap();
+
+        let stored = inner.get_effects(&digest).await.unwrap().unwrap();
+        assert!(stored.starts_with(COMPRESSED_PREFIX));
+        assert!(stored.len() < raw.len(), "{} stored bytes for {} of JSON", stored.len(), raw.len());
+    }
+
+    #[tokio::test]
+    async fn uncompressed_entries_stay_readable() {
+        let inner = Arc::new(InMemoryEffectsStore::new());
+        let digest = TransactionDigest("tx-1".to_string());
+        inner.save_effects(&digest, "{\"plain\":true}").await.unwrap();
+
+        let store = CompressedEffectsStore::new(inner);
+
+        assert_eq!(store.get_effects(&digest).await.unwrap().as_deref(), Some("{\"plain\":true}"));
+    }
+}
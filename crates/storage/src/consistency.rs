@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use sui_core::object::{ObjectID, Owner, SuiObject};
+
+/// A broken store invariant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConsistencyViolation {
+    /// More than one stored object carries this id.
+    DuplicateId { id: ObjectID, count: usize },
+    /// The object is owned by an object that is not in the store.
+    MissingParent { child: ObjectID, parent: ObjectID },
+    ZeroVersion { id: ObjectID },
+    /// A wrapped object whose wrapper is not in the store.
+    MissingWrapper { id: ObjectID, wrapper: ObjectID },
+    /// A wrapped object not owned by its wrapper, and therefore reachable
+    /// on its own.
+    WrappedNotOwnedByWrapper { id: ObjectID, wrapper: ObjectID },
+}
+
+/// Result of [`crate::ObjectStore::check_consistency`]. Violations are
+/// sorted by object id.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsistencyReport {
+    pub objects_checked: usize,
+    pub violations: Vec<ConsistencyViolation>,
+}
+
+impl ConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Checks every invariant over a full listing of a store.
+    ///
+    /// Wrapped objects stay in the store here, owned by their wrapper, so
+    /// "not in global storage" amounts to being owned by a wrapper that
+    /// exists.
+    pub fn from_objects(objects: &[SuiObject]) -> Self {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for object in objects {
+            *counts.entry(object.id.0.as_str()).or_default() += 1;
+        }
+        let ids: HashSet<&str> = counts.keys().copied().collect();
+
+        let mut violations: Vec<(String, ConsistencyViolation)> = counts
+            .iter()
+            .filter(|(_, count)| **count > 1)
+            .map(|(id, count)| {
+                let violation = ConsistencyViolation::DuplicateId {
+                    id: ObjectID::new(*id),
+                    count: *count,
+                };
+                (id.to_string(), violation)
+            })
+            .collect();
+
+        for object in objects {
+            let mut report = |violation| violations.push((object.id.0.clone(), violation));
+            if object.version == 0 {
+                report(ConsistencyViolation::ZeroVersion { id: object.id.clone() });
+            }
+            if let Owner::Object(parent) = &object.owner {
+                if !ids.contains(parent.0.as_str()) {
+                    report(ConsistencyViolation::MissingParent {
+                        child: object.id.clone(),
+                        parent: parent.clone(),
+                    });
+                }
+            }
+            if let Some(wrapper) = &object.wrapped_by {
+                if !ids.contains(wrapper.0.as_str()) {
+                    report(ConsistencyViolation::MissingWrapper {
+                        id: object.id.clone(),
+                        wrapper: wrapper.clone(),
+                    });
+                } else if object.owner != Owner::Object(wrapper.clone()) {
+                    report(ConsistencyViolation::WrappedNotOwnedByWrapper {
+                        id: object.id.clone(),
+                        wrapper: wrapper.clone(),
+                    });
+                }
+            }
+        }
+
+        // Stable sort keeps each object's violations in check order.
+        violations.sort_by(|a, b| a.0.cmp(&b.0));
+        Self {
+            objects_checked: objects.len(),
+            violations: violations.into_iter().map(|(_, violation)| violation).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InMemoryObjectStore, ObjectStore};
+    use sui_core::object::ObjectData;
+
+    fn object(id: &str, owner: Owner) -> SuiObject {
+        SuiObject::new(ObjectID::new(id), owner, ObjectData::Coin { balance: 1 })
+    }
+
+    fn wrapped(id: &str, wrapper: &str, owner: Owner) -> SuiObject {
+        let mut object = object(id, owner);
+        object.wrapped_by = Some(ObjectID::new(wrapper));
+        object
+    }
+
+    #[tokio::test]
+    async fn healthy_store_reports_no_violations() {
+        let store = InMemoryObjectStore::new();
+        store.put_object(object("parent", Owner::Address("alice".to_string()))).await.unwrap();
+        store.put_object(object("child", Owner::Object(ObjectID::new("parent")))).await.unwrap();
+        store.put_object(wrapped("inner", "parent", Owner::Object(ObjectID::new("parent")))).await.unwrap();
+        store.put_object(object("pool", Owner::Shared)).await.unwrap();
+
+        let report = store.check_consistency().await.unwrap();
+
+        assert!(report.is_consistent(), "{:?}", report.violations);
+        assert_eq!(report.objects_checked, 4);
+    }
+
+    #[tokio::test]
+    async fn child_of_a_missing_parent_is_reported() {
+        let store = InMemoryObjectStore::new();
+        store.put_object(object("child", Owner::Object(ObjectID::new("gone")))).await.unwrap();
+
+        let report = store.check_consistency().await.unwrap();
+
+        assert_eq!(
+            report.violations,
+            vec![ConsistencyViolation::MissingParent { child: ObjectID::new("child"), parent: ObjectID::new("gone") }]
+        );
+    }
+
+    #[test]
+    fn every_kind_of_violation_is_reported_in_id_order() {
+        let mut unversioned = object("d-zero", Owner::Shared);
+        unversioned.version = 0;
+        let objects = vec![
+            unversioned,
+            object("a-dup", Owner::Shared),
+            object("a-dup", Owner::Shared),
+            object("wrapper", Owner::Address("alice".to_string())),
+            wrapped("b-loose", "wrapper", Owner::Address("alice".to_string())),
+            wrapped("c-orphan", "gone", Owner::Object(ObjectID::new("gone"))),
+        ];
+
+        let report = ConsistencyReport::from_objects(&objects);
+
+        assert_eq!(report.objects_checked, 6);
+        assert_eq!(
+            report.violations,
+            vec![
+                ConsistencyViolation::DuplicateId { id: ObjectID::new("a-dup"), count: 2 },
+                ConsistencyViolation::WrappedNotOwnedByWrapper {
+                    id: ObjectID::new("b-loose"),
+                    wrapper: ObjectID::new("wrapper"),
+                },
+                ConsistencyViolation::MissingParent { child: ObjectID::new("c-orphan"), parent: ObjectID::new("gone") },
+                ConsistencyViolation::MissingWrapper { id: ObjectID::new("c-orphan"), wrapper: ObjectID::new("gone") },
+                ConsistencyViolation::ZeroVersion { id: ObjectID::new("d-zero") },
+            ]
+        );
+    }
+}
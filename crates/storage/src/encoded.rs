@@ -0,0 +1,264 @@
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+use sui_core::hash::{default_hasher, Hasher};
+use sui_core::object::SuiObject;
+
+use crate::{ObjectStore, StoreError};
+
+/// Byte encoding used when objects are stored serialized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ObjectFormat {
+    #[default]
+    Json,
+    Cbor,
+}
+
+impl ObjectFormat {
+    pub fn encode(&self, object: &SuiObject) -> anyhow::Result<Vec<u8>> {
+        match self {
+            ObjectFormat::Json => Ok(serde_json::to_vec(object)?),
+            ObjectFormat::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(object, &mut bytes)?;
+                Ok(bytes)
+            }
+        }
+    }
+
+    /// Decodes and validates an object, rejecting bytes that parse but
+    /// describe an invalid object.
+    pub fn decode(&self, bytes: &[u8]) -> anyhow::Result<SuiObject> {
+        let object: SuiObject = match self {
+            ObjectFormat::Json => serde_json::from_slice(bytes)?,
+            ObjectFormat::Cbor => ciborium::from_reader(bytes)?,
+        };
+        object.validate().map_err(StoreError::from)?;
+        Ok(object)
+    }
+}
+
+/// Encoded bytes of one object with the checksum taken when it was written.
+struct StoredBytes {
+    bytes: Vec<u8>,
+    checksum: String,
+}
+
+/// Object store that keeps every object in its encoded byte form and decodes
+/// on read, so sizes reflect what the chosen format really costs.
+///
+/// Each entry carries a checksum of its bytes, verified before decoding, so
+/// bytes damaged at rest surface as [`StoreError::Corruption`] instead of a
+/// decode error or, worse, a different valid object.
+pub struct EncodedObjectStore {
+    objects: RwLock<HashMap<String, StoredBytes>>,
+    format: ObjectFormat,
+    hasher: Arc<dyn Hasher>,
+}
+
+impl EncodedObjectStore {
+    pub fn new(format: ObjectFormat) -> Self {
+        Self {
+            objects: RwLock::new(HashMap::new()),
+            format,
+            hasher: default_hasher(),
+        }
+    }
+
+    /// Checksums entries with `hasher` instead of the default hasher. Only
+    /// affects objects written afterwards.
+    pub fn with_checksum_hasher(mut self, hasher: Arc<dyn Hasher>) -> Self {
+        self.hasher = hasher;
+        self
+    }
+
+    pub fn format(&self) -> ObjectFormat {
+        self.format
+    }
+
+    /// Encoded length of a stored object.
+    pub fn storage_size(&self, id: &str) -> Option<usize> {
+        self.objects.read().get(id).map(|stored| stored.bytes.len())
+    }
+
+    /// Encoded length of every stored object combined.
+    pub fn total_storage_size(&self) -> usize {
+        self.objects.read().values().map(|stored| stored.bytes.len()).sum()
+    }
+
+    /// Checksum recorded for a stored object.
+    pub fn checksum(&self, id: &str) -> Option<String> {
+        self.objects.read().get(id).map(|stored| stored.checksum.clone())
+    }
+
+    /// Verifies the checksum of `stored`, then decodes it.
+    fn decode_checked(&self, id: &str, stored: &StoredBytes) -> anyhow::Result<SuiObject> {
+        let actual = self.hasher.hash(&stored.bytes);
+        if actual != stored.checksum {
+            return Err(StoreError::Corruption {
+                id: id.to_string(),
+                expected: stored.checksum.clone(),
+                actual,
+            }
+            .into());
+        }
+        self.format.decode(&stored.bytes)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for EncodedObjectStore {
+    async fn get_object(&self, id: &str) -> anyhow::Result<Option<SuiObject>> {
+        let objects = self.objects.read();
+        objects
+            .get(id)
+            .map(|stored| self.decode_checked(id, stored))
+            .transpose()
+    }
+
+    async fn put_object(&self, object: SuiObject) -> anyhow::Result<()> {
+        let bytes = self.format.encode(&object)?;
+        let checksum = self.hasher.hash(&bytes);
+        self.objects.write().insert(object.id.0, StoredBytes { bytes, checksum });
+        Ok(())
+    }
+
+    async fn delete_object(&self, id: &str) -> anyhow::Result<()> {
+        self.objects.write().remove(id);
+        Ok(())
+    }
+
+    async fn list_objects(&self, owner: Option<&str>) -> anyhow::Result<Vec<SuiObject>> {
+        let stored = self.objects.read();
+        let mut objects = Vec::with_capacity(stored.len());
+        for (id, bytes) in stored.iter() {
+            let object = self.decode_checked(id, bytes)?;
+            let owned = match owner {
+                Some(owner_addr) => object.owner.matches_address(owner_addr),
+                None => true,
+            };
+            if owned {
+                objects.push(object);
+            }
+        }
+        Ok(objects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use sui_core::object::{ObjectData, ObjectID, ObjectValidationError, Owner};
+
+    fn profile() -> SuiObject {
+        SuiObject::new(
+            ObjectID::new("hero"),
+            Owner::Address("alice".to_string()),
+            ObjectData::MoveStruct { type_name: "game::Profile".to_string(), fields: json!({ "level": 3, "name": "hero" }) },
+        )
+    }
+
+    #[tokio::test]
+    async fn storage_size_matches_the_encoded_length() {
+        for format in [ObjectFormat::Json, ObjectFormat::Cbor] {
+            let store = EncodedObjectStore::new(format);
+            store.put_object(profile()).await.unwrap();
+
+            let expected = format.encode(&profile()).unwrap().len();
+            assert_eq!(store.storage_size("hero"), Some(expected), "{:?}", format);
+            assert_eq!(store.total_storage_size(), expected);
+        }
+        assert_ne!(
+            ObjectFormat::Json.encode(&profile()).unwrap().len(),
+            ObjectFormat::Cbor.encode(&profile()).unwrap().len()
+        );
+    }
+
+    #[tokio::test]
+    async fn objects_round_trip_through_each_format() {
+        for format in [ObjectFormat::Json, ObjectFormat::Cbor] {
+            let store = EncodedObjectStore::new(format);
+            store.put_object(profile()).await.unwrap();
+
+            let read = store.get_object("hero").await.unwrap().unwrap();
+            assert_eq!(serde_json::to_value(&read).unwrap(), serde_json::to_value(profile()).unwrap());
+        }
+    }
+
+    #[test]
+    fn valid_object_passes_validation() {
+        assert!(profile().validate().is_ok());
+    }
+
+    #[tokio::test]
+    async fn stored_objects_with_an_empty_id_or_version_zero_are_rejected() {
+        let mut unversioned = profile();
+        unversioned.version = 0;
+        let mut unnamed = profile();
+        unnamed.id = ObjectID::new("");
+
+        for format in [ObjectFormat::Json, ObjectFormat::Cbor] {
+            let store = EncodedObjectStore::new(format);
+            store.put_object(unversioned.clone()).await.unwrap();
+            store.put_object(unnamed.clone()).await.unwrap();
+
+            let err = store.get_object("hero").await.unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<StoreError>(),
+                Some(StoreError::CorruptObject(ObjectValidationError::ZeroVersion(id))) if id == "hero"
+            ));
+            let err = store.get_object("").await.unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<StoreError>(),
+                Some(StoreError::CorruptObject(ObjectValidationError::EmptyId))
+            ));
+        }
+    }
+
+    /// Flips the low bit of the byte at `index` of a stored entry.
+    fn flip_byte(store: &EncodedObjectStore, id: &str, index: usize) {
+        store.objects.write().get_mut(id).unwrap().bytes[index] ^= 1;
+    }
+
+    fn is_corruption(err: &anyhow::Error) -> bool {
+        matches!(err.downcast_ref::<StoreError>(), Some(StoreError::Corruption { id, .. }) if id == "hero")
+    }
+
+    #[tokio::test]
+    async fn intact_objects_pass_their_checksum() {
+        let store = EncodedObjectStore::new(ObjectFormat::Cbor);
+        store.put_object(profile()).await.unwrap();
+
+        let bytes = ObjectFormat::Cbor.encode(&profile()).unwrap();
+        assert_eq!(store.checksum("hero"), Some(default_hasher().hash(&bytes)));
+        assert!(store.get_object("hero").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn a_flipped_byte_is_reported_as_corruption() {
+        for format in [ObjectFormat::Json, ObjectFormat::Cbor] {
+            let store = EncodedObjectStore::new(format);
+            store.put_object(profile()).await.unwrap();
+
+            flip_byte(&store, "hero", 0);
+
+            assert!(is_corruption(&store.get_object("hero").await.unwrap_err()), "{:?}", format);
+            assert!(is_corruption(&store.list_objects(None).await.unwrap_err()), "{:?}", format);
+        }
+    }
+
+    #[tokio::test]
+    async fn corruption_that_still_decodes_is_caught() {
+        let store = EncodedObjectStore::new(ObjectFormat::Json);
+        store.put_object(profile()).await.unwrap();
+        let bytes = ObjectFormat::Json.encode(&profile()).unwrap();
+        let level = bytes.windows(9).position(|window| window == b"\"level\":3").unwrap() + 8;
+
+        // '3' becomes '2': still a valid profile, just not the stored one.
+        flip_byte(&store, "hero", level);
+
+        assert!(is_corruption(&store.get_object("hero").await.unwrap_err()));
+    }
+}
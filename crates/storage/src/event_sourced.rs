@@ -0,0 +1,192 @@
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use sui_core::object::SuiObject;
+use tokio::sync::Mutex;
+
+use crate::ObjectStore;
+
+/// One change made to a store, with the object as it was before.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum StoreMutation {
+    Put {
+        before: Option<SuiObject>,
+        after: SuiObject,
+    },
+    Delete {
+        before: SuiObject,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreLogEntry {
+    /// Position in the log, starting at 1.
+    pub sequence: u64,
+    pub mutation: StoreMutation,
+}
+
+/// Object store wrapper that keeps an ordered log of every mutation made
+/// through it, from which the store's state can be audited or rebuilt.
+///
+/// Unlike transaction effects, the log is purely store-level: every put
+/// and delete shows up, whichever code path made it. Writes are serialized
+/// so the log order is the order the inner store saw them in. Deletes of
+/// objects that do not exist change nothing and are not logged.
+pub struct EventSourcedStore {
+    inner: Arc<dyn ObjectStore>,
+    log: RwLock<Vec<StoreLogEntry>>,
+    writing: Mutex<()>,
+}
+
+impl EventSourcedStore {
+    pub fn new(inner: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            inner,
+            log: RwLock::new(Vec::new()),
+            writing: Mutex::new(()),
+        }
+    }
+
+    pub fn log(&self) -> Vec<StoreLogEntry> {
+        self.log.read().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.log.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Applies every logged mutation to `target` in order and returns how
+    /// many were applied. Replaying into an empty store reproduces the
+    /// state built through this wrapper.
+    pub async fn replay_into(&self, target: &dyn ObjectStore) -> anyhow::Result<usize> {
+        let log = self.log();
+        for entry in &log {
+            match &entry.mutation {
+                StoreMutation::Put { after, .. } => target.put_object(after.clone()).await?,
+                StoreMutation::Delete { before } => target.delete_object(&before.id.0).await?,
+            }
+        }
+        Ok(log.len())
+    }
+
+    fn append(&self, mutation: StoreMutation) {
+        let mut log = self.log.write();
+        let sequence = log.len() as u64 + 1;
+        log.push(StoreLogEntry { sequence, mutation });
+    }
+}
+
+#[async_trait]
+impl ObjectStore for EventSourcedStore {
+    async fn get_object(&self, id: &str) -> anyhow::Result<Option<SuiObject>> {
+        self.inner.get_object(id).await
+    }
+
+    async fn put_object(&self, object: SuiObject) -> anyhow::Result<()> {
+        let _writing = self.writing.lock().await;
+        let before = self.inner.get_object(&object.id.0).await?;
+        self.inner.put_object(object.clone()).await?;
+        self.append(StoreMutation::Put { before, after: object });
+        Ok(())
+    }
+
+    async fn create_object(&self, object: SuiObject) -> anyhow::Result<()> {
+        let _writing = self.writing.lock().await;
+        self.inner.create_object(object.clone()).await?;
+        self.append(StoreMutation::Put {
+            before: None,
+            after: object,
+        });
+        Ok(())
+    }
+
+    async fn delete_object(&self, id: &str) -> anyhow::Result<()> {
+        let _writing = self.writing.lock().await;
+        let before = self.inner.get_object(id).await?;
+        self.inner.delete_object(id).await?;
+        if let Some(before) = before {
+            self.append(StoreMutation::Delete { before });
+        }
+        Ok(())
+    }
+
+    async fn list_objects(&self, owner: Option<&str>) -> anyhow::Result<Vec<SuiObject>> {
+        self.inner.list_objects(owner).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryObjectStore;
+    use sui_core::object::{ObjectData, ObjectID, Owner};
+
+    fn coin(id: &str, owner: &str, balance: u64) -> SuiObject {
+        SuiObject::new(ObjectID::new(id), Owner::Address(owner.to_string()), ObjectData::Coin { balance })
+    }
+
+    /// Every object in `store`, ordered by id, as JSON for comparison.
+    async fn snapshot(store: &dyn ObjectStore) -> Vec<serde_json::Value> {
+        let mut objects = store.list_objects(None).await.unwrap();
+        objects.sort_by(|a, b| a.id.0.cmp(&b.id.0));
+        objects.iter().map(|object| serde_json::to_value(object).unwrap()).collect()
+    }
+
+    /// Creates three coins, transfers one, and deletes another.
+    async fn mutated() -> EventSourcedStore {
+        let store = EventSourcedStore::new(Arc::new(InMemoryObjectStore::new()));
+        store.create_object(coin("coin-a", "alice", 10)).await.unwrap();
+        store.put_object(coin("coin-b", "alice", 20)).await.unwrap();
+        store.create_object(coin("coin-c", "alice", 30)).await.unwrap();
+        let mut transferred = coin("coin-a", "bob", 10);
+        transferred.version = 2;
+        store.put_object(transferred).await.unwrap();
+        store.delete_object("coin-b").await.unwrap();
+        store
+    }
+
+    #[tokio::test]
+    async fn replaying_into_a_fresh_store_reproduces_its_state() {
+        let store = mutated().await;
+        let fresh = InMemoryObjectStore::new();
+
+        let applied = store.replay_into(&fresh).await.unwrap();
+
+        assert_eq!(applied, 5);
+        assert_eq!(snapshot(&fresh).await, snapshot(&store).await);
+        assert_eq!(snapshot(&fresh).await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn log_records_each_mutation_in_order_with_its_before_state() {
+        let store = mutated().await;
+        store.delete_object("missing").await.unwrap();
+
+        let log = store.log();
+
+        assert_eq!(log.iter().map(|entry| entry.sequence).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+        assert!(matches!(&log[0].mutation, StoreMutation::Put { before: None, after } if after.id.0 == "coin-a"));
+        match &log[3].mutation {
+            StoreMutation::Put { before: Some(before), after } => {
+                assert_eq!(before.owner, Owner::Address("alice".to_string()));
+                assert_eq!((after.owner.clone(), after.version), (Owner::Address("bob".to_string()), 2));
+            }
+            other => panic!("expected an overwrite, got {:?}", other),
+        }
+        assert!(matches!(&log[4].mutation, StoreMutation::Delete { before } if before.id.0 == "coin-b"));
+    }
+
+    #[tokio::test]
+    async fn failed_writes_are_not_logged() {
+        let store = mutated().await;
+
+        assert!(store.create_object(coin("coin-c", "carol", 1)).await.is_err());
+
+        assert_eq!(store.len(), 5);
+    }
+}
@@ -0,0 +1,225 @@
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Arc;
+use sui_core::transaction::TransactionDigest;
+
+use crate::{EffectsStore, InMemoryEffectsStore};
+
+/// When saved effects reach disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Durability {
+    /// Every save is written and fsynced before it returns.
+    #[default]
+    Sync,
+    /// Saves return straight away; a background task writes and fsyncs.
+    Async,
+    /// Saves are held in memory and written out, with one fsync, every `n`
+    /// saves or on `sync`.
+    Buffered(usize),
+}
+
+#[derive(Serialize, Deserialize)]
+struct LogEntry {
+    digest: TransactionDigest,
+    effects: String,
+}
+
+/// The append-only file and the entries not yet written to it.
+struct EffectsLog {
+    file: Mutex<File>,
+    pending: Mutex<Vec<String>>,
+}
+
+impl EffectsLog {
+    /// Appends every pending entry and fsyncs. Entries are taken under the
+    /// file lock so they reach the file in save order.
+    fn write_pending(&self) -> std::io::Result<()> {
+        let mut file = self.file.lock();
+        let pending = std::mem::take(&mut *self.pending.lock());
+        if pending.is_empty() {
+            return Ok(());
+        }
+        for line in &pending {
+            writeln!(file, "{}", line)?;
+        }
+        file.sync_data()
+    }
+}
+
+/// Effects store backed by an append-only newline-delimited JSON file.
+///
+/// Reads are served from memory, so effects are visible as soon as they are
+/// saved whatever the durability; what `durability` controls is how much a
+/// crash can lose. Entries not yet flushed are lost with the process, and
+/// `sync` flushes them on demand.
+pub struct FileEffectsStore {
+    index: InMemoryEffectsStore,
+    log: Arc<EffectsLog>,
+    durability: Durability,
+}
+
+impl FileEffectsStore {
+    /// Opens or creates the log at `path`, loading the effects already in it.
+    pub fn open(path: impl AsRef<Path>, durability: Durability) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        let index = InMemoryEffectsStore::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            // A torn final line is what a crash mid-write leaves behind.
+            let Ok(entry) = serde_json::from_str::<LogEntry>(&line) else {
+                continue;
+            };
+            index.insert(&entry.digest, &entry.effects);
+        }
+
+        Ok(Self {
+            index,
+            log: Arc::new(EffectsLog {
+                file: Mutex::new(file),
+                pending: Mutex::new(Vec::new()),
+            }),
+            durability,
+        })
+    }
+
+    pub fn durability(&self) -> Durability {
+        self.durability
+    }
+
+    /// Saved effects not yet written to the file.
+    pub fn pending_len(&self) -> usize {
+        self.log.pending.lock().len()
+    }
+}
+
+#[async_trait]
+impl EffectsStore for FileEffectsStore {
+    async fn save_effects(&self, digest: &TransactionDigest, effects_json: &str) -> anyhow::Result<()> {
+        let line = serde_json::to_string(&LogEntry {
+            digest: digest.clone(),
+            effects: effects_json.to_string(),
+        })?;
+        self.index.insert(digest, effects_json);
+        let pending = {
+            let mut pending = self.log.pending.lock();
+            pending.push(line);
+            pending.len()
+        };
+
+        match self.durability {
+            Durability::Sync => self.log.write_pending()?,
+            Durability::Async => {
+                let log = Arc::clone(&self.log);
+                tokio::task::spawn_blocking(move || {
+                    if let Err(err) = log.write_pending() {
+                        eprintln!("Error writing effects log: {}", err);
+                    }
+                });
+            }
+            Durability::Buffered(n) => {
+                if pending >= n {
+                    self.log.write_pending()?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_effects(&self, digest: &TransactionDigest) -> anyhow::Result<Option<String>> {
+        self.index.get_effects(digest).await
+    }
+
+    async fn list_effects(&self) -> anyhow::Result<Vec<(TransactionDigest, String)>> {
+        self.index.list_effects().await
+    }
+
+    async fn sync(&self) -> anyhow::Result<()> {
+        let log = Arc::clone(&self.log);
+        tokio::task::spawn_blocking(move || log.write_pending()).await??;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh log path under the system temp directory.
+    fn log_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("sui-effects-{}-{}.log", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    async fn save(store: &FileEffectsStore, count: usize) {
+        for i in 1..=count {
+            let digest = TransactionDigest(format!("tx-{}", i));
+            store.save_effects(&digest, &format!("{{\"n\":{}}}", i)).await.unwrap();
+        }
+    }
+
+    /// Digests recovered by reopening the log, as after a crash.
+    async fn recovered(path: &Path) -> Vec<String> {
+        let store = FileEffectsStore::open(path, Durability::Sync).unwrap();
+        store.list_effects().await.unwrap().into_iter().map(|(digest, _)| digest.0).collect()
+    }
+
+    #[tokio::test]
+    async fn sync_saves_survive_an_immediate_crash() {
+        let path = log_path("sync");
+        let store = FileEffectsStore::open(&path, Durability::Sync).unwrap();
+
+        save(&store, 3).await;
+        assert_eq!(store.pending_len(), 0);
+        drop(store);
+
+        assert_eq!(recovered(&path).await, vec!["tx-1", "tx-2", "tx-3"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn buffered_saves_survive_only_once_flushed() {
+        let path = log_path("buffered");
+        let store = FileEffectsStore::open(&path, Durability::Buffered(2)).unwrap();
+
+        save(&store, 3).await;
+        assert_eq!(store.pending_len(), 1);
+        assert!(store.get_effects(&TransactionDigest("tx-3".to_string())).await.unwrap().is_some());
+        drop(store);
+
+        assert_eq!(recovered(&path).await, vec!["tx-1", "tx-2"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn explicit_sync_flushes_buffered_saves() {
+        let path = log_path("explicit");
+        let store = FileEffectsStore::open(&path, Durability::Buffered(10)).unwrap();
+
+        save(&store, 3).await;
+        store.sync().await.unwrap();
+        assert_eq!(store.pending_len(), 0);
+        drop(store);
+
+        assert_eq!(recovered(&path).await, vec!["tx-1", "tx-2", "tx-3"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn torn_final_line_is_skipped_on_open() {
+        let path = log_path("torn");
+        let store = FileEffectsStore::open(&path, Durability::Sync).unwrap();
+        save(&store, 2).await;
+        drop(store);
+        OpenOptions::new().append(true).open(&path).unwrap().write_all(b"{\"digest\":\"tx-3\",\"eff").unwrap();
+
+        assert_eq!(recovered(&path).await, vec!["tx-1", "tx-2"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
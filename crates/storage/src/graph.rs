@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use sui_core::object::{Owner, SuiObject};
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum GraphNode {
+    Address(String),
+    Object(String),
+}
+
+impl GraphNode {
+    /// Identifier used in exported formats, e.g. `address:alice`.
+    pub fn label(&self) -> String {
+        match self {
+            GraphNode::Address(addr) => format!("address:{}", addr),
+            GraphNode::Object(id) => format!("object:{}", id),
+        }
+    }
+}
+
+/// An ownership edge from an owner to the object it owns.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub owner: GraphNode,
+    pub object: GraphNode,
+}
+
+/// Ownership graph of a set of objects. Shared and immutable objects appear
+/// as nodes without an incoming edge. Nodes and edges are sorted so exports
+/// are stable.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl ObjectGraph {
+    pub fn from_objects(objects: &[SuiObject]) -> Self {
+        let mut nodes = BTreeSet::new();
+        let mut edges = BTreeSet::new();
+
+        for object in objects {
+            let node = GraphNode::Object(object.id.0.clone());
+            nodes.insert(node.clone());
+
+            let owner = match &object.owner {
+                Owner::Address(addr) => Some(GraphNode::Address(addr.clone())),
+                Owner::Object(parent) => Some(GraphNode::Object(parent.0.clone())),
+                Owner::Shared | Owner::Immutable => None,
+            };
+            if let Some(owner) = owner {
+                nodes.insert(owner.clone());
+                edges.insert(GraphEdge { owner, object: node });
+            }
+        }
+
+        Self {
+            nodes: nodes.into_iter().collect(),
+            edges: edges.into_iter().collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Graphviz rendering: addresses are boxes, objects ellipses.
+    pub fn to_dot(&self) -> String {
+        let quote = |node: &GraphNode| format!("\"{}\"", node.label().replace('"', "\\\""));
+
+        let mut dot = String::from("digraph objects {\n");
+        for node in &self.nodes {
+            let shape = match node {
+                GraphNode::Address(_) => "box",
+                GraphNode::Object(_) => "ellipse",
+            };
+            dot.push_str(&format!("    {} [shape={}];\n", quote(node), shape));
+        }
+        for edge in &self.edges {
+            dot.push_str(&format!("    {} -> {};\n", quote(&edge.owner), quote(&edge.object)));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InMemoryObjectStore, ObjectStore};
+    use sui_core::object::{ObjectData, ObjectID};
+
+    fn object(id: &str, owner: Owner) -> SuiObject {
+        SuiObject::new(ObjectID::new(id), owner, ObjectData::Coin { balance: 1 })
+    }
+
+    fn edge(owner: GraphNode, object: &str) -> GraphEdge {
+        GraphEdge { owner, object: GraphNode::Object(object.to_string()) }
+    }
+
+    #[tokio::test]
+    async fn parent_with_two_children_exports_as_a_tree() {
+        let store = InMemoryObjectStore::new();
+        store.put_object(object("parent", Owner::Address("alice".to_string()))).await.unwrap();
+        for child in ["child-a", "child-b"] {
+            store.put_object(object(child, Owner::Object(ObjectID::new("parent")))).await.unwrap();
+        }
+
+        let graph = store.export_graph().await.unwrap();
+
+        assert_eq!(
+            graph.nodes,
+            vec![
+                GraphNode::Address("alice".to_string()),
+                GraphNode::Object("child-a".to_string()),
+                GraphNode::Object("child-b".to_string()),
+                GraphNode::Object("parent".to_string()),
+            ]
+        );
+        let parent = GraphNode::Object("parent".to_string());
+        assert_eq!(
+            graph.edges,
+            vec![
+                edge(GraphNode::Address("alice".to_string()), "parent"),
+                edge(parent.clone(), "child-a"),
+                edge(parent, "child-b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn dot_export_lists_every_edge() {
+        let graph = ObjectGraph::from_objects(&[
+            object("parent", Owner::Address("alice".to_string())),
+            object("child", Owner::Object(ObjectID::new("parent"))),
+            object("pool", Owner::Shared),
+        ]);
+
+        let dot = graph.to_dot();
+
+        assert!(dot.contains("\"address:alice\" -> \"object:parent\""));
+        assert!(dot.contains("\"object:parent\" -> \"object:child\""));
+        assert!(dot.contains("\"object:pool\""));
+        assert!(!dot.contains("-> \"object:pool\""));
+    }
+}
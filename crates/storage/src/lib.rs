@@ -1,8 +1,60 @@
+mod buffered;
+mod cached;
+mod compressed;
+mod consistency;
+mod encoded;
+mod event_sourced;
+mod file;
+mod graph;
+mod overlay;
+mod replay;
+mod snapshot;
+mod tiered;
+mod versioned;
+
+pub use buffered::{spawn_checkpoint_flusher, BufferedCheckpointStore, DEFAULT_CHECKPOINT_FLUSH_COUNT};
+pub use cached::{CachedObjectStore, DEFAULT_OBJECT_CACHE_CAPACITY};
+pub use compressed::{CompressedEffectsStore, DEFAULT_COMPRESSION_LEVEL};
+pub use consistency::{ConsistencyReport, ConsistencyViolation};
+pub use encoded::{EncodedObjectStore, ObjectFormat};
+pub use event_sourced::{EventSourcedStore, StoreLogEntry, StoreMutation};
+pub use file::{Durability, FileEffectsStore};
+pub use graph::{GraphEdge, GraphNode, ObjectGraph};
+pub use overlay::OverlayObjectStore;
+pub use replay::reconstruct_object_at;
+pub use snapshot::StoreSnapshot;
+pub use tiered::{TierWritePolicy, TieredObjectStore};
+pub use versioned::{RollbackMode, VersionedObjectStore, DEFAULT_MAX_HISTORY_DEPTH};
+
 use async_trait::async_trait;
 use parking_lot::RwLock;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use sui_core::{object::SuiObject, transaction::TransactionDigest};
+use sui_core::{
+    messages::CheckpointSummary,
+    object::{normalize_address, ObjectID, ObjectValidationError, SuiObject},
+    transaction::TransactionDigest,
+};
+use sui_effects::{migrate_effects_json, TransactionEffects};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error("object {0} already exists")]
+    ObjectAlreadyExists(String),
+    #[error("object {id} serializes to {size} bytes, over the {max}-byte limit")]
+    ObjectTooLarge { id: String, size: usize, max: usize },
+    #[error("object {0} is immutable")]
+    ImmutableObject(String),
+    #[error("version {version} of object {id} is not available")]
+    VersionNotFound { id: String, version: u64 },
+    #[error("stored object is corrupt: {0}")]
+    CorruptObject(#[from] ObjectValidationError),
+    #[error("stored bytes of object {id} fail their checksum: expected {expected}, got {actual}")]
+    Corruption { id: String, expected: String, actual: String },
+}
 
 #[async_trait]
 pub trait ObjectStore: Send + Sync {
@@ -10,12 +62,122 @@ pub trait ObjectStore: Send + Sync {
     async fn put_object(&self, object: SuiObject) -> anyhow::Result<()>;
     async fn delete_object(&self, id: &str) -> anyhow::Result<()>;
     async fn list_objects(&self, owner: Option<&str>) -> anyhow::Result<Vec<SuiObject>>;
+
+    /// Fetches several objects in one call, returning them in the order of
+    /// `ids` with `None` for the missing ones.
+    ///
+    /// The default implementation reads one object at a time; stores that
+    /// can serve the whole batch in one round-trip should override it.
+    async fn get_objects(&self, ids: &[String]) -> anyhow::Result<Vec<Option<SuiObject>>> {
+        let mut objects = Vec::with_capacity(ids.len());
+        for id in ids {
+            objects.push(self.get_object(id).await?);
+        }
+        Ok(objects)
+    }
+
+    /// Stores a newly created object, failing with
+    /// [`StoreError::ObjectAlreadyExists`] rather than overwriting an
+    /// existing one. Mutations go through `put_object`.
+    ///
+    /// The default implementation is not atomic; stores that can check and
+    /// insert under one lock should override it.
+    async fn create_object(&self, object: SuiObject) -> anyhow::Result<()> {
+        if self.get_object(&object.id.0).await?.is_some() {
+            return Err(StoreError::ObjectAlreadyExists(object.id.0.clone()).into());
+        }
+        self.put_object(object).await
+    }
+
+    /// Deletes every object owned by the address `owner` and returns their
+    /// ids, sorted. Shared, immutable and object-owned objects are never
+    /// touched.
+    ///
+    /// The default implementation deletes one object at a time; stores that
+    /// can do it under one lock should override it.
+    async fn delete_by_owner(&self, owner: &str) -> anyhow::Result<Vec<ObjectID>> {
+        let mut ids: Vec<ObjectID> = self
+            .list_objects(Some(owner))
+            .await?
+            .into_iter()
+            .filter(|object| object.owner.matches_address(owner))
+            .map(|object| object.id)
+            .collect();
+        ids.sort_by(|a, b| a.0.cmp(&b.0));
+        for id in &ids {
+            self.delete_object(&id.0).await?;
+        }
+        Ok(ids)
+    }
+
+    /// Exports the ownership graph of every stored object.
+    async fn export_graph(&self) -> anyhow::Result<ObjectGraph> {
+        let objects = self.list_objects(None).await?;
+        Ok(ObjectGraph::from_objects(&objects))
+    }
+
+    /// Checks the store's invariants over every stored object; a debugging
+    /// aid for after long simulations. See [`ConsistencyReport::from_objects`].
+    async fn check_consistency(&self) -> anyhow::Result<ConsistencyReport> {
+        let objects = self.list_objects(None).await?;
+        Ok(ConsistencyReport::from_objects(&objects))
+    }
 }
 
 #[async_trait]
 pub trait EffectsStore: Send + Sync {
     async fn save_effects(&self, digest: &TransactionDigest, effects_json: &str) -> anyhow::Result<()>;
     async fn get_effects(&self, digest: &TransactionDigest) -> anyhow::Result<Option<String>>;
+
+    /// Every stored `(digest, effects JSON)` pair in the order they were
+    /// first saved, which for a validator is checkpoint sequence order.
+    async fn list_effects(&self) -> anyhow::Result<Vec<(TransactionDigest, String)>>;
+
+    /// Writes all stored effects to `writer` as newline-delimited JSON, in
+    /// [`EffectsStore::list_effects`] order, and returns how many were written.
+    async fn export_all(&self, writer: &mut (dyn Write + Send)) -> anyhow::Result<usize> {
+        let effects = self.list_effects().await?;
+        for (_, raw) in &effects {
+            // Re-encode compactly so that each entry occupies one line.
+            let value: serde_json::Value = serde_json::from_str(raw)?;
+            writeln!(writer, "{}", value)?;
+        }
+        writer.flush()?;
+        Ok(effects.len())
+    }
+
+    /// Loads effects written by [`EffectsStore::export_all`], upgrading older
+    /// schema versions, and returns how many were imported.
+    async fn import_all(&self, reader: &mut (dyn BufRead + Send)) -> anyhow::Result<usize> {
+        let mut entries = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let effects = migrate_effects_json(&line)?;
+            entries.push((effects.digest.clone(), serde_json::to_string(&effects)?));
+        }
+
+        for (digest, raw) in &entries {
+            self.save_effects(digest, raw).await?;
+        }
+        Ok(entries.len())
+    }
+
+    /// Makes every effects entry saved so far durable. A no-op for stores
+    /// that write through.
+    async fn sync(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Reads effects as a struct, migrating older schema versions on the way.
+    async fn get_effects_typed(&self, digest: &TransactionDigest) -> anyhow::Result<Option<TransactionEffects>> {
+        match self.get_effects(digest).await? {
+            Some(raw) => Ok(Some(migrate_effects_json(&raw)?)),
+            None => Ok(None),
+        }
+    }
 }
 
 #[async_trait]
@@ -23,18 +185,190 @@ pub trait CheckpointStore: Send + Sync {
     async fn save_checkpoint(&self, sequence: u64, checkpoint_json: &str) -> anyhow::Result<()>;
     async fn get_checkpoint(&self, sequence: u64) -> anyhow::Result<Option<String>>;
     async fn get_latest_sequence(&self) -> anyhow::Result<Option<u64>>;
+
+    /// Saves `summary` under its own sequence number.
+    async fn save_checkpoint_typed(&self, summary: &CheckpointSummary) -> anyhow::Result<()> {
+        let checkpoint_json = serde_json::to_string(summary)?;
+        self.save_checkpoint(summary.sequence_number, &checkpoint_json)
+            .await
+    }
+
+    /// Reads a checkpoint as a struct.
+    async fn get_checkpoint_typed(&self, sequence: u64) -> anyhow::Result<Option<CheckpointSummary>> {
+        match self.get_checkpoint(sequence).await? {
+            Some(raw) => Ok(Some(serde_json::from_str(&raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Saves several checkpoints at once. Stores with a cheaper bulk write
+    /// should override the default, which saves them one by one.
+    async fn save_checkpoints(&self, checkpoints: &[(u64, String)]) -> anyhow::Result<()> {
+        for (sequence, checkpoint) in checkpoints {
+            self.save_checkpoint(*sequence, checkpoint).await?;
+        }
+        Ok(())
+    }
+
+    /// Makes every checkpoint saved so far durable. A no-op for stores that
+    /// write through.
+    async fn flush(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Up to `n` of the most recent checkpoints, newest first. The default
+    /// walks back from the latest sequence one lookup at a time; stores that
+    /// can list their keys should override it.
+    async fn get_recent(&self, n: usize) -> anyhow::Result<Vec<(u64, String)>> {
+        let mut recent = Vec::new();
+        let Some(latest) = self.get_latest_sequence().await? else {
+            return Ok(recent);
+        };
+        for sequence in (0..=latest).rev() {
+            if recent.len() >= n {
+                break;
+            }
+            if let Some(checkpoint) = self.get_checkpoint(sequence).await? {
+                recent.push((sequence, checkpoint));
+            }
+        }
+        Ok(recent)
+    }
+}
+
+/// Approximate in-memory footprint of an object: its serialized JSON length.
+pub fn serialized_size(object: &SuiObject) -> usize {
+    serde_json::to_vec(object).map(|bytes| bytes.len()).unwrap_or(0)
 }
 
 pub struct InMemoryObjectStore {
-    objects: Arc<RwLock<HashMap<String, SuiObject>>>,
+    /// Copy-on-write: writers go through `Arc::make_mut`, so outstanding
+    /// snapshots keep the map they were taken from.
+    objects: Arc<RwLock<Arc<HashMap<String, SuiObject>>>>,
+    /// Ids of address-owned objects by normalized owner address, so owner
+    /// listings cost the size of the result rather than of the store. Only
+    /// updated while the `objects` write lock is held.
+    owners: Arc<RwLock<HashMap<String, HashSet<String>>>>,
+    /// Sum of `serialized_size` over stored objects; only updated while the
+    /// `objects` write lock is held.
+    memory_usage: Arc<AtomicUsize>,
+    capacity_bytes: Option<usize>,
+    max_object_size: Option<usize>,
+}
+
+impl Default for InMemoryObjectStore {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl InMemoryObjectStore {
     pub fn new() -> Self {
         Self {
-            objects: Arc::new(RwLock::new(HashMap::new())),
+            objects: Arc::new(RwLock::new(Arc::new(HashMap::new()))),
+            owners: Arc::new(RwLock::new(HashMap::new())),
+            memory_usage: Arc::new(AtomicUsize::new(0)),
+            capacity_bytes: None,
+            max_object_size: None,
+        }
+    }
+
+    /// Rejects any object whose serialized size exceeds `max_bytes`.
+    pub fn with_max_object_size(mut self, max_bytes: usize) -> Self {
+        self.max_object_size = Some(max_bytes);
+        self
+    }
+
+    /// Rejects puts which would take the store's memory usage past
+    /// `capacity_bytes`.
+    pub fn with_capacity_bytes(mut self, capacity_bytes: usize) -> Self {
+        self.capacity_bytes = Some(capacity_bytes);
+        self
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        self.memory_usage.load(Ordering::SeqCst)
+    }
+
+    /// A consistent view of the store as of now, unaffected by later writes.
+    pub fn snapshot(&self) -> StoreSnapshot {
+        StoreSnapshot::new(Arc::clone(&self.objects.read()))
+    }
+
+    /// Inserts `object` into the already locked map, enforcing the capacity.
+    fn insert_locked(&self, objects: &mut HashMap<String, SuiObject>, object: SuiObject) -> anyhow::Result<()> {
+        let new_size = serialized_size(&object);
+        if let Some(max) = self.max_object_size {
+            if new_size > max {
+                return Err(StoreError::ObjectTooLarge {
+                    id: object.id.0.clone(),
+                    size: new_size,
+                    max,
+                }
+                .into());
+            }
+        }
+        let old_size = objects.get(&object.id.0).map(serialized_size).unwrap_or(0);
+        let usage = self.memory_usage.load(Ordering::SeqCst) - old_size + new_size;
+
+        if let Some(capacity) = self.capacity_bytes {
+            if usage > capacity {
+                anyhow::bail!(
+                    "object store capacity exceeded: storing {} needs {} bytes, capacity is {}",
+                    object.id.0,
+                    usage,
+                    capacity
+                );
+            }
+        }
+
+        let previous_owner = objects.get(&object.id.0).and_then(|old| old.owner.as_address());
+        if previous_owner != object.owner.as_address() {
+            let mut owners = self.owners.write();
+            if let Some(previous) = previous_owner {
+                Self::unindex(&mut owners, previous, &object.id.0);
+            }
+            if let Some(owner) = object.owner.as_address() {
+                owners
+                    .entry(normalize_address(owner))
+                    .or_default()
+                    .insert(object.id.0.clone());
+            }
+        }
+        objects.insert(object.id.0.clone(), object);
+        self.memory_usage.store(usage, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Removes `id` from the locked map and the owner index.
+    fn remove_locked(&self, objects: &mut HashMap<String, SuiObject>, id: &str) {
+        if let Some(removed) = objects.remove(id) {
+            if let Some(owner) = removed.owner.as_address() {
+                Self::unindex(&mut self.owners.write(), owner, id);
+            }
+            self.memory_usage
+                .fetch_sub(serialized_size(&removed), Ordering::SeqCst);
+        }
+    }
+
+    fn unindex(owners: &mut HashMap<String, HashSet<String>>, owner: &str, id: &str) {
+        let owner = normalize_address(owner);
+        if let Some(ids) = owners.get_mut(&owner) {
+            ids.remove(id);
+            if ids.is_empty() {
+                owners.remove(&owner);
+            }
         }
     }
+
+    /// Ids of the objects owned by the address `owner`, from the index.
+    fn owned_ids(&self, owner: &str) -> Vec<String> {
+        self.owners
+            .read()
+            .get(&normalize_address(owner))
+            .map(|ids| ids.iter().cloned().collect())
+            .unwrap_or_default()
+    }
 }
 
 #[async_trait]
@@ -43,41 +377,90 @@ impl ObjectStore for InMemoryObjectStore {
         Ok(self.objects.read().get(id).cloned())
     }
 
+    async fn get_objects(&self, ids: &[String]) -> anyhow::Result<Vec<Option<SuiObject>>> {
+        let objects = self.objects.read();
+        Ok(ids.iter().map(|id| objects.get(id).cloned()).collect())
+    }
+
     async fn put_object(&self, object: SuiObject) -> anyhow::Result<()> {
-        self.objects.write().insert(object.id.0.clone(), object);
-        Ok(())
+        let mut objects = self.objects.write();
+        self.insert_locked(Arc::make_mut(&mut objects), object)
+    }
+
+    async fn create_object(&self, object: SuiObject) -> anyhow::Result<()> {
+        let mut objects = self.objects.write();
+        if objects.contains_key(&object.id.0) {
+            return Err(StoreError::ObjectAlreadyExists(object.id.0.clone()).into());
+        }
+        self.insert_locked(Arc::make_mut(&mut objects), object)
     }
 
     async fn delete_object(&self, id: &str) -> anyhow::Result<()> {
-        self.objects.write().remove(id);
+        let mut objects = self.objects.write();
+        if !objects.contains_key(id) {
+            return Ok(());
+        }
+        self.remove_locked(Arc::make_mut(&mut objects), id);
         Ok(())
     }
 
+    async fn delete_by_owner(&self, owner: &str) -> anyhow::Result<Vec<ObjectID>> {
+        let mut objects = self.objects.write();
+        let mut ids = self.owned_ids(owner);
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        ids.sort();
+
+        let objects = Arc::make_mut(&mut objects);
+        for id in &ids {
+            self.remove_locked(objects, id);
+        }
+        Ok(ids.into_iter().map(ObjectID).collect())
+    }
+
+    /// Owner listings go through the owner index; full listings iterate a
+    /// snapshot so that they do not hold the lock.
     async fn list_objects(&self, owner: Option<&str>) -> anyhow::Result<Vec<SuiObject>> {
+        let Some(owner) = owner else {
+            return Ok(self.snapshot().list_objects(None));
+        };
         let objects = self.objects.read();
-        if let Some(owner_addr) = owner {
-            Ok(objects
-                .values()
-                .filter(|obj| match &obj.owner {
-                    sui_core::Owner::Address(addr) => addr == owner_addr,
-                    _ => false,
-                })
-                .cloned()
-                .collect())
-        } else {
-            Ok(objects.values().cloned().collect())
-        }
+        Ok(self
+            .owned_ids(owner)
+            .iter()
+            .filter_map(|id| objects.get(id).cloned())
+            .collect())
     }
 }
 
 pub struct InMemoryEffectsStore {
     effects: Arc<RwLock<HashMap<String, String>>>,
+    /// Digests in first-save order; only updated while `effects` is locked.
+    order: Arc<RwLock<Vec<String>>>,
+}
+
+impl Default for InMemoryEffectsStore {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl InMemoryEffectsStore {
     pub fn new() -> Self {
         Self {
             effects: Arc::new(RwLock::new(HashMap::new())),
+            order: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    fn insert(&self, digest: &TransactionDigest, effects_json: &str) {
+        let mut effects = self.effects.write();
+        if effects
+            .insert(digest.0.clone(), effects_json.to_string())
+            .is_none()
+        {
+            self.order.write().push(digest.0.clone());
         }
     }
 }
@@ -85,13 +468,26 @@ impl InMemoryEffectsStore {
 #[async_trait]
 impl EffectsStore for InMemoryEffectsStore {
     async fn save_effects(&self, digest: &TransactionDigest, effects_json: &str) -> anyhow::Result<()> {
-        self.effects.write().insert(digest.0.clone(), effects_json.to_string());
+        self.insert(digest, effects_json);
         Ok(())
     }
 
     async fn get_effects(&self, digest: &TransactionDigest) -> anyhow::Result<Option<String>> {
         Ok(self.effects.read().get(&digest.0).cloned())
     }
+
+    async fn list_effects(&self) -> anyhow::Result<Vec<(TransactionDigest, String)>> {
+        let effects = self.effects.read();
+        let order = self.order.read();
+        Ok(order
+            .iter()
+            .filter_map(|digest| {
+                effects
+                    .get(digest)
+                    .map(|raw| (TransactionDigest(digest.clone()), raw.clone()))
+            })
+            .collect())
+    }
 }
 
 pub struct InMemoryCheckpointStore {
@@ -99,6 +495,12 @@ pub struct InMemoryCheckpointStore {
     latest: Arc<RwLock<Option<u64>>>,
 }
 
+impl Default for InMemoryCheckpointStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl InMemoryCheckpointStore {
     pub fn new() -> Self {
         Self {
@@ -123,5 +525,340 @@ impl CheckpointStore for InMemoryCheckpointStore {
     async fn get_latest_sequence(&self) -> anyhow::Result<Option<u64>> {
         Ok(*self.latest.read())
     }
+
+    async fn get_recent(&self, n: usize) -> anyhow::Result<Vec<(u64, String)>> {
+        let checkpoints = self.checkpoints.read();
+        let mut sequences: Vec<u64> = checkpoints.keys().copied().collect();
+        sequences.sort_unstable_by(|a, b| b.cmp(a));
+        Ok(sequences
+            .into_iter()
+            .take(n)
+            .map(|sequence| (sequence, checkpoints[&sequence].clone()))
+            .collect())
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use sui_core::object::{ObjectData, Owner};
+
+    fn coin(id: &str, balance: u64) -> SuiObject {
+        SuiObject::new(ObjectID::new(id), Owner::Address("alice".to_string()), ObjectData::Coin { balance })
+    }
+
+    #[tokio::test]
+    async fn puts_under_the_capacity_succeed() {
+        let size = serialized_size(&coin("coin-a", 1));
+        let store = InMemoryObjectStore::new().with_capacity_bytes(size * 2);
+
+        store.put_object(coin("coin-a", 1)).await.unwrap();
+        store.put_object(coin("coin-b", 1)).await.unwrap();
+
+        assert_eq!(store.memory_usage(), size * 2);
+    }
+
+    #[tokio::test]
+    async fn snapshot_keeps_the_state_it_was_taken_at() {
+        let store = InMemoryObjectStore::new();
+        store.put_object(coin("coin-a", 1)).await.unwrap();
+        let snapshot = store.snapshot();
+
+        store.put_object(coin("coin-a", 2)).await.unwrap();
+        store.put_object(coin("coin-b", 3)).await.unwrap();
+
+        assert!(matches!(snapshot.get_object("coin-a").unwrap().data, ObjectData::Coin { balance: 1 }));
+        assert!(snapshot.get_object("coin-b").is_none());
+        assert_eq!(snapshot.list_objects(Some("alice")).len(), 1);
+        let live = store.get_object("coin-a").await.unwrap().unwrap();
+        assert!(matches!(live.data, ObjectData::Coin { balance: 2 }));
+        assert_eq!(store.snapshot().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn held_snapshot_does_not_block_writers() {
+        let store = Arc::new(InMemoryObjectStore::new());
+        store.put_object(coin("coin-a", 1)).await.unwrap();
+        let snapshot = store.snapshot();
+
+        let writer = Arc::clone(&store);
+        let write = tokio::spawn(async move {
+            for balance in 0..100 {
+                writer.put_object(coin(&format!("coin-{}", balance), balance)).await.unwrap();
+            }
+        });
+        tokio::time::timeout(std::time::Duration::from_secs(5), write).await.unwrap().unwrap();
+
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(store.snapshot().len(), 101);
+    }
+
+    async fn five_checkpoints(store: &dyn CheckpointStore) {
+        for sequence in 1..=5 {
+            store.save_checkpoint(sequence, &format!("checkpoint-{}", sequence)).await.unwrap();
+        }
+    }
+
+    fn sequences(recent: &[(u64, String)]) -> Vec<u64> {
+        recent.iter().map(|(sequence, _)| *sequence).collect()
+    }
+
+    #[tokio::test]
+    async fn recent_checkpoints_come_newest_first() {
+        let store = InMemoryCheckpointStore::new();
+        five_checkpoints(&store).await;
+
+        let recent = store.get_recent(3).await.unwrap();
+
+        assert_eq!(sequences(&recent), vec![5, 4, 3]);
+        assert_eq!(recent[0].1, "checkpoint-5");
+        assert_eq!(sequences(&store.get_recent(10).await.unwrap()), vec![5, 4, 3, 2, 1]);
+    }
+
+    #[tokio::test]
+    async fn default_recent_checkpoints_walk_back_from_the_latest() {
+        let store = BufferedCheckpointStore::new(Arc::new(InMemoryCheckpointStore::new()));
+        five_checkpoints(&store).await;
+
+        assert_eq!(sequences(&store.get_recent(3).await.unwrap()), vec![5, 4, 3]);
+        assert_eq!(sequences(&store.get_recent(10).await.unwrap()), vec![5, 4, 3, 2, 1]);
+    }
+
+    #[tokio::test]
+    async fn typed_checkpoints_round_trip_with_every_field() {
+        let store = InMemoryCheckpointStore::new();
+        let mut summary = CheckpointSummary::new(7, "root");
+        summary.record_transaction(300, 1, 2, 1);
+        summary.timestamp_ms = 1_234;
+        summary.objects_root = "objects".to_string();
+        summary.previous_digest = Some("previous".to_string());
+        summary.consensus_position = Some(9);
+
+        store.save_checkpoint_typed(&summary).await.unwrap();
+        let loaded = store.get_checkpoint_typed(7).await.unwrap().unwrap();
+
+        assert_eq!(serde_json::to_value(&loaded).unwrap(), serde_json::to_value(&summary).unwrap());
+        assert_eq!((loaded.sequence_number, loaded.transaction_count, loaded.total_gas_used), (7, 1, 300));
+        assert_eq!((loaded.objects_created, loaded.objects_mutated, loaded.objects_deleted), (1, 2, 1));
+        assert_eq!(loaded.consensus_position, Some(9));
+        assert_eq!(loaded.digest(), summary.digest());
+        assert_eq!(store.get_latest_sequence().await.unwrap(), Some(7));
+    }
+
+    #[tokio::test]
+    async fn typed_reads_report_missing_and_unparseable_checkpoints() {
+        let store = InMemoryCheckpointStore::new();
+        store.save_checkpoint(1, "not json").await.unwrap();
+
+        assert!(store.get_checkpoint_typed(2).await.unwrap().is_none());
+        assert!(store.get_checkpoint_typed(1).await.is_err());
+    }
+
+    fn owned(id: &str, owner: Owner) -> SuiObject {
+        SuiObject { owner, ..coin(id, 1) }
+    }
+
+    async fn listed(store: &InMemoryObjectStore, owner: &str) -> Vec<String> {
+        let mut ids: Vec<String> =
+            store.list_objects(Some(owner)).await.unwrap().into_iter().map(|object| object.id.0).collect();
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn owner_listings_follow_transfers() {
+        let store = InMemoryObjectStore::new();
+        store.put_object(coin("coin-a", 1)).await.unwrap();
+        store.put_object(coin("coin-b", 1)).await.unwrap();
+
+        store.put_object(owned("coin-a", Owner::Address("0xBob".to_string()))).await.unwrap();
+        store.put_object(owned("coin-b", Owner::Address("alice".to_string()))).await.unwrap();
+
+        assert_eq!(listed(&store, "alice").await, vec!["coin-b"]);
+        assert_eq!(listed(&store, "bob").await, vec!["coin-a"]);
+        assert_eq!(listed(&store, "0xbob").await, vec!["coin-a"]);
+        assert_eq!(store.owners.read().values().map(HashSet::len).sum::<usize>(), 2);
+    }
+
+    #[tokio::test]
+    async fn objects_leave_the_owner_index_when_shared_or_deleted() {
+        let store = InMemoryObjectStore::new();
+        for id in ["coin-a", "coin-b", "coin-c"] {
+            store.put_object(coin(id, 1)).await.unwrap();
+        }
+
+        store.put_object(owned("coin-a", Owner::Shared)).await.unwrap();
+        store.delete_object("coin-b").await.unwrap();
+
+        assert_eq!(listed(&store, "alice").await, vec!["coin-c"]);
+        store.delete_object("coin-c").await.unwrap();
+        assert!(listed(&store, "alice").await.is_empty());
+        assert!(store.owners.read().is_empty());
+        assert_eq!(store.list_objects(None).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn put_over_the_capacity_is_rejected() {
+        let size = serialized_size(&coin("coin-a", 1));
+        let store = InMemoryObjectStore::new().with_capacity_bytes(size * 2 - 1);
+        store.put_object(coin("coin-a", 1)).await.unwrap();
+
+        let err = store.put_object(coin("coin-b", 1)).await.unwrap_err();
+
+        assert!(err.to_string().contains("capacity exceeded"));
+        assert!(store.get_object("coin-b").await.unwrap().is_none());
+        assert_eq!(store.memory_usage(), size);
+    }
+
+    #[tokio::test]
+    async fn memory_usage_follows_overwrites_and_deletes() {
+        let store = InMemoryObjectStore::new();
+        store.put_object(coin("coin-a", 1)).await.unwrap();
+        store.put_object(coin("coin-b", 1)).await.unwrap();
+        store.put_object(coin("coin-a", 1_000_000)).await.unwrap();
+
+        let expected = serialized_size(&coin("coin-a", 1_000_000)) + serialized_size(&coin("coin-b", 1));
+        assert_eq!(store.memory_usage(), expected);
+
+        store.delete_object("coin-b").await.unwrap();
+        assert_eq!(store.memory_usage(), serialized_size(&coin("coin-a", 1_000_000)));
+        store.delete_object("coin-a").await.unwrap();
+        assert_eq!(store.memory_usage(), 0);
+    }
+
+    fn effects(digest: &str, object: &str) -> TransactionEffects {
+        let mut effects = TransactionEffects::new(TransactionDigest(digest.to_string()));
+        effects.mutated.push(coin(object, 5));
+        effects.deleted.push(ObjectID::new("old"));
+        effects
+    }
+
+    #[tokio::test]
+    async fn export_then_import_reproduces_the_effects() {
+        let source = InMemoryEffectsStore::new();
+        for (digest, object) in [("tx-3", "c"), ("tx-1", "a"), ("tx-2", "b")] {
+            let raw = serde_json::to_string_pretty(&effects(digest, object)).unwrap();
+            source.save_effects(&TransactionDigest(digest.to_string()), &raw).await.unwrap();
+        }
+
+        let mut exported = Vec::new();
+        assert_eq!(source.export_all(&mut exported).await.unwrap(), 3);
+        assert_eq!(String::from_utf8(exported.clone()).unwrap().lines().count(), 3);
+        let target = InMemoryEffectsStore::new();
+        assert_eq!(target.import_all(&mut exported.as_slice()).await.unwrap(), 3);
+
+        let digests: Vec<_> = target.list_effects().await.unwrap().into_iter().map(|(digest, _)| digest.0).collect();
+        assert_eq!(digests, ["tx-3", "tx-1", "tx-2"]);
+        for (digest, object) in [("tx-1", "a"), ("tx-2", "b"), ("tx-3", "c")] {
+            let imported = target.get_effects_typed(&TransactionDigest(digest.to_string())).await.unwrap().unwrap();
+            assert_eq!(
+                serde_json::to_value(&imported).unwrap(),
+                serde_json::to_value(effects(digest, object)).unwrap()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn import_upgrades_old_schema_versions() {
+        let old = r#"{"digest":"tx-1","created":[],"mutated":[],"events":[]}"#;
+        let store = InMemoryEffectsStore::new();
+
+        assert_eq!(store.import_all(&mut format!("{}\n\n", old).as_bytes()).await.unwrap(), 1);
+
+        let imported = store.get_effects_typed(&TransactionDigest("tx-1".to_string())).await.unwrap().unwrap();
+        assert_eq!(imported.schema_version, sui_effects::EFFECTS_SCHEMA_VERSION);
+    }
+
+    #[tokio::test]
+    async fn creating_an_existing_id_fails() {
+        let store = InMemoryObjectStore::new();
+        store.create_object(coin("coin-a", 1)).await.unwrap();
+
+        let err = store.create_object(coin("coin-a", 2)).await.unwrap_err();
+
+        assert!(matches!(err.downcast_ref::<StoreError>(), Some(StoreError::ObjectAlreadyExists(id)) if id == "coin-a"));
+        let stored = store.get_object("coin-a").await.unwrap().unwrap();
+        assert!(matches!(stored.data, ObjectData::Coin { balance: 1 }));
+    }
+
+    async fn alice_bob_and_shared(store: &dyn ObjectStore) {
+        let objects = [
+            ("alice-1", Owner::Address("alice".to_string())),
+            ("alice-2", Owner::Address("alice".to_string())),
+            ("bob-1", Owner::Address("bob".to_string())),
+            ("shared", Owner::Shared),
+            ("frozen", Owner::Immutable),
+            ("child", Owner::Object(ObjectID::new("alice-1"))),
+        ];
+        for (id, owner) in objects {
+            let object = SuiObject::new(ObjectID::new(id), owner, ObjectData::Coin { balance: 1 });
+            store.put_object(object).await.unwrap();
+        }
+    }
+
+    async fn remaining(store: &dyn ObjectStore) -> Vec<String> {
+        let objects = store.list_objects(None).await.unwrap();
+        let mut ids: Vec<String> = objects.into_iter().map(|object| object.id.0).collect();
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn delete_by_owner_removes_exactly_that_owners_objects() {
+        let store = InMemoryObjectStore::new();
+        alice_bob_and_shared(&store).await;
+
+        let deleted = store.delete_by_owner("alice").await.unwrap();
+
+        assert_eq!(deleted, vec![ObjectID::new("alice-1"), ObjectID::new("alice-2")]);
+        assert_eq!(remaining(&store).await, vec!["bob-1", "child", "frozen", "shared"]);
+        assert!(store.list_objects(Some("alice")).await.unwrap().is_empty());
+        assert!(store.delete_by_owner("alice").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn default_delete_by_owner_leaves_other_owners_intact() {
+        let base = Arc::new(InMemoryObjectStore::new());
+        alice_bob_and_shared(base.as_ref()).await;
+        let store = OverlayObjectStore::new(base.clone());
+
+        let deleted = store.delete_by_owner("alice").await.unwrap();
+
+        assert_eq!(deleted, vec![ObjectID::new("alice-1"), ObjectID::new("alice-2")]);
+        assert_eq!(remaining(&store).await, vec!["bob-1", "child", "frozen", "shared"]);
+        assert_eq!(remaining(base.as_ref()).await.len(), 6);
+    }
+
+    #[tokio::test]
+    async fn objects_over_the_size_limit_are_rejected() {
+        let store = InMemoryObjectStore::new().with_max_object_size(1_024);
+        let fields = json!({ "bio": "x".repeat(2_000) });
+        let oversized = SuiObject::new(
+            ObjectID::new("hero"),
+            Owner::Address("alice".to_string()),
+            ObjectData::MoveStruct { type_name: "game::Profile".to_string(), fields },
+        );
+
+        store.put_object(coin("coin-a", 1)).await.unwrap();
+        let err = store.put_object(oversized).await.unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<StoreError>(),
+            Some(StoreError::ObjectTooLarge { id, size, max: 1_024 }) if id == "hero" && *size > 2_000
+        ));
+        assert!(store.get_object("hero").await.unwrap().is_none());
+        assert_eq!(store.memory_usage(), serialized_size(&coin("coin-a", 1)));
+    }
+
+    #[tokio::test]
+    async fn put_overwrites_an_existing_object() {
+        let store = InMemoryObjectStore::new();
+        store.create_object(coin("coin-a", 1)).await.unwrap();
+
+        store.put_object(coin("coin-a", 2)).await.unwrap();
+
+        let stored = store.get_object("coin-a").await.unwrap().unwrap();
+        assert!(matches!(stored.data, ObjectData::Coin { balance: 2 }));
+    }
+}
@@ -0,0 +1,90 @@
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+use sui_core::object::{ObjectID, SuiObject};
+
+use crate::ObjectStore;
+
+/// Object store layered over another that keeps every write to itself.
+///
+/// Reads see the overlay's own writes and deletions first and fall through
+/// to the base store otherwise; the base store is never written. A group of
+/// transactions can thereby execute against their combined state and be
+/// thrown away as a whole by dropping the overlay.
+pub struct OverlayObjectStore {
+    base: Arc<dyn ObjectStore>,
+    /// `None` marks a deletion.
+    changes: RwLock<HashMap<String, Option<SuiObject>>>,
+}
+
+impl OverlayObjectStore {
+    pub fn new(base: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            base,
+            changes: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Objects written and ids deleted through the overlay, each ordered by
+    /// object id.
+    pub fn changes(&self) -> (Vec<SuiObject>, Vec<ObjectID>) {
+        let changes = self.changes.read();
+        let mut ids: Vec<&String> = changes.keys().collect();
+        ids.sort();
+        let mut written = Vec::new();
+        let mut deleted = Vec::new();
+        for id in ids {
+            match &changes[id] {
+                Some(object) => written.push(object.clone()),
+                None => deleted.push(ObjectID(id.clone())),
+            }
+        }
+        (written, deleted)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.read().is_empty()
+    }
+}
+
+#[async_trait]
+impl ObjectStore for OverlayObjectStore {
+    async fn get_object(&self, id: &str) -> anyhow::Result<Option<SuiObject>> {
+        let changed = self.changes.read().get(id).cloned();
+        match changed {
+            Some(object) => Ok(object),
+            None => self.base.get_object(id).await,
+        }
+    }
+
+    async fn put_object(&self, object: SuiObject) -> anyhow::Result<()> {
+        self.changes.write().insert(object.id.0.clone(), Some(object));
+        Ok(())
+    }
+
+    async fn delete_object(&self, id: &str) -> anyhow::Result<()> {
+        self.changes.write().insert(id.to_string(), None);
+        Ok(())
+    }
+
+    async fn list_objects(&self, owner: Option<&str>) -> anyhow::Result<Vec<SuiObject>> {
+        let stored = self.base.list_objects(owner).await?;
+        let changes = self.changes.read();
+        let mut objects: Vec<SuiObject> = stored
+            .into_iter()
+            .filter(|object| !changes.contains_key(&object.id.0))
+            .collect();
+        objects.extend(
+            changes
+                .values()
+                .flatten()
+                .filter(|object| match owner {
+                    Some(addr) => object.owner.matches_address(addr),
+                    None => true,
+                })
+                .cloned(),
+        );
+        Ok(objects)
+    }
+}
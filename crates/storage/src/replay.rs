@@ -0,0 +1,94 @@
+use sui_core::object::SuiObject;
+use sui_effects::migrate_effects_json;
+
+use crate::EffectsStore;
+
+/// Rebuilds the state of `object_id` as of checkpoint `up_to_sequence` by
+/// replaying stored effects, without needing a versioned object store.
+///
+/// Returns `None` if the object did not exist yet or had been deleted at
+/// that point. Sequence numbers are taken from the store's insertion order:
+/// the n-th saved effects belong to checkpoint n, as they do for a validator
+/// that commits one transaction per checkpoint from genesis.
+pub async fn reconstruct_object_at(
+    effects_store: &dyn EffectsStore,
+    object_id: &str,
+    up_to_sequence: u64,
+) -> anyhow::Result<Option<SuiObject>> {
+    let mut state = None;
+    for (raw, _) in effects_store
+        .list_effects()
+        .await?
+        .into_iter()
+        .map(|(_, raw)| raw)
+        .zip(1..=up_to_sequence)
+    {
+        let effects = migrate_effects_json(&raw)?;
+        if let Some(written) = effects
+            .created
+            .iter()
+            .chain(&effects.mutated)
+            .find(|object| object.id.0 == object_id)
+        {
+            state = Some(written.clone());
+        }
+        if effects.deleted.iter().any(|id| id.0 == object_id) {
+            state = None;
+        }
+    }
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryEffectsStore;
+    use sui_core::object::{ObjectData, ObjectID, Owner};
+    use sui_core::transaction::TransactionDigest;
+    use sui_effects::TransactionEffects;
+
+    fn coin(owner: &str) -> SuiObject {
+        SuiObject::new(ObjectID::new("coin"), Owner::Address(owner.to_string()), ObjectData::Coin { balance: 10 })
+    }
+
+    async fn record(store: &InMemoryEffectsStore, digest: &str, change: impl FnOnce(&mut TransactionEffects)) {
+        let mut effects = TransactionEffects::new(TransactionDigest(digest.to_string()));
+        change(&mut effects);
+        let raw = serde_json::to_string(&effects).unwrap();
+        store.save_effects(&effects.digest, &raw).await.unwrap();
+    }
+
+    /// Creates the coin for alice, then transfers it to bob and to carol, and
+    /// finally deletes it, one checkpoint each.
+    async fn history() -> InMemoryEffectsStore {
+        let store = InMemoryEffectsStore::new();
+        record(&store, "tx-1", |effects| effects.created.push(coin("alice"))).await;
+        record(&store, "tx-2", |effects| effects.mutated.push(coin("bob"))).await;
+        record(&store, "tx-3", |effects| effects.mutated.push(coin("carol"))).await;
+        record(&store, "tx-4", |effects| effects.deleted.push(ObjectID::new("coin"))).await;
+        store
+    }
+
+    async fn owner_at(store: &InMemoryEffectsStore, sequence: u64) -> Option<Owner> {
+        reconstruct_object_at(store, "coin", sequence).await.unwrap().map(|object| object.owner)
+    }
+
+    #[tokio::test]
+    async fn reconstructs_the_owner_at_each_intermediate_sequence() {
+        let store = history().await;
+
+        assert_eq!(owner_at(&store, 1).await, Some(Owner::Address("alice".to_string())));
+        assert_eq!(owner_at(&store, 2).await, Some(Owner::Address("bob".to_string())));
+        assert_eq!(owner_at(&store, 3).await, Some(Owner::Address("carol".to_string())));
+    }
+
+    #[tokio::test]
+    async fn object_is_absent_before_creation_and_after_deletion() {
+        let store = history().await;
+
+        assert_eq!(owner_at(&store, 0).await, None);
+        assert_eq!(owner_at(&store, 4).await, None);
+        assert_eq!(owner_at(&store, 100).await, None);
+        assert!(reconstruct_object_at(&store, "unknown", 3).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use sui_core::object::SuiObject;
+
+/// Immutable point-in-time view of an `InMemoryObjectStore`.
+///
+/// Taking a snapshot only clones a pointer; the store copies its map on the
+/// next write instead, so snapshot reads never hold a lock or block writers.
+#[derive(Debug, Clone)]
+pub struct StoreSnapshot {
+    objects: Arc<HashMap<String, SuiObject>>,
+}
+
+impl StoreSnapshot {
+    pub(crate) fn new(objects: Arc<HashMap<String, SuiObject>>) -> Self {
+        Self { objects }
+    }
+
+    pub fn get_object(&self, id: &str) -> Option<&SuiObject> {
+        self.objects.get(id)
+    }
+
+    pub fn list_objects(&self, owner: Option<&str>) -> Vec<SuiObject> {
+        self.objects
+            .values()
+            .filter(|obj| match owner {
+                Some(addr) => obj.owner.matches_address(addr),
+                None => true,
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+}
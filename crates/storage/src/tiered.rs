@@ -0,0 +1,253 @@
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use sui_core::object::SuiObject;
+
+use crate::ObjectStore;
+
+/// When writes to a [`TieredObjectStore`] reach the cold store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TierWritePolicy {
+    /// Every write goes to the cold store, then the hot one.
+    #[default]
+    WriteThrough,
+    /// Writes only go to the hot store until [`TieredObjectStore::flush`].
+    WriteBack,
+}
+
+/// A fast store in front of a slow one, e.g. memory in front of disk.
+///
+/// Reads try the hot store first and fall through to the cold store on a
+/// miss, copying what they find into the hot store when promotion is on.
+/// The cold store holds every object; the hot store holds a subset, plus,
+/// under write-back, writes not yet flushed.
+pub struct TieredObjectStore {
+    hot: Arc<dyn ObjectStore>,
+    cold: Arc<dyn ObjectStore>,
+    promote: bool,
+    write_policy: TierWritePolicy,
+    /// Ids written or deleted in the hot store but not yet in the cold one.
+    dirty: Mutex<HashSet<String>>,
+    hot_hits: AtomicU64,
+    cold_hits: AtomicU64,
+}
+
+impl TieredObjectStore {
+    /// Write-through, promoting cold reads into the hot store.
+    pub fn new(hot: Arc<dyn ObjectStore>, cold: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            hot,
+            cold,
+            promote: true,
+            write_policy: TierWritePolicy::WriteThrough,
+            dirty: Mutex::new(HashSet::new()),
+            hot_hits: AtomicU64::new(0),
+            cold_hits: AtomicU64::new(0),
+        }
+    }
+
+    /// Whether objects read from the cold store are copied into the hot one.
+    pub fn with_promotion(mut self, promote: bool) -> Self {
+        self.promote = promote;
+        self
+    }
+
+    pub fn with_write_policy(mut self, policy: TierWritePolicy) -> Self {
+        self.write_policy = policy;
+        self
+    }
+
+    pub fn write_policy(&self) -> TierWritePolicy {
+        self.write_policy
+    }
+
+    /// Reads served by the hot store.
+    pub fn hot_hits(&self) -> u64 {
+        self.hot_hits.load(Ordering::SeqCst)
+    }
+
+    /// Reads that fell through to the cold store and found the object.
+    pub fn cold_hits(&self) -> u64 {
+        self.cold_hits.load(Ordering::SeqCst)
+    }
+
+    /// Writes not yet in the cold store; always 0 under write-through.
+    pub fn dirty_len(&self) -> usize {
+        self.dirty.lock().len()
+    }
+
+    /// Copies every pending write-back change into the cold store and
+    /// returns how many there were. Changes that fail to copy stay pending.
+    pub async fn flush(&self) -> anyhow::Result<usize> {
+        let pending: Vec<String> = self.dirty.lock().drain().collect();
+        let mut flushed = 0;
+        for (index, id) in pending.iter().enumerate() {
+            let result = match self.hot.get_object(id).await {
+                Ok(Some(object)) => self.cold.put_object(object).await,
+                Ok(None) => self.cold.delete_object(id).await,
+                Err(err) => Err(err),
+            };
+            if let Err(err) = result {
+                self.dirty.lock().extend(pending[index..].iter().cloned());
+                return Err(err);
+            }
+            flushed += 1;
+        }
+        Ok(flushed)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for TieredObjectStore {
+    async fn get_object(&self, id: &str) -> anyhow::Result<Option<SuiObject>> {
+        if let Some(object) = self.hot.get_object(id).await? {
+            self.hot_hits.fetch_add(1, Ordering::SeqCst);
+            return Ok(Some(object));
+        }
+        // Deleted in the hot store, not yet in the cold one.
+        if self.dirty.lock().contains(id) {
+            return Ok(None);
+        }
+        let object = self.cold.get_object(id).await?;
+        if let Some(object) = &object {
+            self.cold_hits.fetch_add(1, Ordering::SeqCst);
+            if self.promote {
+                self.hot.put_object(object.clone()).await?;
+            }
+        }
+        Ok(object)
+    }
+
+    async fn put_object(&self, object: SuiObject) -> anyhow::Result<()> {
+        match self.write_policy {
+            TierWritePolicy::WriteThrough => {
+                self.cold.put_object(object.clone()).await?;
+                self.hot.put_object(object).await
+            }
+            TierWritePolicy::WriteBack => {
+                let id = object.id.0.clone();
+                self.hot.put_object(object).await?;
+                self.dirty.lock().insert(id);
+                Ok(())
+            }
+        }
+    }
+
+    async fn delete_object(&self, id: &str) -> anyhow::Result<()> {
+        match self.write_policy {
+            TierWritePolicy::WriteThrough => {
+                self.cold.delete_object(id).await?;
+                self.hot.delete_object(id).await
+            }
+            TierWritePolicy::WriteBack => {
+                self.hot.delete_object(id).await?;
+                self.dirty.lock().insert(id.to_string());
+                Ok(())
+            }
+        }
+    }
+
+    /// The cold store's listing, overlaid with unflushed write-back changes.
+    async fn list_objects(&self, owner: Option<&str>) -> anyhow::Result<Vec<SuiObject>> {
+        let dirty: Vec<String> = self.dirty.lock().iter().cloned().collect();
+        let mut objects: HashMap<String, SuiObject> = self
+            .cold
+            .list_objects(owner)
+            .await?
+            .into_iter()
+            .map(|object| (object.id.0.clone(), object))
+            .collect();
+        for id in dirty {
+            objects.remove(&id);
+            if let Some(object) = self.hot.get_object(&id).await? {
+                let owned = match owner {
+                    Some(addr) => object.owner.matches_address(addr),
+                    None => true,
+                };
+                if owned {
+                    objects.insert(id, object);
+                }
+            }
+        }
+        Ok(objects.into_values().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryObjectStore;
+    use sui_core::object::{ObjectData, ObjectID, Owner};
+
+    fn coin(id: &str, balance: u64) -> SuiObject {
+        SuiObject::new(ObjectID::new(id), Owner::Address("alice".to_string()), ObjectData::Coin { balance })
+    }
+
+    fn tiers() -> (Arc<InMemoryObjectStore>, Arc<InMemoryObjectStore>) {
+        (Arc::new(InMemoryObjectStore::new()), Arc::new(InMemoryObjectStore::new()))
+    }
+
+    #[tokio::test]
+    async fn cold_reads_are_promoted_and_then_served_hot() {
+        let (hot, cold) = tiers();
+        cold.put_object(coin("coin-a", 10)).await.unwrap();
+        let store = TieredObjectStore::new(hot.clone(), cold);
+
+        assert!(store.get_object("coin-a").await.unwrap().is_some());
+        assert_eq!((store.hot_hits(), store.cold_hits()), (0, 1));
+        assert!(hot.get_object("coin-a").await.unwrap().is_some());
+
+        assert!(store.get_object("coin-a").await.unwrap().is_some());
+        assert_eq!((store.hot_hits(), store.cold_hits()), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn without_promotion_every_read_falls_through() {
+        let (hot, cold) = tiers();
+        cold.put_object(coin("coin-a", 10)).await.unwrap();
+        let store = TieredObjectStore::new(hot.clone(), cold).with_promotion(false);
+
+        store.get_object("coin-a").await.unwrap();
+        store.get_object("coin-a").await.unwrap();
+
+        assert_eq!((store.hot_hits(), store.cold_hits()), (0, 2));
+        assert!(hot.get_object("coin-a").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn write_through_reaches_both_tiers() {
+        let (hot, cold) = tiers();
+        let store = TieredObjectStore::new(hot.clone(), cold.clone());
+
+        store.put_object(coin("coin-a", 10)).await.unwrap();
+        assert!(hot.get_object("coin-a").await.unwrap().is_some());
+        assert!(cold.get_object("coin-a").await.unwrap().is_some());
+
+        store.delete_object("coin-a").await.unwrap();
+        assert!(hot.get_object("coin-a").await.unwrap().is_none());
+        assert!(cold.get_object("coin-a").await.unwrap().is_none());
+        assert_eq!(store.dirty_len(), 0);
+    }
+
+    #[tokio::test]
+    async fn write_back_reaches_the_cold_store_on_flush() {
+        let (hot, cold) = tiers();
+        cold.put_object(coin("coin-b", 20)).await.unwrap();
+        let store = TieredObjectStore::new(hot, cold.clone()).with_write_policy(TierWritePolicy::WriteBack);
+
+        store.put_object(coin("coin-a", 10)).await.unwrap();
+        store.delete_object("coin-b").await.unwrap();
+
+        assert!(cold.get_object("coin-a").await.unwrap().is_none());
+        assert!(store.get_object("coin-b").await.unwrap().is_none());
+        let listed = store.list_objects(None).await.unwrap();
+        assert_eq!(listed.iter().map(|object| object.id.0.as_str()).collect::<Vec<_>>(), vec!["coin-a"]);
+
+        assert_eq!(store.flush().await.unwrap(), 2);
+        assert_eq!(store.dirty_len(), 0);
+        assert!(cold.get_object("coin-a").await.unwrap().is_some());
+        assert!(cold.get_object("coin-b").await.unwrap().is_none());
+    }
+}
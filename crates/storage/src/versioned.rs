@@ -0,0 +1,287 @@
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use sui_core::object::{Owner, SuiObject};
+
+use crate::{ObjectStore, StoreError};
+
+/// Prior versions kept per object unless configured otherwise.
+pub const DEFAULT_MAX_HISTORY_DEPTH: usize = 16;
+
+/// How `VersionedObjectStore::rollback` writes the restored object.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RollbackMode {
+    /// Restores the old owner and data under a new, higher version, so
+    /// versions keep increasing and version-pinned readers see the change.
+    #[default]
+    BumpVersion,
+    /// Restores the old object exactly, version included.
+    Revert,
+}
+
+/// Wraps an object store and archives the previous version of an object
+/// whenever a different version replaces it, so past states stay readable.
+///
+/// At most `max_history_depth` prior versions are kept per object; older
+/// ones are pruned. History survives deletion of the live object.
+pub struct VersionedObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    history: RwLock<HashMap<String, VecDeque<SuiObject>>>,
+    max_history_depth: usize,
+    rollback_mode: RollbackMode,
+}
+
+impl VersionedObjectStore {
+    pub fn new(inner: Arc<dyn ObjectStore>) -> Self {
+        Self::with_max_history_depth(inner, DEFAULT_MAX_HISTORY_DEPTH)
+    }
+
+    pub fn with_max_history_depth(inner: Arc<dyn ObjectStore>, max_history_depth: usize) -> Self {
+        Self {
+            inner,
+            history: RwLock::new(HashMap::new()),
+            max_history_depth,
+            rollback_mode: RollbackMode::default(),
+        }
+    }
+
+    pub fn with_rollback_mode(mut self, mode: RollbackMode) -> Self {
+        self.rollback_mode = mode;
+        self
+    }
+
+    pub fn max_history_depth(&self) -> usize {
+        self.max_history_depth
+    }
+
+    /// Reads `id` as it was at `version`, whether that is the live version
+    /// or an archived one. Returns `None` if the version was pruned or never
+    /// existed.
+    pub async fn get_object_at_version(&self, id: &str, version: u64) -> anyhow::Result<Option<SuiObject>> {
+        if let Some(live) = self.inner.get_object(id).await? {
+            if live.version == version {
+                return Ok(Some(live));
+            }
+        }
+        Ok(self
+            .history
+            .read()
+            .get(id)
+            .and_then(|versions| versions.iter().find(|obj| obj.version == version))
+            .cloned())
+    }
+
+    /// Archived versions of `id`, oldest first.
+    pub fn history(&self, id: &str) -> Vec<SuiObject> {
+        self.history
+            .read()
+            .get(id)
+            .map(|versions| versions.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Restores `id` to its owner and data as of `to_version`, written
+    /// according to the store's `RollbackMode`, and returns the restored
+    /// object. The version being replaced is archived like any other write.
+    /// Deleted objects can be restored; immutable ones cannot be rolled back.
+    pub async fn rollback(&self, id: &str, to_version: u64) -> anyhow::Result<SuiObject> {
+        let live = self.inner.get_object(id).await?;
+        if let Some(live) = &live {
+            if live.owner == Owner::Immutable {
+                return Err(StoreError::ImmutableObject(id.to_string()).into());
+            }
+        }
+
+        let mut restored = self
+            .get_object_at_version(id, to_version)
+            .await?
+            .ok_or_else(|| StoreError::VersionNotFound {
+                id: id.to_string(),
+                version: to_version,
+            })?;
+        if self.rollback_mode == RollbackMode::BumpVersion {
+            let latest = self
+                .history(id)
+                .iter()
+                .map(|object| object.version)
+                .chain(live.as_ref().map(|object| object.version))
+                .max()
+                .unwrap_or(to_version);
+            restored.version = latest + 1;
+        }
+
+        self.put_object(restored.clone()).await?;
+        Ok(restored)
+    }
+
+    fn archive(&self, previous: SuiObject) {
+        if self.max_history_depth == 0 {
+            return;
+        }
+        let mut history = self.history.write();
+        let versions = history.entry(previous.id.0.clone()).or_default();
+        versions.push_back(previous);
+        while versions.len() > self.max_history_depth {
+            versions.pop_front();
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for VersionedObjectStore {
+    async fn get_object(&self, id: &str) -> anyhow::Result<Option<SuiObject>> {
+        self.inner.get_object(id).await
+    }
+
+    async fn put_object(&self, object: SuiObject) -> anyhow::Result<()> {
+        let previous = self.inner.get_object(&object.id.0).await?;
+        self.inner.put_object(object.clone()).await?;
+        if let Some(previous) = previous.filter(|prev| prev.version != object.version) {
+            self.archive(previous);
+        }
+        Ok(())
+    }
+
+    async fn create_object(&self, object: SuiObject) -> anyhow::Result<()> {
+        self.inner.create_object(object).await
+    }
+
+    async fn delete_object(&self, id: &str) -> anyhow::Result<()> {
+        let previous = self.inner.get_object(id).await?;
+        self.inner.delete_object(id).await?;
+        if let Some(previous) = previous {
+            self.archive(previous);
+        }
+        Ok(())
+    }
+
+    async fn list_objects(&self, owner: Option<&str>) -> anyhow::Result<Vec<SuiObject>> {
+        self.inner.list_objects(owner).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryObjectStore;
+    use sui_core::object::{ObjectData, ObjectID};
+
+    fn coin(version: u64, balance: u64) -> SuiObject {
+        let mut coin = SuiObject::new(ObjectID::new("coin"), Owner::Address("alice".to_string()), ObjectData::Coin { balance });
+        coin.version = version;
+        coin
+    }
+
+    fn balance(object: &SuiObject) -> u64 {
+        match object.data {
+            ObjectData::Coin { balance } => balance,
+            _ => panic!("not a coin"),
+        }
+    }
+
+    /// A store holding versions 1 to 4 of `coin`, the balance growing by ten each time.
+    async fn mutated_three_times(max_history_depth: usize) -> VersionedObjectStore {
+        let store = VersionedObjectStore::with_max_history_depth(Arc::new(InMemoryObjectStore::new()), max_history_depth);
+        for version in 1..=4 {
+            store.put_object(coin(version, version * 10)).await.unwrap();
+        }
+        store
+    }
+
+    #[tokio::test]
+    async fn original_version_is_readable_after_three_mutations() {
+        let store = mutated_three_times(DEFAULT_MAX_HISTORY_DEPTH).await;
+
+        let original = store.get_object_at_version("coin", 1).await.unwrap().unwrap();
+        let live = store.get_object_at_version("coin", 4).await.unwrap().unwrap();
+
+        assert_eq!(balance(&original), 10);
+        assert_eq!(balance(&live), 40);
+        assert_eq!(store.history("coin").len(), 3);
+    }
+
+    #[tokio::test]
+    async fn pruned_version_reads_as_none() {
+        let store = mutated_three_times(2).await;
+
+        assert!(store.get_object_at_version("coin", 1).await.unwrap().is_none());
+        assert_eq!(balance(&store.get_object_at_version("coin", 2).await.unwrap().unwrap()), 20);
+        assert!(store.get_object_at_version("coin", 9).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn history_survives_deletion() {
+        let store = mutated_three_times(DEFAULT_MAX_HISTORY_DEPTH).await;
+
+        store.delete_object("coin").await.unwrap();
+
+        assert!(store.get_object("coin").await.unwrap().is_none());
+        assert_eq!(balance(&store.get_object_at_version("coin", 4).await.unwrap().unwrap()), 40);
+    }
+
+    /// Version 1 of `coin` owned by alice with balance 10, then version 2
+    /// moved to bob and version 3 with balance 30.
+    async fn transferred_then_topped_up(mode: RollbackMode) -> VersionedObjectStore {
+        let store = VersionedObjectStore::new(Arc::new(InMemoryObjectStore::new())).with_rollback_mode(mode);
+        store.put_object(coin(1, 10)).await.unwrap();
+        let mut moved = coin(2, 10);
+        moved.owner = Owner::Address("bob".to_string());
+        store.put_object(moved.clone()).await.unwrap();
+        moved.version = 3;
+        moved.data = ObjectData::Coin { balance: 30 };
+        store.put_object(moved).await.unwrap();
+        store
+    }
+
+    #[tokio::test]
+    async fn rollback_restores_the_original_owner_and_data_under_a_new_version() {
+        let store = transferred_then_topped_up(RollbackMode::BumpVersion).await;
+
+        let restored = store.rollback("coin", 1).await.unwrap();
+
+        assert_eq!(restored.owner, Owner::Address("alice".to_string()));
+        assert_eq!(balance(&restored), 10);
+        assert_eq!(restored.version, 4);
+        let live = store.get_object("coin").await.unwrap().unwrap();
+        assert_eq!((live.owner, live.version), (restored.owner, 4));
+        assert_eq!(balance(&store.get_object_at_version("coin", 3).await.unwrap().unwrap()), 30);
+    }
+
+    #[tokio::test]
+    async fn revert_mode_restores_the_old_version_exactly() {
+        let store = transferred_then_topped_up(RollbackMode::Revert).await;
+
+        let restored = store.rollback("coin", 1).await.unwrap();
+
+        assert_eq!(restored.owner, Owner::Address("alice".to_string()));
+        assert_eq!((restored.version, balance(&restored)), (1, 10));
+        let live = store.get_object("coin").await.unwrap().unwrap();
+        assert_eq!((live.version, balance(&live)), (1, 10));
+    }
+
+    #[tokio::test]
+    async fn deleted_objects_can_be_rolled_back() {
+        let store = transferred_then_topped_up(RollbackMode::BumpVersion).await;
+        store.delete_object("coin").await.unwrap();
+
+        let restored = store.rollback("coin", 2).await.unwrap();
+
+        assert_eq!(restored.owner, Owner::Address("bob".to_string()));
+        assert_eq!(restored.version, 4);
+    }
+
+    #[tokio::test]
+    async fn immutable_objects_and_unknown_versions_cannot_be_rolled_back() {
+        let store = transferred_then_topped_up(RollbackMode::BumpVersion).await;
+
+        let err = store.rollback("coin", 9).await.unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(StoreError::VersionNotFound { version: 9, .. })));
+
+        let mut frozen = coin(4, 30);
+        frozen.owner = Owner::Immutable;
+        store.put_object(frozen).await.unwrap();
+        let err = store.rollback("coin", 1).await.unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(StoreError::ImmutableObject(id)) if id == "coin"));
+    }
+}
@@ -4,7 +4,7 @@ use anyhow::Result;
 use sui_core::{
     messages::ExecutionRequest,
     object::{ObjectData, ObjectID, Owner, SuiObject},
-    transaction::{SignedTransaction, TransactionDigest, TransactionKind, TransactionPayload},
+    transaction::{Argument, SignedTransaction, TransactionDigest, TransactionKind, TransactionPayload},
     mock_signed_transfer,
 };
 use sui_network::NetworkClient;
@@ -93,9 +93,17 @@ async fn main() -> Result<()> {
             package: ObjectID::new("coin-package"),
             module: "coin".to_string(),
             function: "mint".to_string(),
-            arguments: vec![serde_json::json!("new-owner"), serde_json::json!(2000u64)],
+            arguments: vec![
+                Argument::Value(serde_json::json!("new-owner")),
+                Argument::Value(serde_json::json!(2000u64)),
+            ],
         },
         gas_budget: 5000,
+        input_objects: vec![],
+        sequence_number: 0,
+        gas_price: 0,
+        read_only: false,
+        gas_payment: None,
     };
 
     let tx3 = SignedTransaction::new("system".to_string(), payload);
@@ -134,9 +142,11 @@ async fn main() -> Result<()> {
     // Query checkpoints
     let latest_seq = checkpoint_store.get_latest_sequence().await?;
     if let Some(seq) = latest_seq {
-        let checkpoint_data = checkpoint_store.get_checkpoint(seq).await?;
-        if checkpoint_data.is_some() {
-            println!("   ✅ Checkpoint {} retrieved from storage", seq);
+        if let Some(checkpoint) = checkpoint_store.get_checkpoint_typed(seq).await? {
+            println!(
+                "   ✅ Checkpoint {} retrieved from storage ({} transactions)",
+                seq, checkpoint.transaction_count
+            );
         }
     }
 
@@ -151,6 +161,7 @@ async fn main() -> Result<()> {
     // ============================================
     println!("🌐 Step 8: Starting network server (will run for 5 seconds)...");
     
+    validator.warm_up().await?;
     let validator_clone = validator.clone();
     let server_handle = tokio::spawn(async move {
         validator_clone.start_network_server(8080).await
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+use sui_core::object::normalize_address;
+use sui_vm::{ApprovalChange, ApprovalScope, OperatorApprovals};
+
+/// Operators each owner has authorized to transfer their objects, either
+/// one object at a time or all of them.
+///
+/// Updated from the `ApprovalChange`s of committed transactions and read by
+/// the VM when a non-owner attempts a transfer.
+#[derive(Debug, Default)]
+pub struct ApprovalRegistry {
+    approvals: RwLock<HashMap<(String, ApprovalScope), Vec<String>>>,
+}
+
+impl ApprovalRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn approve(&self, owner: &str, scope: ApprovalScope, operator: &str) {
+        let operator = normalize_address(operator);
+        let mut approvals = self.approvals.write().unwrap();
+        let operators = approvals.entry((normalize_address(owner), scope)).or_default();
+        if !operators.contains(&operator) {
+            operators.push(operator);
+        }
+    }
+
+    pub fn revoke(&self, owner: &str, scope: ApprovalScope, operator: &str) {
+        let operator = normalize_address(operator);
+        let key = (normalize_address(owner), scope);
+        let mut approvals = self.approvals.write().unwrap();
+        if let Some(operators) = approvals.get_mut(&key) {
+            operators.retain(|approved| *approved != operator);
+            if operators.is_empty() {
+                approvals.remove(&key);
+            }
+        }
+    }
+
+    /// Operators approved for exactly `scope`, in approval order.
+    pub fn operators(&self, owner: &str, scope: &ApprovalScope) -> Vec<String> {
+        self.approvals
+            .read()
+            .unwrap()
+            .get(&(normalize_address(owner), scope.clone()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn apply(&self, changes: &[ApprovalChange]) {
+        for change in changes {
+            match change {
+                ApprovalChange::Approve { owner, scope, operator } => self.approve(owner, scope.clone(), operator),
+                ApprovalChange::Revoke { owner, scope, operator } => self.revoke(owner, scope.clone(), operator),
+            }
+        }
+    }
+}
+
+impl OperatorApprovals for ApprovalRegistry {
+    fn is_approved(&self, owner: &str, scope: &ApprovalScope, operator: &str) -> bool {
+        let operator = normalize_address(operator);
+        self.approvals
+            .read()
+            .unwrap()
+            .get(&(normalize_address(owner), scope.clone()))
+            .is_some_and(|operators| operators.contains(&operator))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_core::object::ObjectID;
+
+    fn coin() -> ApprovalScope {
+        ApprovalScope::Object(ObjectID::new("coin-a"))
+    }
+
+    #[test]
+    fn approvals_are_per_owner_scope_and_operator() {
+        let registry = ApprovalRegistry::new();
+
+        registry.approve("alice", coin(), "bob");
+        registry.approve("alice", coin(), "bob");
+
+        assert_eq!(registry.operators("alice", &coin()), vec!["bob".to_string()]);
+        assert!(registry.is_approved("alice", &coin(), "bob"));
+        assert!(!registry.is_approved("alice", &coin(), "carol"));
+        assert!(!registry.is_approved("alice", &ApprovalScope::All, "bob"));
+        assert!(!registry.is_approved("carol", &coin(), "bob"));
+    }
+
+    #[test]
+    fn revocation_removes_only_that_operator() {
+        let registry = ApprovalRegistry::new();
+        let change = |approve: bool, operator: &str| {
+            let (owner, scope, operator) = ("alice".to_string(), ApprovalScope::All, operator.to_string());
+            if approve {
+                ApprovalChange::Approve { owner, scope, operator }
+            } else {
+                ApprovalChange::Revoke { owner, scope, operator }
+            }
+        };
+        registry.apply(&[change(true, "bob"), change(true, "carol")]);
+
+        registry.apply(&[change(false, "bob")]);
+
+        assert!(!registry.is_approved("alice", &ApprovalScope::All, "bob"));
+        assert_eq!(registry.operators("alice", &ApprovalScope::All), vec!["carol".to_string()]);
+    }
+}
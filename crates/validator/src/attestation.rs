@@ -0,0 +1,198 @@
+use anyhow::{bail, Result};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use sui_core::clock::{is_past, Clock};
+use tokio::sync::Mutex;
+
+/// Attestation documents stay valid for five minutes by default.
+pub const DEFAULT_ATTESTATION_TTL_MS: u64 = 5 * 60 * 1000;
+
+struct CachedToken {
+    token: String,
+    fetched_at: u64,
+}
+
+/// Caches an enclave attestation token until its TTL elapses.
+///
+/// The cache slot is guarded by an async mutex that is held across the
+/// refresh, so callers arriving while an attestation is in flight wait for it
+/// and reuse its token instead of attesting again.
+pub struct AttestationCache {
+    ttl_ms: u64,
+    /// Clock difference forgiven when judging timestamps from elsewhere.
+    skew_tolerance_ms: u64,
+    clock: Arc<dyn Clock>,
+    cached: Mutex<Option<CachedToken>>,
+    fetches: AtomicU64,
+}
+
+impl AttestationCache {
+    pub fn new(ttl_ms: u64, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            ttl_ms,
+            skew_tolerance_ms: 0,
+            clock,
+            cached: Mutex::new(None),
+            fetches: AtomicU64::new(0),
+        }
+    }
+
+    pub fn ttl_ms(&self) -> u64 {
+        self.ttl_ms
+    }
+
+    /// Forgives up to `tolerance_ms` of clock difference with whoever
+    /// timestamped an attestation; see [`AttestationCache::validate_timestamp`].
+    pub fn with_skew_tolerance(mut self, tolerance_ms: u64) -> Self {
+        self.skew_tolerance_ms = tolerance_ms;
+        self
+    }
+
+    pub fn skew_tolerance_ms(&self) -> u64 {
+        self.skew_tolerance_ms
+    }
+
+    /// Checks that an attestation issued at `issued_at_ms` is still within
+    /// its TTL and not from the future, each by more than the skew
+    /// tolerance.
+    pub fn validate_timestamp(&self, issued_at_ms: u64) -> Result<()> {
+        let now = self.clock.now();
+        if is_past(issued_at_ms, now, self.skew_tolerance_ms) {
+            bail!(
+                "attestation timestamp {} is {}ms ahead of the local clock",
+                issued_at_ms,
+                issued_at_ms - now
+            );
+        }
+        if is_past(now, issued_at_ms.saturating_add(self.ttl_ms), self.skew_tolerance_ms) {
+            bail!(
+                "attestation issued at {} expired {}ms ago",
+                issued_at_ms,
+                now - issued_at_ms.saturating_add(self.ttl_ms)
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the cached token, calling `attest` only if there is none or
+    /// it has expired.
+    pub async fn get_or_attest<F, Fut>(&self, attest: F) -> Result<String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        let mut cached = self.cached.lock().await;
+        let now = self.clock.now();
+        if let Some(entry) = cached.as_ref() {
+            if now.saturating_sub(entry.fetched_at) < self.ttl_ms {
+                return Ok(entry.token.clone());
+            }
+        }
+
+        let token = attest().await?;
+        self.fetches.fetch_add(1, Ordering::SeqCst);
+        *cached = Some(CachedToken {
+            token: token.clone(),
+            fetched_at: now,
+        });
+        Ok(token)
+    }
+
+    /// Number of attestations actually performed (cache misses).
+    pub fn attestation_count(&self) -> u64 {
+        self.fetches.load(Ordering::SeqCst)
+    }
+
+    pub async fn invalidate(&self) {
+        *self.cached.lock().await = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_core::clock::MockClock;
+    use std::sync::atomic::AtomicUsize;
+
+    #[tokio::test]
+    async fn cached_token_is_refreshed_once_the_ttl_elapses() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let cache = AttestationCache::new(100, clock.clone());
+
+        cache.get_or_attest(|| async { Ok("first".to_string()) }).await.unwrap();
+        clock.advance(99);
+        let cached = cache.get_or_attest(|| async { Ok("second".to_string()) }).await.unwrap();
+        assert_eq!(cached, "first");
+        assert_eq!(cache.attestation_count(), 1);
+
+        clock.advance(1);
+        let refreshed = cache.get_or_attest(|| async { Ok("second".to_string()) }).await.unwrap();
+        assert_eq!(refreshed, "second");
+        assert_eq!(cache.attestation_count(), 2);
+    }
+
+    #[test]
+    fn attestation_goes_stale_after_the_ttl() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let cache = AttestationCache::new(100, clock.clone());
+
+        clock.advance(100);
+        assert!(cache.validate_timestamp(1_000).is_ok());
+        clock.advance(1);
+        assert!(cache.validate_timestamp(1_000).is_err());
+    }
+
+    #[test]
+    fn skew_tolerance_delays_staleness() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let cache = AttestationCache::new(100, clock.clone()).with_skew_tolerance(50);
+
+        clock.advance(150);
+        assert!(cache.validate_timestamp(1_000).is_ok());
+        clock.advance(1);
+        assert!(cache.validate_timestamp(1_000).is_err());
+        assert!(cache.validate_timestamp(1_202).is_err());
+    }
+
+    #[test]
+    fn timestamps_ahead_of_the_clock_pass_only_within_the_skew_tolerance() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let strict = AttestationCache::new(100, clock.clone());
+        let tolerant = AttestationCache::new(100, clock).with_skew_tolerance(50);
+
+        assert!(strict.validate_timestamp(1_001).is_err());
+        assert!(tolerant.validate_timestamp(1_050).is_ok());
+        let err = tolerant.validate_timestamp(1_051).unwrap_err();
+        assert!(err.to_string().contains("51ms ahead"));
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests_share_one_attestation() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let cache = Arc::new(AttestationCache::new(100, clock));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let requests: Vec<_> = (0..8)
+            .map(|_| {
+                let cache = Arc::clone(&cache);
+                let calls = Arc::clone(&calls);
+                tokio::spawn(async move {
+                    cache
+                        .get_or_attest(|| async move {
+                            calls.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                            Ok("token".to_string())
+                        })
+                        .await
+                })
+            })
+            .collect();
+
+        for request in requests {
+            assert_eq!(request.await.unwrap().unwrap(), "token");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.attestation_count(), 1);
+    }
+}
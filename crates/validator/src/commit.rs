@@ -0,0 +1,508 @@
+use anyhow::{anyhow, bail, Result};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use sui_checkpoint::CheckpointAggregator;
+use sui_core::{
+    messages::CheckpointSummary,
+    object::{ObjectID, SuiObject},
+    transaction::TransactionDigest,
+};
+use sui_precheck::SequenceStore;
+use sui_storage::{CheckpointStore, EffectsStore, ObjectStore};
+use tokio::sync::Mutex;
+
+/// Everything a transaction persists after execution, applied all-or-nothing,
+/// alone or together with other bundles.
+///
+/// Commits are ordered by the aggregator lock: the sequence number is
+/// assigned, the stores are written and the aggregator is updated while it
+/// is held, and only then is the counter advanced. Objects are written
+/// first, then the checkpoint, then the effects. If a write fails, the
+/// objects already written are restored to their previous state and the
+/// counter and the aggregator are left untouched, so the next commit reuses
+/// the same sequence number, overwriting any checkpoint saved for the failed
+/// one, and each checkpoint's `previous_digest` points at the checkpoint
+/// committed just before it. The counter itself is atomic so that readers
+/// never wait on a commit.
+///
+/// However many transactions finish at once, checkpoints therefore get
+/// strictly increasing, gap-free sequence numbers. Timestamps are also
+/// settled under the lock, never going backwards between consecutive
+/// checkpoints even when a transaction stamped earlier commits later.
+///
+/// The signer's sequence number, if tracked, is checked again under the
+/// lock before anything is written and only consumed once everything else
+/// is, so a transaction that fails anywhere before then can be retried with
+/// the same number, and two racing transactions cannot both commit it.
+pub struct CommitBundle {
+    pub digest: TransactionDigest,
+    pub effects_json: String,
+    /// Checkpoint contents; its sequence number is assigned on apply.
+    pub checkpoint: CheckpointSummary,
+    /// Objects the transaction created, written with `create_object`.
+    pub created: Vec<SuiObject>,
+    /// Existing objects the transaction changed, the gas coin included.
+    pub mutated: Vec<SuiObject>,
+    pub deleted: Vec<ObjectID>,
+    pub sequence_advance: Option<SequenceAdvance>,
+}
+
+/// A signer's sequence number, consumed when its transaction commits.
+pub struct SequenceAdvance {
+    pub store: Arc<dyn SequenceStore>,
+    pub signer: String,
+    pub sequence: u64,
+}
+
+impl SequenceAdvance {
+    /// Checks the number against the store, `pending` numbers of the same
+    /// signer being about to be consumed first.
+    fn check(&self, pending: u64) -> Result<()> {
+        let expected = self.store.next_sequence(&self.signer) + pending;
+        if self.sequence != expected {
+            bail!("bad sequence number: expected {}, got {}", expected, self.sequence);
+        }
+        Ok(())
+    }
+
+    fn apply(&self) -> Result<()> {
+        self.store
+            .advance(&self.signer, self.sequence)
+            .map_err(|expected| anyhow!("bad sequence number: expected {}, got {}", expected, self.sequence))
+    }
+}
+
+impl CommitBundle {
+    pub async fn apply(
+        self,
+        sequence: &AtomicU64,
+        aggregator: &Mutex<CheckpointAggregator>,
+        object_store: &dyn ObjectStore,
+        effects_store: &dyn EffectsStore,
+        checkpoint_store: &dyn CheckpointStore,
+    ) -> Result<CheckpointSummary> {
+        let mut committed =
+            Self::apply_all(vec![self], sequence, aggregator, object_store, effects_store, checkpoint_store).await?;
+        Ok(committed.remove(0))
+    }
+
+    /// Applies `bundles` in order as one unit, under a single hold of the
+    /// aggregator lock: each gets the next checkpoint, but if any write
+    /// fails, the objects of every bundle are restored and none of them is
+    /// recorded, exactly as for a single failed bundle. Checkpoints and
+    /// effects saved before the failure stay behind: the checkpoints are
+    /// overwritten by the next commits, and the effects are those of
+    /// transactions that did not commit.
+    pub async fn apply_all(
+        mut bundles: Vec<Self>,
+        sequence: &AtomicU64,
+        aggregator: &Mutex<CheckpointAggregator>,
+        object_store: &dyn ObjectStore,
+        effects_store: &dyn EffectsStore,
+        checkpoint_store: &dyn CheckpointStore,
+    ) -> Result<Vec<CheckpointSummary>> {
+        if bundles.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut aggregator = aggregator.lock().await;
+        let first = sequence.load(Ordering::SeqCst) + 1;
+        let mut previous_checkpoint = aggregator.latest().cloned();
+        if let Some(latest) = &previous_checkpoint {
+            // Only possible if something advanced one without the other.
+            if latest.sequence_number + 1 != first {
+                bail!(
+                    "checkpoint sequence out of step: last recorded {}, next would be {}",
+                    latest.sequence_number,
+                    first
+                );
+            }
+        }
+        for (index, bundle) in bundles.iter_mut().enumerate() {
+            if let Some(previous) = &previous_checkpoint {
+                bundle.checkpoint.timestamp_ms = bundle.checkpoint.timestamp_ms.max(previous.timestamp_ms);
+                bundle.checkpoint.previous_digest = Some(aggregator.digest(previous));
+            }
+            bundle.checkpoint.sequence_number = first + index as u64;
+            previous_checkpoint = Some(bundle.checkpoint.clone());
+        }
+        for (index, bundle) in bundles.iter().enumerate() {
+            if let Some(advance) = &bundle.sequence_advance {
+                let pending = bundles[..index]
+                    .iter()
+                    .filter_map(|earlier| earlier.sequence_advance.as_ref())
+                    .filter(|earlier| earlier.signer == advance.signer)
+                    .count() as u64;
+                advance.check(pending)?;
+            }
+        }
+
+        let mut previous = Vec::new();
+        for bundle in &bundles {
+            match bundle.write_objects(object_store).await {
+                Ok(written) => previous.extend(written),
+                Err(err) => {
+                    restore(object_store, previous).await;
+                    return Err(err);
+                }
+            }
+        }
+        let persisted = async {
+            for bundle in &bundles {
+                checkpoint_store
+                    .save_checkpoint_typed(&bundle.checkpoint)
+                    .await?;
+                effects_store
+                    .save_effects(&bundle.digest, &bundle.effects_json)
+                    .await?;
+            }
+            bundles
+                .iter()
+                .filter_map(|bundle| bundle.sequence_advance.as_ref())
+                .try_for_each(SequenceAdvance::apply)
+        }
+        .await;
+        if let Err(err) = persisted {
+            restore(object_store, previous).await;
+            return Err(err);
+        }
+
+        let checkpoints: Vec<CheckpointSummary> = bundles.into_iter().map(|bundle| bundle.checkpoint).collect();
+        for checkpoint in &checkpoints {
+            aggregator.record(checkpoint.clone());
+        }
+        sequence.store(first + checkpoints.len() as u64 - 1, Ordering::SeqCst);
+        Ok(checkpoints)
+    }
+
+    /// Writes the bundle's objects, returning the previous state of each
+    /// one. On failure the objects written so far are restored first.
+    async fn write_objects(&self, store: &dyn ObjectStore) -> Result<Vec<(String, Option<SuiObject>)>> {
+        let ids: Vec<String> = self
+            .created
+            .iter()
+            .chain(&self.mutated)
+            .map(|object| object.id.0.clone())
+            .chain(self.deleted.iter().map(|id| id.0.clone()))
+            .collect();
+        let before = store.get_objects(&ids).await?;
+
+        let mut previous = Vec::with_capacity(ids.len());
+        let writes = self
+            .created
+            .iter()
+            .map(|object| (object, true))
+            .chain(self.mutated.iter().map(|object| (object, false)));
+        for ((object, created), prior) in writes.zip(before.iter()) {
+            let written = if created {
+                store.create_object(object.clone()).await
+            } else {
+                store.put_object(object.clone()).await
+            };
+            if let Err(err) = written {
+                restore(store, previous).await;
+                return Err(err);
+            }
+            previous.push((object.id.0.clone(), prior.clone()));
+        }
+        let written = self.created.len() + self.mutated.len();
+        for (id, prior) in self.deleted.iter().zip(&before[written..]) {
+            if let Err(err) = store.delete_object(&id.0).await {
+                restore(store, previous).await;
+                return Err(err);
+            }
+            previous.push((id.0.clone(), prior.clone()));
+        }
+        Ok(previous)
+    }
+}
+
+/// Puts each object back as it was before the commit, newest write first.
+/// Failures are logged; there is nothing further to fall back on.
+async fn restore(store: &dyn ObjectStore, previous: Vec<(String, Option<SuiObject>)>) {
+    for (id, prior) in previous.into_iter().rev() {
+        let restored = match prior {
+            Some(object) => store.put_object(object).await,
+            None => store.delete_object(&id).await,
+        };
+        if let Err(err) = restored {
+            eprintln!("Error restoring object {} after a failed commit: {}", id, err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use async_trait::async_trait;
+    use sui_core::object::{ObjectData, Owner};
+    use sui_precheck::InMemorySequenceStore;
+    use sui_storage::{InMemoryCheckpointStore, InMemoryEffectsStore, InMemoryObjectStore};
+
+    /// Checkpoint store whose writes always fail.
+    struct FailingCheckpointStore;
+
+    #[async_trait]
+    impl CheckpointStore for FailingCheckpointStore {
+        async fn save_checkpoint(&self, _sequence: u64, _checkpoint_json: &str) -> Result<()> {
+            Err(anyhow!("disk full"))
+        }
+
+        async fn get_checkpoint(&self, _sequence: u64) -> Result<Option<String>> {
+            Ok(None)
+        }
+
+        async fn get_latest_sequence(&self) -> Result<Option<u64>> {
+            Ok(None)
+        }
+    }
+
+    fn coin(id: &str, balance: u64) -> SuiObject {
+        SuiObject::new(
+            ObjectID::new(id),
+            Owner::Address("alice".to_string()),
+            ObjectData::Coin { balance },
+        )
+    }
+
+    fn bundle(digest: &str, created: Vec<SuiObject>, mutated: Vec<SuiObject>) -> CommitBundle {
+        CommitBundle {
+            digest: TransactionDigest(digest.to_string()),
+            effects_json: "{}".to_string(),
+            checkpoint: CheckpointSummary::new(0, digest),
+            created,
+            mutated,
+            deleted: Vec::new(),
+            sequence_advance: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_checkpoint_write_commits_nothing() {
+        let sequence = AtomicU64::new(0);
+        let aggregator = Mutex::new(CheckpointAggregator::new());
+        let objects = InMemoryObjectStore::new();
+        let effects = InMemoryEffectsStore::new();
+        objects.put_object(coin("gas", 100)).await.unwrap();
+
+        let mut charged = coin("gas", 90);
+        charged.version = 2;
+        let result = bundle("tx-1", vec![coin("minted", 5)], vec![charged])
+            .apply(&sequence, &aggregator, &objects, &effects, &FailingCheckpointStore)
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(sequence.load(Ordering::SeqCst), 0);
+        assert!(aggregator.lock().await.latest().is_none());
+        assert!(objects.get_object("minted").await.unwrap().is_none());
+        let gas = objects.get_object("gas").await.unwrap().unwrap();
+        assert_eq!(gas.version, 1);
+        assert!(matches!(gas.data, ObjectData::Coin { balance: 100 }));
+        assert!(effects
+            .get_effects(&TransactionDigest("tx-1".to_string()))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn commit_after_a_failure_reuses_the_sequence_number() {
+        let sequence = AtomicU64::new(0);
+        let aggregator = Mutex::new(CheckpointAggregator::new());
+        let objects = InMemoryObjectStore::new();
+        let effects = InMemoryEffectsStore::new();
+        let checkpoints = InMemoryCheckpointStore::new();
+
+        let failed = bundle("tx-1", vec![coin("a", 1)], Vec::new())
+            .apply(&sequence, &aggregator, &objects, &effects, &FailingCheckpointStore)
+            .await;
+        assert!(failed.is_err());
+
+        let committed = bundle("tx-2", vec![coin("b", 1)], Vec::new())
+            .apply(&sequence, &aggregator, &objects, &effects, &checkpoints)
+            .await
+            .unwrap();
+        assert_eq!(committed.sequence_number, 1);
+        assert_eq!(sequence.load(Ordering::SeqCst), 1);
+        assert_eq!(checkpoints.get_latest_sequence().await.unwrap(), Some(1));
+        assert!(objects.get_object("a").await.unwrap().is_none());
+        assert!(objects.get_object("b").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn failed_object_write_restores_earlier_writes() {
+        let sequence = AtomicU64::new(0);
+        let aggregator = Mutex::new(CheckpointAggregator::new());
+        let objects = InMemoryObjectStore::new();
+        let effects = InMemoryEffectsStore::new();
+        let checkpoints = InMemoryCheckpointStore::new();
+        objects.put_object(coin("taken", 1)).await.unwrap();
+
+        // Creating an id that already exists fails after "fresh" is written.
+        let result = bundle("tx-1", vec![coin("fresh", 1), coin("taken", 2)], Vec::new())
+            .apply(&sequence, &aggregator, &objects, &effects, &checkpoints)
+            .await;
+
+        assert!(result.is_err());
+        assert!(objects.get_object("fresh").await.unwrap().is_none());
+        assert_eq!(checkpoints.get_latest_sequence().await.unwrap(), None);
+        assert_eq!(sequence.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn failed_write_in_a_later_bundle_undoes_the_whole_batch() {
+        let sequence = AtomicU64::new(0);
+        let aggregator = Mutex::new(CheckpointAggregator::new());
+        let objects = InMemoryObjectStore::new();
+        let effects = InMemoryEffectsStore::new();
+        let checkpoints = InMemoryCheckpointStore::new();
+        objects.put_object(coin("gas", 100)).await.unwrap();
+        objects.put_object(coin("taken", 1)).await.unwrap();
+
+        let mut charged = coin("gas", 90);
+        charged.version = 2;
+        let result = CommitBundle::apply_all(
+            vec![
+                bundle("tx-1", vec![coin("fresh", 1)], vec![charged]),
+                bundle("tx-2", vec![coin("taken", 2)], Vec::new()),
+            ],
+            &sequence,
+            &aggregator,
+            &objects,
+            &effects,
+            &checkpoints,
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert!(objects.get_object("fresh").await.unwrap().is_none());
+        assert_eq!(objects.get_object("gas").await.unwrap().unwrap().version, 1);
+        assert_eq!(sequence.load(Ordering::SeqCst), 0);
+        assert!(aggregator.lock().await.latest().is_none());
+    }
+
+    #[tokio::test]
+    async fn batch_checkpoints_chain_in_order() {
+        let sequence = AtomicU64::new(0);
+        let aggregator = Mutex::new(CheckpointAggregator::new());
+        let objects = InMemoryObjectStore::new();
+        let effects = InMemoryEffectsStore::new();
+        let checkpoints = InMemoryCheckpointStore::new();
+
+        let committed = CommitBundle::apply_all(
+            vec![bundle("tx-1", vec![coin("a", 1)], Vec::new()), bundle("tx-2", vec![coin("b", 1)], Vec::new())],
+            &sequence,
+            &aggregator,
+            &objects,
+            &effects,
+            &checkpoints,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(committed[0].sequence_number, 1);
+        assert_eq!(committed[1].sequence_number, 2);
+        assert_eq!(committed[1].previous_digest, Some(committed[0].digest()));
+        assert_eq!(sequence.load(Ordering::SeqCst), 2);
+        assert!(aggregator.lock().await.verify_chain());
+    }
+
+    #[tokio::test]
+    async fn signer_sequence_is_consumed_only_by_a_successful_commit() {
+        let sequence = AtomicU64::new(0);
+        let aggregator = Mutex::new(CheckpointAggregator::new());
+        let objects = InMemoryObjectStore::new();
+        let effects = InMemoryEffectsStore::new();
+        let sequences: Arc<dyn SequenceStore> = Arc::new(InMemorySequenceStore::new());
+        let sequenced = |digest: &str| CommitBundle {
+            sequence_advance: Some(SequenceAdvance {
+                store: Arc::clone(&sequences),
+                signer: "alice".to_string(),
+                sequence: 1,
+            }),
+            ..bundle(digest, Vec::new(), Vec::new())
+        };
+
+        let failed = sequenced("tx-1")
+            .apply(&sequence, &aggregator, &objects, &effects, &FailingCheckpointStore)
+            .await;
+        assert!(failed.is_err());
+        assert_eq!(sequences.last_sequence("alice"), None);
+
+        let checkpoints = InMemoryCheckpointStore::new();
+        sequenced("tx-2")
+            .apply(&sequence, &aggregator, &objects, &effects, &checkpoints)
+            .await
+            .unwrap();
+        assert_eq!(sequences.last_sequence("alice"), Some(1));
+        let replayed = sequenced("tx-3")
+            .apply(&sequence, &aggregator, &objects, &effects, &checkpoints)
+            .await;
+        assert!(replayed.is_err());
+        assert_eq!(sequence.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_commits_get_gap_free_sequences_and_monotonic_timestamps() {
+        let sequence = Arc::new(AtomicU64::new(0));
+        let aggregator = Arc::new(Mutex::new(CheckpointAggregator::new()));
+        let objects = Arc::new(InMemoryObjectStore::new());
+        let effects = Arc::new(InMemoryEffectsStore::new());
+        let checkpoints = Arc::new(InMemoryCheckpointStore::new());
+
+        let handles: Vec<_> = (0..64u64)
+            .map(|index| {
+                let (sequence, aggregator) = (Arc::clone(&sequence), Arc::clone(&aggregator));
+                let (objects, effects, checkpoints) =
+                    (Arc::clone(&objects), Arc::clone(&effects), Arc::clone(&checkpoints));
+                tokio::spawn(async move {
+                    let id = format!("coin-{}", index);
+                    let mut committing = bundle(&format!("tx-{}", index), vec![coin(&id, 1)], Vec::new());
+                    // Later transactions carry earlier timestamps.
+                    committing.checkpoint.timestamp_ms = 1_000 - index;
+                    committing
+                        .apply(&sequence, &aggregator, objects.as_ref(), effects.as_ref(), checkpoints.as_ref())
+                        .await
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+
+        assert_eq!(sequence.load(Ordering::SeqCst), 64);
+        let mut previous: Option<CheckpointSummary> = None;
+        for number in 1..=64 {
+            let checkpoint = checkpoints.get_checkpoint_typed(number).await.unwrap().unwrap();
+            assert_eq!(checkpoint.sequence_number, number);
+            if let Some(previous) = &previous {
+                assert!(checkpoint.timestamp_ms >= previous.timestamp_ms);
+                assert_eq!(checkpoint.previous_digest, Some(previous.digest()));
+            }
+            previous = Some(checkpoint);
+        }
+        assert!(aggregator.lock().await.verify_chain());
+    }
+
+    #[tokio::test]
+    async fn commit_refuses_a_sequence_out_of_step_with_the_aggregator() {
+        let sequence = AtomicU64::new(0);
+        let aggregator = Mutex::new(CheckpointAggregator::new());
+        let objects = InMemoryObjectStore::new();
+        let effects = InMemoryEffectsStore::new();
+        let checkpoints = InMemoryCheckpointStore::new();
+        bundle("tx-1", Vec::new(), Vec::new())
+            .apply(&sequence, &aggregator, &objects, &effects, &checkpoints)
+            .await
+            .unwrap();
+
+        sequence.store(5, Ordering::SeqCst);
+        let result = bundle("tx-2", vec![coin("a", 1)], Vec::new())
+            .apply(&sequence, &aggregator, &objects, &effects, &checkpoints)
+            .await;
+
+        assert!(result.unwrap_err().to_string().contains("out of step"));
+        assert!(objects.get_object("a").await.unwrap().is_none());
+        assert_eq!(checkpoints.get_latest_sequence().await.unwrap(), Some(1));
+    }
+}
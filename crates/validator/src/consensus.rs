@@ -0,0 +1,268 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sui_core::clock::{Clock, SystemClock};
+use sui_core::messages::ExecutionRequest;
+use tokio::sync::watch;
+
+/// Orders transactions before the validator executes them.
+///
+/// Positions start at 1 and every position is handed out exactly once; the
+/// validator executes and checkpoints transactions in position order.
+#[async_trait]
+pub trait ConsensusEngine: Send + Sync {
+    async fn order(&self, request: &ExecutionRequest) -> Result<u64>;
+}
+
+/// Longest a batch member sleeps before checking its deadline again.
+const DEADLINE_POLL_MS: u64 = 10;
+
+/// Orders transactions immediately, in arrival order.
+#[derive(Debug, Default)]
+pub struct LocalConsensus {
+    last: AtomicU64,
+}
+
+impl LocalConsensus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ConsensusEngine for LocalConsensus {
+    async fn order(&self, _request: &ExecutionRequest) -> Result<u64> {
+        Ok(self.last.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+}
+
+/// Simulates a consensus round trip: transactions are collected into a
+/// batch until it holds `batch_size` transactions or `latency` has passed
+/// since the batch opened. A sealed batch is ordered by digest, so the order
+/// within it does not depend on arrival.
+///
+/// Batch deadlines are read from a [`Clock`], so a mock clock decides when
+/// a partly filled batch is sealed.
+pub struct SimulatedConsensus {
+    clock: Arc<dyn Clock>,
+    latency: Duration,
+    batch_size: usize,
+    state: Mutex<BatchState>,
+}
+
+#[derive(Default)]
+struct BatchState {
+    last: u64,
+    open: Option<Arc<Batch>>,
+}
+
+struct Batch {
+    /// Clock reading, in milliseconds, at which the batch is sealed.
+    deadline: u64,
+    /// Digests in arrival order; a transaction's slot is its index.
+    digests: Mutex<Vec<String>>,
+    /// Position of each slot, published when the batch is sealed.
+    positions: watch::Sender<Option<Vec<u64>>>,
+}
+
+impl SimulatedConsensus {
+    pub fn new(latency: Duration, batch_size: usize) -> Self {
+        Self {
+            clock: Arc::new(SystemClock),
+            latency,
+            batch_size: batch_size.max(1),
+            state: Mutex::new(BatchState::default()),
+        }
+    }
+
+    /// Times batches with `clock` instead of the system clock.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Adds `digest` to the open batch, opening one if needed. Returns the
+    /// batch, the transaction's slot in it and whether it filled the batch.
+    fn join(&self, digest: &str) -> (Arc<Batch>, usize, bool) {
+        let mut state = self.state.lock().unwrap();
+        let batch = state
+            .open
+            .get_or_insert_with(|| {
+                Arc::new(Batch {
+                    deadline: self.clock.now().saturating_add(self.latency.as_millis() as u64),
+                    digests: Mutex::new(Vec::new()),
+                    positions: watch::channel(None).0,
+                })
+            })
+            .clone();
+        let mut digests = batch.digests.lock().unwrap();
+        digests.push(digest.to_string());
+        let (slot, full) = (digests.len() - 1, digests.len() >= self.batch_size);
+        drop(digests);
+        (batch, slot, full)
+    }
+
+    /// Assigns positions to `batch` unless it has been sealed already.
+    fn seal(&self, batch: &Arc<Batch>) {
+        let mut state = self.state.lock().unwrap();
+        if !state.open.as_ref().is_some_and(|open| Arc::ptr_eq(open, batch)) {
+            return;
+        }
+        state.open = None;
+
+        let digests = batch.digests.lock().unwrap();
+        let mut slots: Vec<usize> = (0..digests.len()).collect();
+        slots.sort_by(|a, b| digests[*a].cmp(&digests[*b]).then(a.cmp(b)));
+        let mut positions = vec![0; digests.len()];
+        for slot in slots {
+            state.last += 1;
+            positions[slot] = state.last;
+        }
+        batch.positions.send_replace(Some(positions));
+    }
+}
+
+#[async_trait]
+impl ConsensusEngine for SimulatedConsensus {
+    async fn order(&self, request: &ExecutionRequest) -> Result<u64> {
+        let (batch, slot, full) = self.join(&request.digest.0);
+        let mut positions = batch.positions.subscribe();
+        if full {
+            self.seal(&batch);
+        }
+        // Every member seals at the deadline, so the batch closes even if
+        // the one that opened it has gone away. The clock is re-read every
+        // poll interval since it may not be the system clock.
+        loop {
+            let remaining = batch.deadline.saturating_sub(self.clock.now());
+            if remaining == 0 {
+                self.seal(&batch);
+                break;
+            }
+            let wait = Duration::from_millis(remaining.min(DEADLINE_POLL_MS));
+            let timed_out = tokio::time::timeout(wait, positions.wait_for(Option::is_some))
+                .await
+                .is_err();
+            if !timed_out {
+                break;
+            }
+        }
+        let positions = positions
+            .wait_for(Option::is_some)
+            .await
+            .map_err(|_| anyhow::anyhow!("consensus batch dropped"))?;
+        Ok(positions.as_ref().expect("waited for positions")[slot])
+    }
+}
+
+/// Lets transactions proceed one consensus position at a time.
+pub(crate) struct PositionGate {
+    next: watch::Sender<u64>,
+    /// Positions done out of turn, e.g. abandoned before their turn came.
+    finished: Mutex<BTreeSet<u64>>,
+}
+
+impl PositionGate {
+    pub(crate) fn new() -> Self {
+        Self {
+            next: watch::channel(1).0,
+            finished: Mutex::new(BTreeSet::new()),
+        }
+    }
+
+    fn finish(&self, position: u64) {
+        let mut finished = self.finished.lock().unwrap();
+        finished.insert(position);
+        self.next.send_modify(|next| {
+            while finished.remove(next) {
+                *next += 1;
+            }
+        });
+    }
+}
+
+/// A transaction's consensus position. Once its turn has come, later
+/// positions wait until this is dropped, which also releases the turn on
+/// failure or cancellation.
+pub(crate) struct Turn {
+    gate: Arc<PositionGate>,
+    position: u64,
+}
+
+impl Turn {
+    pub(crate) async fn acquire(engine: &dyn ConsensusEngine, gate: &Arc<PositionGate>, request: &ExecutionRequest) -> Result<Self> {
+        let position = engine.order(request).await?;
+        let turn = Turn {
+            gate: Arc::clone(gate),
+            position,
+        };
+        let mut next = gate.next.subscribe();
+        let _ = next.wait_for(|next| *next >= position).await;
+        Ok(turn)
+    }
+
+    pub(crate) fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+impl Drop for Turn {
+    fn drop(&mut self) {
+        self.gate.finish(self.position);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::transfer;
+    use sui_core::clock::MockClock;
+    use sui_core::transaction::TransactionDigest;
+
+    fn ordered(digest: &str) -> ExecutionRequest {
+        let mut request = transfer("alice", "coin", "bob");
+        request.digest = TransactionDigest(digest.to_string());
+        request
+    }
+
+    #[tokio::test]
+    async fn local_consensus_orders_in_arrival_order() {
+        let engine = LocalConsensus::new();
+
+        for expected in 1..=3 {
+            assert_eq!(engine.order(&ordered("tx")).await.unwrap(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn full_batch_is_ordered_by_digest() {
+        let engine = SimulatedConsensus::new(Duration::from_secs(60), 3);
+
+        let (c, a, b) = (ordered("tx-c"), ordered("tx-a"), ordered("tx-b"));
+
+        let (c, a, b) = tokio::join!(engine.order(&c), engine.order(&a), engine.order(&b));
+
+        assert_eq!((a.unwrap(), b.unwrap(), c.unwrap()), (1, 2, 3));
+    }
+
+    #[tokio::test]
+    async fn partly_filled_batch_is_sealed_at_its_deadline() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let engine = Arc::new(SimulatedConsensus::new(Duration::from_millis(500), 10).with_clock(clock.clone()));
+        let (first, second) = (Arc::clone(&engine), engine);
+        let pending = tokio::spawn(async move {
+            let (b, a) = (ordered("tx-b"), ordered("tx-a"));
+            tokio::join!(first.order(&b), second.order(&a))
+        });
+
+        tokio::time::sleep(Duration::from_millis(3 * DEADLINE_POLL_MS)).await;
+        assert!(!pending.is_finished());
+        clock.advance(500);
+        let (b, a) = pending.await.unwrap();
+
+        assert_eq!((a.unwrap(), b.unwrap()), (1, 2));
+    }
+}
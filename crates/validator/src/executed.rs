@@ -0,0 +1,153 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use sui_core::transaction::TransactionDigest;
+
+/// Digests of transactions executed anywhere in a committee, each with the
+/// validator that executed it.
+///
+/// Validators sharing one set, directly or by merging entries received from
+/// peers, reject a transaction another member already executed, so a
+/// replayed transaction is applied once per committee rather than once per
+/// validator. A digest is claimed before execution and only released again
+/// if the transaction fails before it commits.
+#[derive(Debug, Default)]
+pub struct ExecutedDigests {
+    digests: RwLock<HashMap<TransactionDigest, String>>,
+}
+
+impl ExecutedDigests {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The validator that executed, or is executing, `digest`.
+    pub fn executed_by(&self, digest: &TransactionDigest) -> Option<String> {
+        self.digests.read().unwrap().get(digest).cloned()
+    }
+
+    pub fn contains(&self, digest: &TransactionDigest) -> bool {
+        self.digests.read().unwrap().contains_key(digest)
+    }
+
+    /// Records digests executed elsewhere, e.g. as reported by peers.
+    /// Existing entries are kept.
+    pub fn merge(&self, executed: impl IntoIterator<Item = (TransactionDigest, String)>) {
+        let mut digests = self.digests.write().unwrap();
+        for (digest, validator) in executed {
+            digests.entry(digest).or_insert(validator);
+        }
+    }
+
+    /// Every recorded digest with its executor.
+    pub fn entries(&self) -> Vec<(TransactionDigest, String)> {
+        self.digests
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(digest, validator)| (digest.clone(), validator.clone()))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.digests.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Claims `digest` for `validator`, failing if anyone already has it.
+    fn claim(&self, digest: &TransactionDigest, validator: &str) -> Result<()> {
+        let mut digests = self.digests.write().unwrap();
+        if let Some(executor) = digests.get(digest) {
+            return Err(anyhow!(
+                "transaction {} was already executed by {}",
+                digest.0,
+                executor
+            ));
+        }
+        digests.insert(digest.clone(), validator.to_string());
+        Ok(())
+    }
+
+    fn release(&self, digest: &TransactionDigest) {
+        self.digests.write().unwrap().remove(digest);
+    }
+}
+
+/// A digest claimed in an [`ExecutedDigests`] set, released on drop unless
+/// kept once the transaction commits.
+pub(crate) struct Claim<'a> {
+    set: &'a ExecutedDigests,
+    digest: TransactionDigest,
+    kept: bool,
+}
+
+impl<'a> Claim<'a> {
+    pub(crate) fn acquire(set: &'a ExecutedDigests, digest: &TransactionDigest, validator: &str) -> Result<Self> {
+        set.claim(digest, validator)?;
+        Ok(Self {
+            set,
+            digest: digest.clone(),
+            kept: false,
+        })
+    }
+
+    pub(crate) fn keep(mut self) {
+        self.kept = true;
+    }
+}
+
+impl Drop for Claim<'_> {
+    fn drop(&mut self) {
+        if !self.kept {
+            self.set.release(&self.digest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest(name: &str) -> TransactionDigest {
+        TransactionDigest(name.to_string())
+    }
+
+    #[test]
+    fn a_kept_claim_rejects_later_claims() {
+        let executed = ExecutedDigests::new();
+        Claim::acquire(&executed, &digest("tx-1"), "v1").unwrap().keep();
+
+        let replay = Claim::acquire(&executed, &digest("tx-1"), "v2");
+
+        assert!(replay.err().unwrap().to_string().contains("already executed by v1"));
+        assert_eq!(executed.executed_by(&digest("tx-1")).as_deref(), Some("v1"));
+    }
+
+    #[test]
+    fn a_dropped_claim_is_released() {
+        let executed = ExecutedDigests::new();
+        {
+            let _claim = Claim::acquire(&executed, &digest("tx-1"), "v1").unwrap();
+            assert!(executed.contains(&digest("tx-1")));
+        }
+
+        assert!(executed.is_empty());
+        Claim::acquire(&executed, &digest("tx-1"), "v2").unwrap().keep();
+        assert_eq!(executed.executed_by(&digest("tx-1")).as_deref(), Some("v2"));
+    }
+
+    #[test]
+    fn merged_entries_keep_the_first_executor() {
+        let executed = ExecutedDigests::new();
+        Claim::acquire(&executed, &digest("tx-1"), "v1").unwrap().keep();
+
+        executed.merge([(digest("tx-1"), "v2".to_string()), (digest("tx-2"), "v3".to_string())]);
+
+        assert_eq!(executed.len(), 2);
+        assert_eq!(executed.executed_by(&digest("tx-1")).as_deref(), Some("v1"));
+        assert!(Claim::acquire(&executed, &digest("tx-2"), "v1").is_err());
+    }
+}
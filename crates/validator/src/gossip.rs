@@ -0,0 +1,177 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use sui_core::{committee::Committee, messages::ExecutionRequest, transaction::TransactionDigest};
+use sui_network::NetworkClient;
+
+/// Digests remembered for deduplication unless configured otherwise.
+pub const DEFAULT_GOSSIP_MEMORY: usize = 10_000;
+
+/// Propagates transactions between committee members.
+///
+/// A validator forwards each transaction it receives to every other member
+/// of its committee. Each digest is only handled the first time it arrives,
+/// so a transaction echoed back by a peer is neither executed nor forwarded
+/// again and propagation stops once every member has seen it. A digest
+/// that fails to execute is forgotten, so a retry is handled afresh.
+pub struct GossipLayer {
+    /// Committee name of the validator itself, which is never forwarded to.
+    name: String,
+    memory: usize,
+    seen: Mutex<Seen>,
+    clients: Mutex<HashMap<String, Arc<NetworkClient>>>,
+}
+
+#[derive(Default)]
+struct Seen {
+    digests: HashSet<TransactionDigest>,
+    /// Arrival order, for forgetting the oldest digests first.
+    order: VecDeque<TransactionDigest>,
+}
+
+impl GossipLayer {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self::with_memory(name, DEFAULT_GOSSIP_MEMORY)
+    }
+
+    /// Remembers at most `memory` digests; older ones are forgotten and
+    /// would be handled again should they reappear.
+    pub fn with_memory(name: impl Into<String>, memory: usize) -> Self {
+        Self {
+            name: name.into(),
+            memory: memory.max(1),
+            seen: Mutex::new(Seen::default()),
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records `digest`, returning whether this is the first time it has
+    /// been seen.
+    pub fn mark_seen(&self, digest: &TransactionDigest) -> bool {
+        let mut seen = self.seen.lock().unwrap();
+        if !seen.digests.insert(digest.clone()) {
+            return false;
+        }
+        seen.order.push_back(digest.clone());
+        while seen.order.len() > self.memory {
+            if let Some(oldest) = seen.order.pop_front() {
+                seen.digests.remove(&oldest);
+            }
+        }
+        true
+    }
+
+    /// Forgets `digest`, so that its next arrival is handled as new, e.g.
+    /// a retry after it failed to execute.
+    pub fn forget(&self, digest: &TransactionDigest) {
+        let mut seen = self.seen.lock().unwrap();
+        if seen.digests.remove(digest) {
+            seen.order.retain(|seen| seen != digest);
+        }
+    }
+
+    pub fn has_seen(&self, digest: &TransactionDigest) -> bool {
+        self.seen.lock().unwrap().digests.contains(digest)
+    }
+
+    /// Sends `request` to every other committee member in the background.
+    /// Delivery failures are logged, not retried.
+    pub fn forward(&self, committee: &Committee, request: &ExecutionRequest) {
+        for member in committee.members.iter().filter(|member| member.name != self.name) {
+            let client = self.client(&member.endpoint);
+            let (peer, request) = (member.name.clone(), request.clone());
+            tokio::spawn(async move {
+                let digest = request.digest.0.clone();
+                if let Err(err) = client.submit_transaction(request).await {
+                    eprintln!("Error gossiping {} to {}: {}", digest, peer, err);
+                }
+            });
+        }
+    }
+
+    fn client(&self, endpoint: &str) -> Arc<NetworkClient> {
+        let mut clients = self.clients.lock().unwrap();
+        Arc::clone(
+            clients
+                .entry(endpoint.to_string())
+                .or_insert_with(|| Arc::new(NetworkClient::new(endpoint))),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{coin, node, serve_endpoint, transfer};
+    use std::time::Duration;
+    use sui_core::messages::TxLogFilter;
+
+    fn digest(name: &str) -> TransactionDigest {
+        TransactionDigest(name.to_string())
+    }
+
+    #[test]
+    fn each_digest_is_handled_once_until_forgotten() {
+        let gossip = GossipLayer::new("v1");
+
+        assert!(gossip.mark_seen(&digest("tx-1")));
+        assert!(!gossip.mark_seen(&digest("tx-1")));
+        gossip.forget(&digest("tx-1"));
+        assert!(!gossip.has_seen(&digest("tx-1")));
+        assert!(gossip.mark_seen(&digest("tx-1")));
+    }
+
+    #[test]
+    fn oldest_digests_are_forgotten_beyond_the_memory() {
+        let gossip = GossipLayer::with_memory("v1", 2);
+
+        for name in ["tx-1", "tx-2", "tx-3"] {
+            gossip.mark_seen(&digest(name));
+        }
+
+        assert!(!gossip.has_seen(&digest("tx-1")));
+        assert!(gossip.has_seen(&digest("tx-2")) && gossip.has_seen(&digest("tx-3")));
+    }
+
+    #[tokio::test]
+    async fn a_submission_reaches_every_validator_exactly_once() {
+        let mut nodes = Vec::new();
+        let mut members = Vec::new();
+        for name in ["v1", "v2", "v3"] {
+            let node = node(name).await.with_gossip();
+            node.mark_ready();
+            node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+            let endpoint = serve_endpoint(&node).await;
+            members.push(node.committee_member(endpoint));
+            nodes.push(node);
+        }
+        let committee = Committee::new(0, members.clone());
+        for node in &nodes {
+            node.set_committee(committee.clone()).await;
+        }
+        let request = transfer("alice", "coin-a", "bob");
+
+        let response = NetworkClient::new(members[0].endpoint.clone())
+            .submit_transaction(request.clone())
+            .await
+            .unwrap();
+        assert!(response.accepted, "{}", response.message);
+
+        for node in &nodes {
+            let mut effects = None;
+            for _ in 0..200 {
+                effects = node.effects_store.get_effects(&request.digest).await.unwrap();
+                if effects.is_some() {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            assert!(effects.is_some(), "{} never saw the transaction", node.name());
+        }
+        // Give any echo time to arrive; it must be recognised, not re-run.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        for node in &nodes {
+            assert_eq!(node.transaction_log().query(&TxLogFilter::default()).len(), 1, "{}", node.name());
+            assert!(node.gossip.as_ref().unwrap().has_seen(&request.digest));
+        }
+    }
+}
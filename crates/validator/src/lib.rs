@@ -1,32 +1,127 @@
+mod approvals;
+mod attestation;
+mod commit;
+mod consensus;
+mod executed;
+mod gossip;
+mod mempool;
+mod middleware;
+mod parallel;
+mod policy;
+mod receipts;
+mod scheduler;
+mod sink;
+mod supply;
+mod txlog;
+
+#[cfg(test)]
+mod testing;
+
+pub use approvals::ApprovalRegistry;
+pub use attestation::{AttestationCache, DEFAULT_ATTESTATION_TTL_MS};
+pub use commit::{CommitBundle, SequenceAdvance};
+pub use consensus::{ConsensusEngine, LocalConsensus, SimulatedConsensus};
+pub use executed::ExecutedDigests;
+pub use gossip::{GossipLayer, DEFAULT_GOSSIP_MEMORY};
+pub use mempool::{
+    spawn_sweeper, Mempool, MempoolError, MempoolReceiver, PendingTransaction, DEFAULT_MEMPOOL_MAX_AGE_MS,
+};
+pub use middleware::TxMiddleware;
+pub use parallel::{ParallelScheduler, SchedulingStats};
+pub use policy::AdmissionPolicy;
+pub use receipts::DEFAULT_RECEIPT_CAPACITY;
+pub use scheduler::{DeterministicScheduler, DEFAULT_SCHEDULER_WINDOW};
+pub use sink::{ChannelSink, EffectsSink, WebhookSink, DEFAULT_WEBHOOK_TIMEOUT};
+pub use supply::{SupplyTracker, NATIVE_COIN_TYPE};
+pub use txlog::TransactionLog;
+
 use anyhow::{anyhow, Result};
+use consensus::{PositionGate, Turn};
+use executed::Claim;
+use receipts::ReceiptCache;
 use async_trait::async_trait;
-use aws_nautilus_sdk::{EnclaveInfo, NautilusClient};
+use aws_nautilus_sdk::{EnclaveInfo, NautilusClient, PoolStats};
 use serde_json::json;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
-use sui_checkpoint::CheckpointAggregator;
+use std::time::Duration;
+use sui_checkpoint::{objects_root_with, CertifiedTransaction, CheckpointAggregator};
 use sui_core::{
-    messages::{CheckpointSummary, ExecutionRequest},
-    object::{ObjectData, ObjectID, Owner, SuiObject},
+    clock::{Clock, SystemClock},
+    committee::{Committee, CommitteeMember},
+    crypto::KeyPair,
+    hash::{default_hasher, Hasher},
+    messages::{CheckpointSummary, ExecutionRequest, ExecutionStatus, TransactionReceipt, TxLogEntry, TxLogFilter},
+    transaction::TransactionDigest,
+    object::{ObjectData, Owner, SuiObject},
 };
 use sui_effects::EffectsBuilder;
-use sui_locking::{LockManager, LockMode};
+use sui_locking::{ConflictScreener, LockManager, LockMode, LockPlan, LockStats};
 use sui_network::{NetworkServer, TransactionHandler};
-use sui_precheck::PreCheckPipeline;
-use sui_storage::{CheckpointStore, EffectsStore, ObjectStore};
-use sui_vm::MoveVMExecutor;
+use sui_precheck::{FeeModel, PreCheckPipeline, SequenceStore};
+use sui_storage::{CheckpointStore, EffectsStore, ObjectStore, OverlayObjectStore};
+use sui_vm::{ApprovalChange, ExecutionResult, MoveVMExecutor, VmConfig};
 
 pub struct ValidatorNode {
     name: String,
     precheck: PreCheckPipeline,
     lock_manager: Arc<LockManager>,
+    screener: Arc<ConflictScreener>,
     vm: Arc<MoveVMExecutor>,
     checkpoints: Arc<tokio::sync::Mutex<CheckpointAggregator>>,
-    sequence: Arc<tokio::sync::Mutex<u64>>,
+    /// Last committed checkpoint sequence number. Only advanced while the
+    /// `checkpoints` lock is held; reads need no lock.
+    sequence: Arc<AtomicU64>,
     nautilus_client: Arc<NautilusClient>,
     nautilus_enclave_id: String,
     object_store: Arc<dyn ObjectStore>,
     effects_store: Arc<dyn EffectsStore>,
     checkpoint_store: Arc<dyn CheckpointStore>,
+    clock: Arc<dyn Clock>,
+    attestation: Arc<AttestationCache>,
+    keypair: Arc<KeyPair>,
+    committee: Arc<tokio::sync::RwLock<Committee>>,
+    receipts: Arc<tokio::sync::RwLock<ReceiptCache>>,
+    mempool: Arc<Mempool>,
+    ready: Arc<AtomicBool>,
+    hasher: Arc<dyn Hasher>,
+    fee_model: Option<Arc<FeeModel>>,
+    /// Shared with the pre-check pipeline, which only checks sequence
+    /// numbers; they are consumed when a transaction commits.
+    sequences: Option<Arc<dyn SequenceStore>>,
+    in_flight: Arc<AtomicUsize>,
+    admission_limit: Option<usize>,
+    middleware: Vec<Arc<dyn TxMiddleware>>,
+    effects_sink: Option<Arc<dyn EffectsSink>>,
+    scheduler: Option<DeterministicScheduler>,
+    tx_log: Arc<TransactionLog>,
+    supply: Arc<SupplyTracker>,
+    approvals: Arc<ApprovalRegistry>,
+    consensus: Option<Arc<dyn ConsensusEngine>>,
+    consensus_gate: Arc<PositionGate>,
+    gossip: Option<Arc<GossipLayer>>,
+    admission_policy: Option<Arc<dyn AdmissionPolicy>>,
+    parallel: Option<Arc<ParallelScheduler>>,
+    executed: Option<Arc<ExecutedDigests>>,
+}
+
+/// A transaction's results held back until its `CommitBundle` is applied.
+struct PreparedCommit {
+    request: ExecutionRequest,
+    effects: sui_effects::TransactionEffects,
+    minted: u64,
+    burned: u64,
+    approvals: Vec<ApprovalChange>,
+}
+
+/// Takes its count of transactions back off the in-flight count when
+/// dropped.
+struct InFlightGuard<'a>(&'a AtomicUsize, usize);
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(self.1, Ordering::SeqCst);
+    }
 }
 
 impl ValidatorNode {
@@ -37,109 +132,989 @@ impl ValidatorNode {
         checkpoint_store: Arc<dyn CheckpointStore>,
     ) -> Result<Self> {
         let name = name.into();
-        let client = Arc::new(NautilusClient::connect_sync()?);
+        let client = Arc::new(NautilusClient::connect().await?);
         let enclave_id = client.create_enclave_sync(&EnclaveInfo {
             name: format!("{}-enclave", name),
             cpu_cores: 2,
             memory_mb: 4096,
         })?;
 
-        let vm = Arc::new(MoveVMExecutor::with_object_store(
-            Box::new(InMemoryObjectStoreWrapper {
+        let approvals = Arc::new(ApprovalRegistry::new());
+        let vm = Arc::new(
+            MoveVMExecutor::with_object_store(Box::new(InMemoryObjectStoreWrapper {
                 store: object_store.clone(),
+            }))
+            .with_config(VmConfig {
+                approvals: Some(approvals.clone()),
+                ..Default::default()
             }),
+        );
+
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+        let attestation = Arc::new(AttestationCache::new(
+            DEFAULT_ATTESTATION_TTL_MS,
+            Arc::clone(&clock),
         ));
 
+        let keypair = Arc::new(KeyPair::generate());
+        let committee = Committee::new(
+            0,
+            vec![CommitteeMember {
+                name: name.clone(),
+                public_key: keypair.public_key(),
+                endpoint: String::new(),
+            }],
+        );
+
+        let lock_manager = Arc::new(LockManager::new());
         Ok(Self {
             name: name.clone(),
             precheck: PreCheckPipeline::default(),
-            lock_manager: Arc::new(LockManager::new()),
+            screener: Arc::new(ConflictScreener::new(Arc::clone(&lock_manager))),
+            lock_manager,
             vm,
             checkpoints: Arc::new(tokio::sync::Mutex::new(CheckpointAggregator::new())),
-            sequence: Arc::new(tokio::sync::Mutex::new(0)),
+            sequence: Arc::new(AtomicU64::new(0)),
             nautilus_client: client,
             nautilus_enclave_id: enclave_id,
             object_store,
             effects_store,
             checkpoint_store,
+            clock: Arc::clone(&clock),
+            attestation,
+            keypair,
+            committee: Arc::new(tokio::sync::RwLock::new(committee)),
+            receipts: Arc::new(tokio::sync::RwLock::new(ReceiptCache::new(DEFAULT_RECEIPT_CAPACITY))),
+            mempool: Arc::new(Mempool::new(clock, DEFAULT_MEMPOOL_MAX_AGE_MS)),
+            ready: Arc::new(AtomicBool::new(false)),
+            hasher: default_hasher(),
+            fee_model: None,
+            sequences: None,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            admission_limit: None,
+            middleware: Vec::new(),
+            effects_sink: None,
+            scheduler: None,
+            tx_log: Arc::new(TransactionLog::new()),
+            supply: Arc::new(SupplyTracker::new()),
+            approvals,
+            consensus: None,
+            consensus_gate: Arc::new(PositionGate::new()),
+            gossip: None,
+            admission_policy: None,
+            parallel: None,
+            executed: None,
         })
     }
 
+    /// Replaces the committee this validator reports as current. Until one is
+    /// set the validator forms a single-member committee at epoch 0.
+    pub async fn set_committee(&self, committee: Committee) {
+        *self.committee.write().await = committee;
+    }
+
+    pub async fn committee(&self) -> Committee {
+        self.committee.read().await.clone()
+    }
+
+    pub fn public_key(&self) -> String {
+        self.keypair.public_key()
+    }
+
+    /// This validator's `(name, signature)` towards a `CertifiedTransaction`
+    /// for `digest`.
+    pub fn sign_transaction_certificate(&self, digest: &TransactionDigest) -> (String, String) {
+        let signature = self.keypair.sign(&CertifiedTransaction::signing_bytes(digest));
+        (self.name.clone(), signature)
+    }
+
+    /// Describes this validator as a committee member reachable at `endpoint`.
+    pub fn committee_member(&self, endpoint: impl Into<String>) -> CommitteeMember {
+        CommitteeMember {
+            name: self.name.clone(),
+            public_key: self.public_key(),
+            endpoint: endpoint.into(),
+        }
+    }
+
+    /// Replaces the time source used for all time-dependent behaviour.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.attestation = Arc::new(
+            AttestationCache::new(self.attestation.ttl_ms(), Arc::clone(&clock))
+                .with_skew_tolerance(self.attestation.skew_tolerance_ms()),
+        );
+        self.mempool = Arc::new(
+            Mempool::new(Arc::clone(&clock), self.mempool.max_age_ms())
+                .with_capacity(self.mempool.capacity())
+                .with_skew_tolerance(self.mempool.skew_tolerance_ms()),
+        );
+        self.clock = clock;
+        self.replace_lock_manager(self.lock_manager.lease_ms());
+        self
+    }
+
+    /// Tolerates `tolerance_ms` of clock difference with clients and
+    /// enclaves: mempool deadlines and attestation timestamps are only
+    /// enforced once exceeded by more than that.
+    pub fn with_clock_skew_tolerance(mut self, tolerance_ms: u64) -> Self {
+        self.attestation = Arc::new(
+            AttestationCache::new(self.attestation.ttl_ms(), Arc::clone(&self.clock))
+                .with_skew_tolerance(tolerance_ms),
+        );
+        self.mempool = Arc::new(
+            Mempool::new(Arc::clone(&self.clock), self.mempool.max_age_ms())
+                .with_capacity(self.mempool.capacity())
+                .with_skew_tolerance(tolerance_ms),
+        );
+        self
+    }
+
+    /// Caps queued plus executing transactions at `limit`. Beyond it the
+    /// mempool refuses submissions and the network server answers 503 with
+    /// `Retry-After` until load drops.
+    pub fn with_admission_limit(mut self, limit: usize) -> Self {
+        self.mempool = Arc::new(
+            Mempool::new(Arc::clone(&self.clock), self.mempool.max_age_ms())
+                .with_capacity(Some(limit))
+                .with_skew_tolerance(self.mempool.skew_tolerance_ms()),
+        );
+        self.admission_limit = Some(limit);
+        self
+    }
+
+    /// Queued plus executing transactions as a fraction of the admission
+    /// limit; always 0 without one.
+    pub fn queue_saturation(&self) -> f64 {
+        match self.admission_limit {
+            Some(limit) if limit > 0 => self.pending_load() as f64 / limit as f64,
+            Some(_) => 1.0,
+            None => 0.0,
+        }
+    }
+
+    pub fn is_saturated(&self) -> bool {
+        self.admission_limit
+            .is_some_and(|limit| self.pending_load() >= limit)
+    }
+
+    fn pending_load(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst) + self.mempool.len()
+    }
+
+    /// Keeps the receipts of the latest `capacity` transactions instead of
+    /// `DEFAULT_RECEIPT_CAPACITY`.
+    pub fn with_receipt_capacity(mut self, capacity: usize) -> Self {
+        self.receipts = Arc::new(tokio::sync::RwLock::new(ReceiptCache::new(capacity)));
+        self
+    }
+
+    /// Processes the mempool in canonical order instead of arrival order;
+    /// see `DeterministicScheduler`.
+    pub fn with_deterministic_scheduler(mut self, scheduler: DeterministicScheduler) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+
+    /// Records every transaction this validator executes in `executed` and
+    /// rejects any transaction already in it, whoever executed it. Sharing
+    /// one set across a committee keeps a transaction replayed to several
+    /// members from executing more than once.
+    pub fn with_executed_digests(mut self, executed: Arc<ExecutedDigests>) -> Self {
+        self.executed = Some(executed);
+        self
+    }
+
+    pub fn executed_digests(&self) -> Option<Arc<ExecutedDigests>> {
+        self.executed.clone()
+    }
+
+    /// Executes mempool transactions with disjoint declared inputs
+    /// concurrently; see `ParallelScheduler`. Each window drained from the
+    /// mempool is split into waves that run one after another.
+    pub fn with_parallel_execution(mut self) -> Self {
+        self.parallel = Some(Arc::new(ParallelScheduler::new()));
+        self
+    }
+
+    /// Parallelism achieved by `process_mempool`, when parallel execution
+    /// is enabled.
+    pub fn scheduling_stats(&self) -> Option<SchedulingStats> {
+        self.parallel.as_ref().map(|parallel| parallel.stats())
+    }
+
+    /// Has `engine` order every transaction before it executes. Transactions
+    /// then execute one at a time in consensus order, and each checkpoint
+    /// records its transaction's position. Without an engine transactions
+    /// execute as soon as their locks are free.
+    pub fn with_consensus(mut self, engine: Arc<dyn ConsensusEngine>) -> Self {
+        self.consensus = Some(engine);
+        self.consensus_gate = Arc::new(PositionGate::new());
+        self
+    }
+
+    /// Forwards every transaction submitted over the network to the other
+    /// members of the committee, reached at their `endpoint`s, and ignores
+    /// repeat submissions of a digest; see `GossipLayer`.
+    pub fn with_gossip(mut self) -> Self {
+        self.gossip = Some(Arc::new(GossipLayer::new(self.name.clone())));
+        self
+    }
+
+    /// Sends the effects of every committed transaction to `sink`,
+    /// replacing any previous sink.
+    pub fn set_effects_sink(&mut self, sink: Arc<dyn EffectsSink>) {
+        self.effects_sink = Some(sink);
+    }
+
+    /// Caps concurrent sends to the enclave at `size` connections. Installs
+    /// a new client, so clones of the node taken before keep the old one.
+    pub fn with_enclave_pool(mut self, size: usize) -> Self {
+        let mut client = NautilusClient::clone(&self.nautilus_client);
+        client.set_pool(size);
+        self.nautilus_client = Arc::new(client);
+        self
+    }
+
+    /// Enclave connection pool utilization, if a pool is configured.
+    pub fn enclave_pool_stats(&self) -> Option<PoolStats> {
+        self.nautilus_client.pool_stats()
+    }
+
+    /// Gives transaction input locks a lease of `lease_ms`, measured on the
+    /// validator's clock, so `reclaim_expired_locks` can free locks left by
+    /// stuck transactions.
+    pub fn with_lock_lease(mut self, lease_ms: u64) -> Self {
+        self.replace_lock_manager(Some(lease_ms));
+        self
+    }
+
+    /// Installs an empty lock manager on the current clock, along with the
+    /// screener that reads it. Only for use while building the node.
+    fn replace_lock_manager(&mut self, lease_ms: Option<u64>) {
+        let mut lock_manager = LockManager::new().with_clock(Arc::clone(&self.clock));
+        if let Some(lease_ms) = lease_ms {
+            lock_manager = lock_manager.with_lease(lease_ms);
+        }
+        let lock_manager = Arc::new(lock_manager);
+        self.screener = Arc::new(ConflictScreener::new(Arc::clone(&lock_manager)));
+        self.lock_manager = lock_manager;
+    }
+
+    pub fn approvals(&self) -> Arc<ApprovalRegistry> {
+        Arc::clone(&self.approvals)
+    }
+
+    pub fn coin_supply(&self) -> Arc<SupplyTracker> {
+        Arc::clone(&self.supply)
+    }
+
+    pub fn transaction_log(&self) -> Arc<TransactionLog> {
+        Arc::clone(&self.tx_log)
+    }
+
+    /// Forces buffered checkpoints out to durable storage.
+    pub async fn flush_checkpoints(&self) -> Result<()> {
+        self.checkpoint_store.flush().await
+    }
+
+    /// Forces effects held back by the effects store's durability policy
+    /// out to durable storage.
+    pub async fn sync_effects(&self) -> Result<()> {
+        self.effects_store.sync().await
+    }
+
+    /// Releases locks whose lease has run out, returning their keys.
+    pub fn reclaim_expired_locks(&self) -> Vec<String> {
+        self.lock_manager.reclaim_expired(self.clock.now())
+    }
+
+    /// Rejects transactions `policy` does not admit, after the built-in
+    /// pre-checks and before any middleware runs.
+    pub fn with_admission_policy(mut self, policy: Arc<dyn AdmissionPolicy>) -> Self {
+        self.admission_policy = Some(policy);
+        self
+    }
+
+    /// Appends a hook run around every transaction; see `TxMiddleware`.
+    pub fn add_middleware(&mut self, middleware: Arc<dyn TxMiddleware>) {
+        self.middleware.push(middleware);
+    }
+
+    /// Sets how long a transaction may wait in the mempool before expiring.
+    pub fn with_mempool_max_age(mut self, max_age_ms: u64) -> Self {
+        self.mempool = Arc::new(
+            Mempool::new(Arc::clone(&self.clock), max_age_ms)
+                .with_capacity(self.mempool.capacity())
+                .with_skew_tolerance(self.mempool.skew_tolerance_ms()),
+        );
+        self
+    }
+
+    /// Rebuilds the VM with the given configuration. Operator approvals
+    /// always come from this node's `ApprovalRegistry`.
+    pub fn with_vm_config(mut self, mut config: VmConfig) -> Self {
+        config.approvals = Some(self.approvals.clone());
+        self.vm = Arc::new(
+            MoveVMExecutor::with_object_store(Box::new(InMemoryObjectStoreWrapper {
+                store: Arc::clone(&self.object_store),
+            }))
+            .with_config(config),
+        );
+        self
+    }
+
+    /// Sets the hash function used for checkpoint Merkle roots and digests.
+    /// Installs a new checkpoint aggregator, so call it before the node
+    /// records any checkpoints.
+    pub fn with_hasher(mut self, hasher: Arc<dyn Hasher>) -> Self {
+        self.checkpoints = Arc::new(tokio::sync::Mutex::new(
+            CheckpointAggregator::new().with_hasher(Arc::clone(&hasher)),
+        ));
+        self.hasher = hasher;
+        self
+    }
+
+    pub fn hasher(&self) -> Arc<dyn Hasher> {
+        Arc::clone(&self.hasher)
+    }
+
+    /// Enforces a minimum gas price that follows checkpoint load.
+    pub fn with_fee_model(mut self, fee_model: Arc<FeeModel>) -> Self {
+        self.precheck = self.precheck.with_fee_model(Arc::clone(&fee_model));
+        self.fee_model = Some(fee_model);
+        self
+    }
+
+    /// The current minimum gas price, if a fee model is configured.
+    pub fn base_fee(&self) -> Option<u64> {
+        self.fee_model.as_ref().map(|model| model.base_fee())
+    }
+
+    /// Rejects transactions whose sequence number is not the signer's next.
+    pub fn with_sequence_store(mut self, store: Arc<dyn SequenceStore>) -> Self {
+        self.precheck = self.precheck.with_sequence_store(Arc::clone(&store));
+        self.sequences = Some(store);
+        self
+    }
+
+    /// Rejects transactions whose complexity score is above `max`.
+    pub fn with_max_complexity(mut self, max: u64) -> Self {
+        self.precheck = self.precheck.with_max_complexity(max);
+        self
+    }
+
+    /// Sets how long an enclave attestation is reused before re-attesting.
+    pub fn with_attestation_ttl(mut self, ttl_ms: u64) -> Self {
+        self.attestation = Arc::new(
+            AttestationCache::new(ttl_ms, Arc::clone(&self.clock))
+                .with_skew_tolerance(self.attestation.skew_tolerance_ms()),
+        );
+        self
+    }
+
+    pub fn attestation_cache(&self) -> &AttestationCache {
+        &self.attestation
+    }
+
+    /// Returns a valid attestation token for this validator's enclave,
+    /// re-attesting only once the cached one has expired.
+    pub async fn attestation_token(&self) -> Result<String> {
+        self.attestation
+            .get_or_attest(|| self.nautilus_client.attest(&self.nautilus_enclave_id))
+            .await
+    }
+
+    /// Attests the enclave and recovers the checkpoint sequence from the
+    /// checkpoint store, then marks the validator ready. Until then its
+    /// network server answers `/ready` and transaction submissions with 503.
+    pub async fn warm_up(&self) -> Result<()> {
+        self.attestation_token().await?;
+
+        if let Some(latest) = self.checkpoint_store.get_latest_sequence().await? {
+            let mut checkpoints = self.checkpoints.lock().await;
+            if let Some(summary) = self.checkpoint_store.get_checkpoint_typed(latest).await? {
+                checkpoints.record(summary);
+            }
+            self.sequence.store(latest, Ordering::SeqCst);
+        }
+
+        self.mark_ready();
+        Ok(())
+    }
+
+    pub fn mark_ready(&self) {
+        self.ready.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::SeqCst)
+    }
+
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        Arc::clone(&self.clock)
+    }
+
     pub async fn handle_transaction(&self, request: ExecutionRequest) -> Result<sui_effects::TransactionEffects> {
-        let report = self
-            .precheck
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let _in_flight = InFlightGuard(&self.in_flight, 1);
+
+        #[cfg(feature = "fault-injection")]
+        self.check_injected_fault(sui_vm::FaultStage::PreCheck, &request)?;
+        self.precheck
             .run(&request)
             .map_err(|err| anyhow!("pre-check failed: {err}"))?;
+        // Released again unless the transaction commits.
+        let claim = match &self.executed {
+            Some(executed) => Some(Claim::acquire(executed, &request.digest, &self.name)?),
+            None => None,
+        };
 
-        let simulated_object = SuiObject::new(
-            ObjectID::new("object-to-lock"),
-            Owner::Shared,
-            ObjectData::Coin { balance: 0 },
-        );
+        // Held until execution finishes, keeping later positions waiting.
+        let turn = match &self.consensus {
+            Some(engine) => Some(Turn::acquire(engine.as_ref(), &self.consensus_gate, &request).await?),
+            None => None,
+        };
+
+        let inputs = self.load_input_objects(&request).await?;
+        self.precheck
+            .check_input_objects(&request, &inputs)
+            .map_err(|err| anyhow!("pre-check failed: {err}"))?;
+        if let Some(policy) = &self.admission_policy {
+            policy
+                .admit(&request, &inputs)
+                .map_err(|reason| anyhow!("rejected by admission policy: {reason}"))?;
+        }
+
+        for middleware in &self.middleware {
+            middleware.before(&request).await?;
+        }
+
+        let plan = Self::plan_locks(&request, &inputs);
+        if let Err(conflicts) = self.screener.screen(&plan) {
+            let ids: Vec<_> = conflicts.iter().map(|id| id.0.as_str()).collect();
+            return Err(anyhow!("transaction inputs are locked: {}", ids.join(", ")));
+        }
+        if !self.lock_manager.acquire_plan(&plan) {
+            return Err(anyhow!("unable to acquire locks for transaction inputs"));
+        }
+
+        let result = match self.reload_locked_inputs(&request, &inputs).await {
+            Ok(locked) => {
+                self.execute_locked(request.clone(), &locked, turn.as_ref().map(Turn::position))
+                    .await
+            }
+            Err(err) => Err(err),
+        };
+        self.lock_manager.release_plan(&plan);
+        drop(turn);
+        let effects = result?;
+        if let Some(claim) = claim {
+            claim.keep();
+        }
+
+        self.observe_committed(&request, &effects).await;
+        Ok(effects)
+    }
+
+    /// Re-reads the inputs once their locks are held. Locks are taken on the
+    /// versions read before locking, so they only protect the transaction
+    /// if those versions are still current; otherwise another transaction
+    /// committed in between and this one is rejected rather than executed
+    /// against a stale snapshot.
+    async fn reload_locked_inputs(&self, request: &ExecutionRequest, prefetched: &[SuiObject]) -> Result<Vec<SuiObject>> {
+        let current = self.load_input_objects(request).await?;
+        let unchanged = current.len() == prefetched.len()
+            && current
+                .iter()
+                .zip(prefetched)
+                .all(|(now, before)| now.id == before.id && now.version == before.version);
+        if !unchanged {
+            return Err(anyhow!("transaction inputs changed while acquiring their locks"));
+        }
+        Ok(current)
+    }
+
+    /// Hands committed effects to the effects sink and every middleware's
+    /// `after`. Both only observe: the transaction is already committed, so
+    /// their errors are logged rather than returned.
+    async fn observe_committed(&self, request: &ExecutionRequest, effects: &sui_effects::TransactionEffects) {
+        if let Some(sink) = &self.effects_sink {
+            if let Err(err) = sink.emit(effects).await {
+                eprintln!("Error emitting effects for {}: {}", effects.digest.0, err);
+            }
+        }
+        for middleware in &self.middleware {
+            if let Err(err) = middleware.after(request, effects).await {
+                eprintln!("Error in middleware after {}: {}", effects.digest.0, err);
+            }
+        }
+    }
+
+    /// Runs the pre-checks and executes `request` against the current state
+    /// without committing anything, optionally with an execution trace. The
+    /// signer's sequence number is checked but not consumed.
+    pub async fn dry_run(&self, request: &ExecutionRequest, trace: bool) -> Result<ExecutionResult> {
+        self.precheck
+            .run(request)
+            .map_err(|err| anyhow!("pre-check failed: {err}"))?;
+        Ok(if trace {
+            self.vm.execute_traced(request).await
+        } else {
+            self.vm.execute(request).await
+        })
+    }
+
+    /// Maps the request's declared inputs to the locks it must hold: mutable
+    /// inputs are locked exclusively, read-only inputs shared, and immutable
+    /// objects need no lock at all. Every input of a read-only transaction is
+    /// locked shared. Inputs missing from the store are skipped.
+    pub async fn lock_plan(&self, request: &ExecutionRequest) -> Result<LockPlan> {
+        let inputs = self.load_input_objects(request).await?;
+        Ok(Self::plan_locks(request, &inputs))
+    }
+
+    /// Loads the current state of every declared input that exists, in one
+    /// batched store read.
+    async fn load_input_objects(&self, request: &ExecutionRequest) -> Result<Vec<SuiObject>> {
+        Self::load_inputs_from(self.object_store.as_ref(), request).await
+    }
+
+    async fn load_inputs_from(store: &dyn ObjectStore, request: &ExecutionRequest) -> Result<Vec<SuiObject>> {
+        let ids: Vec<String> = request
+            .tx
+            .payload
+            .input_objects
+            .iter()
+            .map(|input| input.id.0.clone())
+            .collect();
+        Ok(store.get_objects(&ids).await?.into_iter().flatten().collect())
+    }
+
+    /// Executes `requests` all-or-nothing: each runs, in order, against the
+    /// state left by the ones before it, and only if every one passes its
+    /// checks and succeeds are they committed, each with its own effects
+    /// and checkpoint, applied together: a store write failing part way
+    /// undoes the writes of the whole batch. Otherwise nothing is written,
+    /// no sequence number is consumed, and the failure is returned.
+    ///
+    /// The inputs of the whole batch are locked up front. Not available
+    /// with a consensus engine, which orders transactions one at a time.
+    pub async fn handle_atomic_batch(
+        &self,
+        requests: Vec<ExecutionRequest>,
+    ) -> Result<Vec<sui_effects::TransactionEffects>> {
+        if self.consensus.is_some() {
+            return Err(anyhow!("atomic batches are not supported with a consensus engine"));
+        }
+        self.in_flight.fetch_add(requests.len(), Ordering::SeqCst);
+        let _in_flight = InFlightGuard(&self.in_flight, requests.len());
 
-        if report.requires_shared_lock
-            && !self
-                .lock_manager
-                .acquire(&simulated_object, LockMode::Exclusive)
-        {
-            return Err(anyhow!("unable to acquire lock for shared object"));
+        let mut plan = LockPlan::new();
+        for (index, request) in requests.iter().enumerate() {
+            self.precheck
+                .run_after(request, &requests[..index])
+                .map_err(|err| anyhow!("pre-check failed for {}: {err}", request.digest.0))?;
+            let inputs = self.load_input_objects(request).await?;
+            for (object, mode) in Self::plan_locks(request, &inputs).entries() {
+                plan.add(object.clone(), *mode);
+            }
+        }
+        if let Err(conflicts) = self.screener.screen(&plan) {
+            let ids: Vec<_> = conflicts.iter().map(|id| id.0.as_str()).collect();
+            return Err(anyhow!("transaction inputs are locked: {}", ids.join(", ")));
         }
+        let claims = match &self.executed {
+            Some(executed) => requests
+                .iter()
+                .map(|request| Claim::acquire(executed, &request.digest, &self.name))
+                .collect::<Result<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+        if !self.lock_manager.acquire_plan(&plan) {
+            return Err(anyhow!("unable to acquire locks for transaction inputs"));
+        }
+        let result = self.execute_atomic(requests).await;
+        self.lock_manager.release_plan(&plan);
+        if result.is_ok() {
+            for claim in claims {
+                claim.keep();
+            }
+        }
+        result
+    }
 
-        let exec_result = self.vm.execute(&request).await;
+    async fn execute_atomic(&self, requests: Vec<ExecutionRequest>) -> Result<Vec<sui_effects::TransactionEffects>> {
+        let overlay: Arc<dyn ObjectStore> = Arc::new(OverlayObjectStore::new(Arc::clone(&self.object_store)));
+        let vm = MoveVMExecutor::with_object_store(Box::new(InMemoryObjectStoreWrapper {
+            store: Arc::clone(&overlay),
+        }))
+        .with_config(self.vm.config().clone());
 
-        for obj in &exec_result.touched_objects {
-            self.object_store.put_object(obj.clone()).await?;
+        let mut results = Vec::with_capacity(requests.len());
+        for request in &requests {
+            let inputs = Self::load_inputs_from(overlay.as_ref(), request).await?;
+            self.precheck
+                .check_input_objects(request, &inputs)
+                .map_err(|err| anyhow!("pre-check failed for {}: {err}", request.digest.0))?;
+            if let Some(policy) = &self.admission_policy {
+                policy
+                    .admit(request, &inputs)
+                    .map_err(|reason| anyhow!("{} rejected by admission policy: {reason}", request.digest.0))?;
+            }
+            for middleware in &self.middleware {
+                middleware.before(request).await?;
+            }
+
+            let result = vm.execute_with_inputs(request, &inputs).await;
+            if let ExecutionStatus::Failure { code, message } = &result.status {
+                return Err(anyhow!(
+                    "{} aborted with code {}: {}; no transaction of the batch was committed",
+                    request.digest.0,
+                    code,
+                    message
+                ));
+            }
+            for object in result.touched_objects.iter().chain(&result.gas_coin) {
+                overlay.put_object(object.clone()).await?;
+            }
+            for id in &result.deleted {
+                overlay.delete_object(&id.0).await?;
+            }
+            results.push(result);
+        }
+
+        let mut bundles = Vec::with_capacity(requests.len());
+        let mut prepared = Vec::with_capacity(requests.len());
+        for (request, result) in requests.iter().zip(results) {
+            let (bundle, commit) = self.prepare_commit(request.clone(), result, None)?;
+            bundles.push(bundle);
+            prepared.push(commit);
         }
+        let checkpoints = CommitBundle::apply_all(
+            bundles,
+            &self.sequence,
+            &self.checkpoints,
+            self.object_store.as_ref(),
+            self.effects_store.as_ref(),
+            self.checkpoint_store.as_ref(),
+        )
+        .await?;
 
-        let mut builder = EffectsBuilder::new(request.digest.clone());
-        for touched in exec_result.touched_objects {
-            builder = builder.record_created(touched);
+        let mut committed = Vec::with_capacity(requests.len());
+        for (commit, checkpoint) in prepared.into_iter().zip(&checkpoints) {
+            committed.push(self.finish_commit(commit, checkpoint).await);
+        }
+        for (request, effects) in requests.iter().zip(&committed) {
+            self.observe_committed(request, effects).await;
+        }
+        Ok(committed)
+    }
+
+    fn plan_locks(request: &ExecutionRequest, objects: &[SuiObject]) -> LockPlan {
+        let mut plan = LockPlan::new();
+        for input in &request.tx.payload.input_objects {
+            let Some(object) = objects.iter().find(|object| object.id == input.id) else {
+                continue;
+            };
+            if object.owner == Owner::Immutable {
+                continue;
+            }
+            let mode = if input.mutable && !request.tx.payload.read_only {
+                LockMode::Exclusive
+            } else {
+                LockMode::Shared
+            };
+            plan.add(object.clone(), mode);
+        }
+        plan
+    }
+
+    /// Fails with the fault armed for `request` at `stage`, if any, in the
+    /// VM config's fault injector.
+    #[cfg(feature = "fault-injection")]
+    fn check_injected_fault(&self, stage: sui_vm::FaultStage, request: &ExecutionRequest) -> Result<()> {
+        let fault = self
+            .vm
+            .config()
+            .fault_injector
+            .as_ref()
+            .and_then(|injector| injector.check(stage, request));
+        match fault {
+            Some(abort) => Err(anyhow!(
+                "injected fault at {:?} (code {}): {}",
+                stage,
+                abort.code,
+                abort.message
+            )),
+            None => Ok(()),
+        }
+    }
+
+    async fn execute_locked(
+        &self,
+        request: ExecutionRequest,
+        inputs: &[SuiObject],
+        consensus_position: Option<u64>,
+    ) -> Result<sui_effects::TransactionEffects> {
+        // The inputs were prefetched for the pre-checks; the VM reads them
+        // from memory rather than going back to the store.
+        let exec_result = self.vm.execute_with_inputs(&request, inputs).await;
+        #[cfg(feature = "fault-injection")]
+        self.check_injected_fault(sui_vm::FaultStage::Commit, &request)?;
+        self.commit_result(request, exec_result, consensus_position).await
+    }
+
+    /// Commits the outcome of an executed transaction: its object writes,
+    /// effects and checkpoint in one `CommitBundle`, then its receipt.
+    async fn commit_result(
+        &self,
+        request: ExecutionRequest,
+        exec_result: ExecutionResult,
+        consensus_position: Option<u64>,
+    ) -> Result<sui_effects::TransactionEffects> {
+        let (bundle, commit) = self.prepare_commit(request, exec_result, consensus_position)?;
+        let committed = bundle
+            .apply(
+                &self.sequence,
+                &self.checkpoints,
+                self.object_store.as_ref(),
+                self.effects_store.as_ref(),
+                self.checkpoint_store.as_ref(),
+            )
+            .await?;
+        Ok(self.finish_commit(commit, &committed).await)
+    }
+
+    /// Builds the effects and the `CommitBundle` of an executed transaction,
+    /// along with what `finish_commit` needs once the bundle is applied.
+    fn prepare_commit(
+        &self,
+        request: ExecutionRequest,
+        exec_result: ExecutionResult,
+        consensus_position: Option<u64>,
+    ) -> Result<(CommitBundle, PreparedCommit)> {
+        let mut builder = EffectsBuilder::new(request.digest.clone())
+            .with_status(exec_result.status.clone())
+            .record_metering(exec_result.reads, exec_result.writes)
+            .record_input_versions(exec_result.input_versions.clone())
+            .record_input_coins(exec_result.input_coins.clone());
+        if let Some(batch) = exec_result.batch.clone() {
+            builder = builder.record_batch_result(batch);
+        }
+
+        // Aborted transactions still pay gas and record their status, but
+        // none of their object writes take effect.
+        let (mut created, mut mutated, mut deleted) = (Vec::new(), Vec::new(), Vec::new());
+        if exec_result.status.is_success() {
+            for id in exec_result.deleted {
+                builder = builder.record_deleted(id.clone());
+                deleted.push(id);
+            }
+            for touched in exec_result.touched_objects {
+                if exec_result.created.contains(&touched.id) {
+                    builder = builder.record_created(touched.clone());
+                    created.push(touched);
+                } else {
+                    builder = builder.record_mutated(touched.clone());
+                    mutated.push(touched);
+                }
+            }
+        }
+        // Gas is paid whether or not the transaction succeeded.
+        if let Some(gas_coin) = exec_result.gas_coin {
+            mutated.push(gas_coin.clone());
+            let balance = match gas_coin.data {
+                ObjectData::Coin { balance } => balance,
+                _ => 0,
+            };
+            builder = builder
+                .record_gas_refund(exec_result.gas_refund, Some((gas_coin.id.clone(), balance)))
+                .record_mutated(gas_coin);
         }
         for log in exec_result.logs {
             builder = builder.record_event(log);
         }
+        for event in exec_result.events {
+            builder = builder.record_object_event(event);
+        }
+        builder = builder.record_return_values(exec_result.return_values);
         let effects = builder.build();
 
-        let effects_json = serde_json::to_string(&effects)?;
-        self.effects_store
-            .save_effects(&request.digest, &effects_json)
-            .await?;
-
-        let mut seq = self.sequence.lock().await;
-        *seq += 1;
-        let current_seq = *seq;
-        drop(seq);
+        let mut checkpoint = CheckpointSummary::new(0, request.digest.0.clone());
+        checkpoint.timestamp_ms = self.clock.now();
+        checkpoint.consensus_position = consensus_position;
+        let written: Vec<_> = effects.created.iter().chain(&effects.mutated).cloned().collect();
+        checkpoint.objects_root = objects_root_with(self.hasher.as_ref(), &written);
+        checkpoint.record_transaction(
+            exec_result.gas_used,
+            effects.created.len(),
+            effects.mutated.len(),
+            effects.deleted.len(),
+        );
 
-        let checkpoint = CheckpointSummary {
-            sequence_number: current_seq,
-            transaction_count: 1,
-            root_digest: request.digest.0.clone(),
+        let bundle = CommitBundle {
+            digest: request.digest.clone(),
+            effects_json: serde_json::to_string(&effects)?,
+            checkpoint,
+            created,
+            mutated,
+            deleted,
+            sequence_advance: self.sequences.as_ref().map(|store| SequenceAdvance {
+                store: Arc::clone(store),
+                signer: request.tx.signer.clone(),
+                sequence: request.tx.payload.sequence_number,
+            }),
+        };
+        let commit = PreparedCommit {
+            request,
+            effects,
+            minted: exec_result.minted,
+            burned: exec_result.burned,
+            approvals: exec_result.approvals,
         };
+        Ok((bundle, commit))
+    }
 
-        let mut checkpoints = self.checkpoints.lock().await;
-        checkpoints.record(checkpoint.clone());
-        drop(checkpoints);
+    /// Everything that follows a transaction's applied bundle: fee load,
+    /// coin supply, approvals, its receipt and log entry.
+    async fn finish_commit(
+        &self,
+        commit: PreparedCommit,
+        committed: &CheckpointSummary,
+    ) -> sui_effects::TransactionEffects {
+        let PreparedCommit { request, effects, minted, burned, approvals } = commit;
+        if let Some(fee_model) = &self.fee_model {
+            fee_model.record_checkpoint(committed.transaction_count);
+        }
+        self.supply.apply(NATIVE_COIN_TYPE, minted, burned);
+        self.approvals.apply(&approvals);
 
-        let checkpoint_json = serde_json::to_string(&checkpoint)?;
-        self.checkpoint_store
-            .save_checkpoint(current_seq, &checkpoint_json)
-            .await?;
+        let receipt = TransactionReceipt::new_signed(
+            request.digest.clone(),
+            self.name.clone(),
+            committed.sequence_number,
+            effects.status.is_success(),
+            committed.timestamp_ms,
+            &self.keypair,
+        );
+        self.receipts.write().await.insert(receipt);
+        self.tx_log.append(TxLogEntry {
+            digest: request.digest.clone(),
+            signer: request.tx.signer.clone(),
+            status: effects.status.clone(),
+            sequence: committed.sequence_number,
+            timestamp_ms: committed.timestamp_ms,
+        });
 
+        let attestation = self.attestation_token().await.ok();
         let payload = json!({
             "validator": self.name,
             "digest": request.digest.0,
             "event_count": effects.events.len(),
+            "attestation": attestation,
         });
         let _ = self
             .nautilus_client
             .send_transaction_sync(&self.nautilus_enclave_id, payload);
 
-        if report.requires_shared_lock {
-            self.lock_manager
-                .release(&simulated_object, LockMode::Exclusive);
+        effects
+    }
+
+    /// Lock usage and contention since the validator started.
+    pub fn lock_stats(&self) -> LockStats {
+        self.lock_manager.stats()
+    }
+
+    pub fn mempool(&self) -> Arc<Mempool> {
+        Arc::clone(&self.mempool)
+    }
+
+    /// Queues a transaction for later execution by [`ValidatorNode::process_mempool`].
+    pub fn enqueue_transaction(&self, request: ExecutionRequest) -> MempoolReceiver {
+        self.mempool.submit(request)
+    }
+
+    /// Executes every live transaction currently in the mempool, delivering
+    /// each outcome to its submitter. Returns how many were executed.
+    pub async fn process_mempool(&self) -> usize {
+        let mut processed = 0;
+        if let Some(parallel) = &self.parallel {
+            loop {
+                let window = match self.scheduler {
+                    Some(scheduler) => scheduler.next_window(&self.mempool),
+                    None => self.mempool.take_up_to(DEFAULT_SCHEDULER_WINDOW),
+                };
+                if window.is_empty() {
+                    break;
+                }
+                for wave in parallel.schedule(window) {
+                    processed += wave.len();
+                    self.execute_wave(wave).await;
+                }
+            }
+            return processed;
         }
 
-        Ok(effects)
+        if let Some(scheduler) = self.scheduler {
+            loop {
+                let window = scheduler.next_window(&self.mempool);
+                if window.is_empty() {
+                    break;
+                }
+                for pending in window {
+                    let outcome = self.handle_transaction(pending.request.clone()).await;
+                    pending.respond(outcome);
+                    processed += 1;
+                }
+            }
+            return processed;
+        }
+
+        while let Some(pending) = self.mempool.take_next() {
+            let outcome = self.handle_transaction(pending.request.clone()).await;
+            pending.respond(outcome);
+            processed += 1;
+        }
+        processed
+    }
+
+    /// Executes the transactions of one wave concurrently and waits for all
+    /// of them.
+    async fn execute_wave(&self, wave: Vec<PendingTransaction>) {
+        let mut tasks = tokio::task::JoinSet::new();
+        for pending in wave {
+            let node = self.clone();
+            tasks.spawn(async move {
+                let outcome = node.handle_transaction(pending.request.clone()).await;
+                pending.respond(outcome);
+            });
+        }
+        while let Some(joined) = tasks.join_next().await {
+            if let Err(err) = joined {
+                eprintln!("Error executing scheduled transaction: {}", err);
+            }
+        }
+    }
+
+    /// Starts a background task that evicts expired mempool entries.
+    pub fn spawn_mempool_sweeper(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        spawn_sweeper(Arc::clone(&self.mempool), interval)
+    }
+
+    /// The signed receipt issued when `digest` was committed, if it is
+    /// among the receipts still kept.
+    pub async fn receipt(&self, digest: &TransactionDigest) -> Option<TransactionReceipt> {
+        self.receipts.read().await.get(digest).cloned()
+    }
+
+    /// Receipts currently kept.
+    pub async fn receipt_count(&self) -> usize {
+        self.receipts.read().await.len()
+    }
+
+    /// Sequence number of the last committed checkpoint, 0 before any.
+    pub fn checkpoint_sequence(&self) -> u64 {
+        self.sequence.load(Ordering::SeqCst)
     }
 
     pub async fn latest_checkpoint(&self) -> Option<CheckpointSummary> {
@@ -155,6 +1130,38 @@ impl ValidatorNode {
         server.start(handler).await
     }
 
+    /// Stops taking new work and lets in-flight work finish: the network
+    /// server starts answering 503, the mempool is closed, queued transactions
+    /// are executed and running ones awaited, all within `timeout`. Buffered
+    /// effects and checkpoints are then flushed and finally the enclave is
+    /// terminated.
+    ///
+    /// Fails without terminating the enclave if the drain times out.
+    pub async fn shutdown(&self, timeout: Duration) -> Result<()> {
+        self.ready.store(false, Ordering::SeqCst);
+        self.mempool.close();
+
+        let drain = async {
+            self.process_mempool().await;
+            while self.in_flight.load(Ordering::SeqCst) > 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        };
+        tokio::time::timeout(timeout, drain).await.map_err(|_| {
+            anyhow!(
+                "shutdown timed out with {} transactions in flight and {} queued",
+                self.in_flight.load(Ordering::SeqCst),
+                self.mempool.len()
+            )
+        })?;
+        self.sync_effects().await?;
+        self.flush_checkpoints().await?;
+
+        self.nautilus_client
+            .terminate_enclave(&self.nautilus_enclave_id)
+            .await
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -164,8 +1171,9 @@ impl Clone for ValidatorNode {
     fn clone(&self) -> Self {
         Self {
             name: self.name.clone(),
-            precheck: PreCheckPipeline::default(),
+            precheck: self.precheck.clone(),
             lock_manager: Arc::clone(&self.lock_manager),
+            screener: Arc::clone(&self.screener),
             vm: Arc::clone(&self.vm),
             checkpoints: Arc::clone(&self.checkpoints),
             sequence: Arc::clone(&self.sequence),
@@ -174,6 +1182,30 @@ impl Clone for ValidatorNode {
             object_store: Arc::clone(&self.object_store),
             effects_store: Arc::clone(&self.effects_store),
             checkpoint_store: Arc::clone(&self.checkpoint_store),
+            clock: Arc::clone(&self.clock),
+            attestation: Arc::clone(&self.attestation),
+            keypair: Arc::clone(&self.keypair),
+            committee: Arc::clone(&self.committee),
+            receipts: Arc::clone(&self.receipts),
+            mempool: Arc::clone(&self.mempool),
+            ready: Arc::clone(&self.ready),
+            hasher: Arc::clone(&self.hasher),
+            fee_model: self.fee_model.clone(),
+            sequences: self.sequences.clone(),
+            in_flight: Arc::clone(&self.in_flight),
+            admission_limit: self.admission_limit,
+            middleware: self.middleware.clone(),
+            effects_sink: self.effects_sink.clone(),
+            scheduler: self.scheduler,
+            tx_log: Arc::clone(&self.tx_log),
+            supply: Arc::clone(&self.supply),
+            approvals: Arc::clone(&self.approvals),
+            consensus: self.consensus.clone(),
+            consensus_gate: Arc::clone(&self.consensus_gate),
+            gossip: self.gossip.clone(),
+            admission_policy: self.admission_policy.clone(),
+            parallel: self.parallel.clone(),
+            executed: self.executed.clone(),
         }
     }
 }
@@ -186,18 +1218,43 @@ struct ValidatorHandler {
 #[async_trait]
 impl TransactionHandler for ValidatorHandler {
     async fn handle_transaction(&self, request: ExecutionRequest) -> Result<sui_network::SubmitTransactionResponse> {
+        let digest = request.digest.clone();
+        if let Some(gossip) = &self.validator.gossip {
+            if !gossip.mark_seen(&digest) {
+                let receipt = self.validator.receipt(&digest).await;
+                return Ok(sui_network::SubmitTransactionResponse {
+                    accepted: true,
+                    message: "Transaction already received".to_string(),
+                    receipt,
+                });
+            }
+            gossip.forward(&self.validator.committee().await, &request);
+        }
+
         match self.validator.handle_transaction(request).await {
             Ok(_effects) => Ok(sui_network::SubmitTransactionResponse {
                 accepted: true,
                 message: "Transaction processed successfully".to_string(),
+                receipt: self.validator.receipt(&digest).await,
             }),
-            Err(e) => Ok(sui_network::SubmitTransactionResponse {
-                accepted: false,
-                message: format!("Transaction failed: {}", e),
-            }),
+            Err(e) => {
+                // A later retry must execute and propagate it again.
+                if let Some(gossip) = &self.validator.gossip {
+                    gossip.forget(&digest);
+                }
+                Ok(sui_network::SubmitTransactionResponse {
+                    accepted: false,
+                    message: format!("Transaction failed: {}", e),
+                    receipt: None,
+                })
+            }
         }
     }
 
+    async fn query_transactions(&self, filter: &TxLogFilter) -> Result<Vec<TxLogEntry>> {
+        Ok(self.validator.tx_log.query(filter))
+    }
+
     async fn get_object(&self, object_id: &str) -> Result<Option<serde_json::Value>> {
         match self.validator.object_store.get_object(object_id).await {
             Ok(Some(obj)) => Ok(Some(serde_json::to_value(obj)?)),
@@ -205,6 +1262,62 @@ impl TransactionHandler for ValidatorHandler {
             Err(e) => Err(anyhow!("Failed to get object: {}", e)),
         }
     }
+
+    async fn get_committee(&self) -> Result<Committee> {
+        Ok(self.validator.committee().await)
+    }
+
+    async fn get_effects(&self, digest: &TransactionDigest) -> Result<Option<serde_json::Value>> {
+        match self.validator.effects_store.get_effects_typed(digest).await? {
+            Some(effects) => Ok(Some(serde_json::to_value(effects)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn handle_atomic_batch(
+        &self,
+        requests: Vec<ExecutionRequest>,
+    ) -> Result<sui_network::SubmitAtomicBatchResponse> {
+        let count = requests.len();
+        let effects = match self.validator.handle_atomic_batch(requests).await {
+            Ok(effects) => effects,
+            Err(err) => {
+                return Ok(sui_network::SubmitAtomicBatchResponse {
+                    committed: false,
+                    message: format!("Batch rejected: {}", err),
+                    receipts: Vec::new(),
+                })
+            }
+        };
+        let mut receipts = Vec::with_capacity(effects.len());
+        for effects in &effects {
+            receipts.extend(self.validator.receipt(&effects.digest).await);
+        }
+        Ok(sui_network::SubmitAtomicBatchResponse {
+            committed: true,
+            message: format!("Committed {} transactions", count),
+            receipts,
+        })
+    }
+
+    async fn dry_run(&self, request: ExecutionRequest, trace: bool) -> Result<sui_network::DryRunResponse> {
+        let result = self.validator.dry_run(&request, trace).await?;
+        Ok(sui_network::DryRunResponse {
+            status: result.status,
+            gas_used: result.gas_used,
+            logs: result.logs,
+            return_values: result.return_values,
+            trace: result.trace.map(serde_json::to_value).transpose()?,
+        })
+    }
+
+    fn is_ready(&self) -> bool {
+        self.validator.is_ready()
+    }
+
+    fn retry_after_secs(&self) -> Option<u64> {
+        self.validator.is_saturated().then_some(1)
+    }
 }
 
 struct InMemoryObjectStoreWrapper {
@@ -221,6 +1334,10 @@ impl sui_storage::ObjectStore for InMemoryObjectStoreWrapper {
         self.store.put_object(object).await
     }
 
+    async fn create_object(&self, object: SuiObject) -> Result<()> {
+        self.store.create_object(object).await
+    }
+
     async fn delete_object(&self, id: &str) -> Result<()> {
         self.store.delete_object(id).await
     }
@@ -228,4 +1345,966 @@ impl sui_storage::ObjectStore for InMemoryObjectStoreWrapper {
     async fn list_objects(&self, owner: Option<&str>) -> Result<Vec<SuiObject>> {
         self.store.list_objects(owner).await
     }
+
+    async fn get_objects(&self, ids: &[String]) -> Result<Vec<Option<SuiObject>>> {
+        self.store.get_objects(ids).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{call, coin, node, payload, request, sequenced_transfer, transfer};
+    use sui_precheck::InMemorySequenceStore;
+    use sui_core::object::ObjectID;
+    use sui_core::transaction::{Argument, InputObject, TransactionKind};
+    use sui_core::clock::MockClock;
+    use crate::testing::serve;
+    use sui_core::messages::{verify_receipt, TxLogStatus};
+
+    #[tokio::test]
+    async fn checkpoints_report_summed_gas_and_object_counts() {
+        let node = node("v1").await;
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        node.object_store.put_object(coin("coin-b", "alice", 10)).await.unwrap();
+        let first = transfer("alice", "coin-a", "bob");
+        let second = transfer("alice", "coin-b", "bob");
+        let expected_gas = node.dry_run(&first, false).await.unwrap().gas_used
+            + node.dry_run(&second, false).await.unwrap().gas_used;
+
+        node.handle_transaction(first).await.unwrap();
+        node.handle_transaction(second).await.unwrap();
+
+        let checkpoints = node.checkpoints.lock().await;
+        assert_eq!(checkpoints.total_transactions(), 2);
+        assert_eq!(checkpoints.total_gas_used(), expected_gas);
+        let latest = checkpoints.latest().unwrap();
+        assert_eq!(latest.objects_mutated, 1);
+        assert_eq!(latest.objects_created, 0);
+    }
+
+    struct FailingAfter;
+
+    #[async_trait]
+    impl TxMiddleware for FailingAfter {
+        async fn after(&self, _request: &ExecutionRequest, _effects: &sui_effects::TransactionEffects) -> Result<()> {
+            Err(anyhow!("audit log unavailable"))
+        }
+    }
+
+    #[tokio::test]
+    async fn failing_after_middleware_does_not_fail_a_committed_transaction() {
+        let mut node = node("v1").await;
+        node.add_middleware(Arc::new(FailingAfter));
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        let request = transfer("alice", "coin-a", "bob");
+        let digest = request.digest.clone();
+
+        let effects = node.handle_transaction(request).await.unwrap();
+
+        assert!(effects.status.is_success());
+        assert!(node.receipt(&digest).await.is_some());
+    }
+
+    /// Holds one transaction back after its inputs were prefetched.
+    struct DelayBefore(TransactionDigest);
+
+    #[async_trait]
+    impl TxMiddleware for DelayBefore {
+        async fn before(&self, request: &ExecutionRequest) -> Result<()> {
+            if request.digest == self.0 {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_transfers_of_one_coin_cannot_both_commit() {
+        let mut node = node("v1").await;
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        let to_bob = transfer("alice", "coin-a", "bob");
+        let to_carol = transfer("alice", "coin-a", "carol");
+        node.add_middleware(Arc::new(DelayBefore(to_bob.digest.clone())));
+
+        let (bob, carol) = tokio::join!(node.handle_transaction(to_bob), node.handle_transaction(to_carol));
+
+        let winner = match (bob, carol) {
+            (Ok(_), Err(_)) => "bob",
+            (Err(_), Ok(_)) => "carol",
+            (bob, carol) => panic!("expected exactly one transfer to commit: {:?} / {:?}", bob.is_ok(), carol.is_ok()),
+        };
+        let coin = node.object_store.get_object("coin-a").await.unwrap().unwrap();
+        assert_eq!(coin.owner, Owner::Address(winner.to_string()));
+        assert_eq!(coin.version, 2);
+    }
+
+    #[tokio::test]
+    async fn enclave_pool_can_be_configured_on_a_shared_node() {
+        let node = node("v1").await;
+        let shared = node.clone();
+
+        let pooled = node.with_enclave_pool(2);
+
+        assert_eq!(pooled.enclave_pool_stats().map(|stats| stats.size), Some(2));
+        assert!(shared.enclave_pool_stats().is_none());
+    }
+
+    #[tokio::test]
+    async fn transaction_rejected_after_pre_checks_keeps_its_sequence_number() {
+        let sequences = Arc::new(InMemorySequenceStore::new());
+        let node = node("v1").await.with_sequence_store(sequences.clone());
+        let held = coin("coin-a", "alice", 10);
+        node.object_store.put_object(held.clone()).await.unwrap();
+        assert!(node.lock_manager.acquire(&held, LockMode::Exclusive));
+
+        let locked_out = node.handle_transaction(sequenced_transfer("alice", "coin-a", "bob", 1)).await;
+        assert!(locked_out.is_err());
+        assert_eq!(sequences.last_sequence("alice"), None);
+
+        node.lock_manager.release(&held, LockMode::Exclusive);
+        node.handle_transaction(sequenced_transfer("alice", "coin-a", "bob", 1)).await.unwrap();
+        assert_eq!(sequences.last_sequence("alice"), Some(1));
+    }
+
+    #[tokio::test]
+    async fn dry_run_leaves_the_sequence_number_for_the_real_submission() {
+        let sequences = Arc::new(InMemorySequenceStore::new());
+        let node = node("v1").await.with_sequence_store(sequences.clone());
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        let request = sequenced_transfer("alice", "coin-a", "bob", 1);
+
+        node.dry_run(&request, false).await.unwrap();
+        assert_eq!(sequences.last_sequence("alice"), None);
+
+        node.handle_transaction(request).await.unwrap();
+        assert_eq!(sequences.last_sequence("alice"), Some(1));
+    }
+
+    #[tokio::test]
+    async fn atomic_batch_consumes_sequence_numbers_only_when_it_commits() {
+        let sequences = Arc::new(InMemorySequenceStore::new());
+        let node = node("v1").await.with_sequence_store(sequences.clone());
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        node.object_store.put_object(coin("coin-b", "alice", 10)).await.unwrap();
+        let mut migrate = request(
+            "alice",
+            payload(
+                TransactionKind::Migrate { object: ObjectID::new("coin-b"), new_fields: serde_json::json!({}) },
+                vec![InputObject::mutable(ObjectID::new("coin-b"))],
+            ),
+        );
+        migrate.tx.payload.sequence_number = 2;
+
+        let aborted = node
+            .handle_atomic_batch(vec![sequenced_transfer("alice", "coin-a", "bob", 1), migrate])
+            .await;
+        assert!(aborted.is_err());
+        assert_eq!(sequences.last_sequence("alice"), None);
+
+        let committed = node
+            .handle_atomic_batch(vec![
+                sequenced_transfer("alice", "coin-a", "bob", 1),
+                sequenced_transfer("alice", "coin-b", "bob", 2),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(committed.len(), 2);
+        assert_eq!(sequences.last_sequence("alice"), Some(2));
+    }
+
+    #[tokio::test]
+    async fn minted_coin_is_reported_as_created() {
+        let node = node("v1").await;
+        let batch = TransactionKind::Batch {
+            commands: vec![
+                call("coin", "mint", Vec::new()),
+                TransactionKind::TransferToSender { object: Argument::Result(0) },
+            ],
+        };
+
+        let effects = node.handle_transaction(request("alice", payload(batch, Vec::new()))).await.unwrap();
+
+        assert_eq!(effects.created.len(), 1);
+        assert!(effects.mutated.is_empty());
+        assert!(matches!(&effects.created[0].owner, Owner::Address(owner) if owner == "alice"));
+        let checkpoint = node.latest_checkpoint().await.unwrap();
+        assert_eq!(checkpoint.objects_created, 1);
+        assert_eq!(checkpoint.objects_mutated, 0);
+    }
+
+    fn read_only_call(object: &str) -> ExecutionRequest {
+        let mut payload = payload(
+            call("object", "inspect", vec![Argument::Object(ObjectID::new(object))]),
+            vec![InputObject::read_only(ObjectID::new(object))],
+        );
+        payload.read_only = true;
+        request("alice", payload)
+    }
+
+    #[tokio::test]
+    async fn transfer_locks_its_coin_exclusively() {
+        let node = node("v1").await;
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+
+        let plan = node.lock_plan(&transfer("alice", "coin-a", "bob")).await.unwrap();
+
+        assert_eq!(plan.entries().len(), 1);
+        let (object, mode) = &plan.entries()[0];
+        assert_eq!(object.id, ObjectID::new("coin-a"));
+        assert_eq!(*mode, LockMode::Exclusive);
+    }
+
+    #[tokio::test]
+    async fn read_only_call_takes_a_shared_lock() {
+        let node = node("v1").await;
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+
+        let plan = node.lock_plan(&read_only_call("coin-a")).await.unwrap();
+
+        assert_eq!(plan.entries().len(), 1);
+        assert_eq!(plan.entries()[0].1, LockMode::Shared);
+    }
+
+    #[tokio::test]
+    async fn read_only_calls_on_the_same_object_lock_concurrently() {
+        let node = node("v1").await;
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        let first = node.lock_plan(&read_only_call("coin-a")).await.unwrap();
+        let second = node.lock_plan(&read_only_call("coin-a")).await.unwrap();
+        let writer = node.lock_plan(&transfer("alice", "coin-a", "bob")).await.unwrap();
+
+        assert!(node.lock_manager.acquire_plan(&first));
+        assert!(node.lock_manager.acquire_plan(&second));
+        assert!(!node.lock_manager.acquire_plan(&writer));
+        node.lock_manager.release_plan(&first);
+        node.lock_manager.release_plan(&second);
+        assert!(node.lock_manager.acquire_plan(&writer));
+    }
+
+    #[tokio::test]
+    async fn lock_lease_uses_the_injected_clock_in_either_builder_order() {
+        for clock_first in [true, false] {
+            let clock = Arc::new(MockClock::new(1_000));
+            let node = if clock_first {
+                node("v1").await.with_clock(clock.clone()).with_lock_lease(100)
+            } else {
+                node("v1").await.with_lock_lease(100).with_clock(clock.clone())
+            };
+            let object = coin("coin-a", "alice", 10);
+            assert!(node.lock_manager.acquire(&object, LockMode::Exclusive));
+
+            clock.advance(99);
+            assert!(node.reclaim_expired_locks().is_empty());
+            clock.advance(1);
+            assert_eq!(node.reclaim_expired_locks().len(), 1);
+            assert!(node.lock_manager.acquire(&object, LockMode::Exclusive));
+        }
+    }
+
+    #[tokio::test]
+    async fn effects_carry_the_call_return_values() {
+        let node = node("v1").await;
+        let kind = call("coin", "transfer", vec![Argument::Value(json!(7)), Argument::Value(json!(true))]);
+
+        let effects = node.handle_transaction(request("alice", payload(kind, Vec::new()))).await.unwrap();
+
+        assert_eq!(effects.return_values, vec![json!(7), json!(true)]);
+    }
+
+    #[tokio::test]
+    async fn committee_route_lists_every_member() {
+        let nodes = [node("v1").await, node("v2").await, node("v3").await];
+        let members: Vec<_> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| node.committee_member(format!("http://validator-{}", i)))
+            .collect();
+        nodes[0].set_committee(Committee::new(3, members.clone())).await;
+        let client = serve(&nodes[0]).await;
+
+        let served = client.get_committee().await.unwrap();
+
+        assert_eq!(served.epoch, 3);
+        assert_eq!(nodes[0].committee().await.epoch, 3);
+        assert_eq!(served.members.len(), 3);
+        for (served, expected) in served.members.iter().zip(&members) {
+            assert_eq!(served.name, expected.name);
+            assert_eq!(served.public_key, expected.public_key);
+            assert_eq!(served.endpoint, expected.endpoint);
+        }
+    }
+
+    #[tokio::test]
+    async fn issued_receipt_verifies_against_the_validator_key() {
+        let node = node("v1").await;
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        let request = transfer("alice", "coin-a", "bob");
+        let digest = request.digest.clone();
+        node.handle_transaction(request).await.unwrap();
+
+        let mut receipt = node.receipt(&digest).await.unwrap();
+        assert!(verify_receipt(&receipt, &node.public_key()));
+        receipt.checkpoint_sequence += 1;
+        assert!(!verify_receipt(&receipt, &node.public_key()));
+    }
+
+    #[tokio::test]
+    async fn submissions_are_refused_until_warm_up_completes() {
+        let node = node("v1").await;
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        let client = serve(&node).await;
+
+        assert!(!client.is_ready().await.unwrap());
+        assert!(client.submit_transaction(transfer("alice", "coin-a", "bob")).await.is_err());
+
+        node.warm_up().await.unwrap();
+
+        assert!(client.is_ready().await.unwrap());
+        let response = client.submit_transaction(transfer("alice", "coin-a", "bob")).await.unwrap();
+        assert!(response.accepted, "{}", response.message);
+    }
+
+    #[tokio::test]
+    async fn saturated_node_answers_503_until_its_queue_drains() {
+        let node = node("v1").await.with_admission_limit(2);
+        node.mark_ready();
+        for id in ["coin-a", "coin-b", "coin-c"] {
+            node.object_store.put_object(coin(id, "alice", 10)).await.unwrap();
+        }
+        let queued = [
+            node.enqueue_transaction(transfer("alice", "coin-a", "bob")),
+            node.enqueue_transaction(transfer("alice", "coin-b", "bob")),
+        ];
+        let client = serve(&node).await;
+
+        assert_eq!(node.queue_saturation(), 1.0);
+        let refused = client.submit_transaction(transfer("alice", "coin-c", "bob")).await.unwrap_err();
+        assert!(refused.to_string().contains("503"), "{}", refused);
+
+        node.process_mempool().await;
+        for receiver in queued {
+            receiver.await.unwrap().unwrap();
+        }
+
+        assert_eq!(node.queue_saturation(), 0.0);
+        let response = client.submit_transaction(transfer("alice", "coin-c", "bob")).await.unwrap();
+        assert!(response.accepted, "{}", response.message);
+    }
+
+    #[tokio::test]
+    async fn client_retries_after_the_server_asks_it_to_wait() {
+        let node = node("v1").await.with_admission_limit(1);
+        node.mark_ready();
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        node.object_store.put_object(coin("coin-b", "alice", 10)).await.unwrap();
+        let queued = node.enqueue_transaction(transfer("alice", "coin-a", "bob"));
+        let client = serve(&node).await;
+        let drainer = node.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            drainer.process_mempool().await;
+        });
+
+        let response = client
+            .submit_transaction_with_retry(transfer("alice", "coin-b", "bob"), 3)
+            .await
+            .unwrap();
+
+        assert!(response.accepted, "{}", response.message);
+        assert!(queued.await.unwrap().unwrap().status.is_success());
+    }
+
+    #[tokio::test]
+    async fn warm_up_recovers_the_checkpoint_sequence() {
+        let checkpoints = Arc::new(sui_storage::InMemoryCheckpointStore::new());
+        checkpoints.save_checkpoint_typed(&CheckpointSummary::new(4, "root")).await.unwrap();
+        let node = ValidatorNode::new(
+            "v1",
+            Arc::new(sui_storage::InMemoryObjectStore::new()),
+            Arc::new(sui_storage::InMemoryEffectsStore::new()),
+            checkpoints,
+        )
+        .await
+        .unwrap();
+
+        node.warm_up().await.unwrap();
+
+        assert!(node.is_ready());
+        assert_eq!(node.checkpoint_sequence(), 4);
+        assert_eq!(node.latest_checkpoint().await.unwrap().sequence_number, 4);
+    }
+
+    #[tokio::test]
+    async fn configured_hasher_computes_the_checkpoint_root() {
+        let node = node("v1").await.with_hasher(Arc::new(sui_core::hash::Blake3));
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+
+        let effects = node.handle_transaction(transfer("alice", "coin-a", "bob")).await.unwrap();
+
+        let checkpoint = node.latest_checkpoint().await.unwrap();
+        assert_eq!(checkpoint.objects_root, objects_root_with(&sui_core::hash::Blake3, &effects.mutated));
+        assert_ne!(checkpoint.objects_root, sui_checkpoint::objects_root(&effects.mutated));
+    }
+
+    #[tokio::test]
+    async fn configured_hasher_chains_checkpoint_digests() {
+        let node = node("v1").await.with_hasher(Arc::new(sui_core::hash::Blake3));
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        node.object_store.put_object(coin("coin-b", "alice", 10)).await.unwrap();
+
+        node.handle_transaction(transfer("alice", "coin-a", "bob")).await.unwrap();
+        let first = node.latest_checkpoint().await.unwrap();
+        node.handle_transaction(transfer("alice", "coin-b", "bob")).await.unwrap();
+        let second = node.latest_checkpoint().await.unwrap();
+
+        assert_eq!(second.previous_digest, Some(first.digest_with(&sui_core::hash::Blake3)));
+        assert_ne!(second.previous_digest, Some(first.digest()));
+        assert!(node.checkpoints.lock().await.verify_chain());
+    }
+
+    #[tokio::test]
+    async fn shutdown_drains_queued_transactions_before_stopping() {
+        let node = node("v1").await;
+        let mut queued = Vec::new();
+        for id in ["coin-a", "coin-b", "coin-c"] {
+            node.object_store.put_object(coin(id, "alice", 10)).await.unwrap();
+            let request = transfer("alice", id, "bob");
+            queued.push((request.digest.clone(), node.enqueue_transaction(request)));
+        }
+
+        node.shutdown(Duration::from_secs(5)).await.unwrap();
+
+        for (digest, receiver) in queued {
+            assert!(receiver.await.unwrap().unwrap().status.is_success());
+            assert!(node.effects_store.get_effects(&digest).await.unwrap().is_some());
+        }
+        assert_eq!(node.latest_checkpoint().await.unwrap().transaction_count, 1);
+        assert_eq!(node.checkpoint_store.get_latest_sequence().await.unwrap(), Some(3));
+        let late = node.enqueue_transaction(transfer("alice", "coin-a", "carol")).await.unwrap();
+        assert!(late.is_err());
+    }
+
+    #[tokio::test]
+    async fn concurrent_transactions_get_unique_contiguous_sequence_numbers() {
+        let node = node("v1").await;
+        let mut handles = Vec::new();
+        for index in 0..16 {
+            let id = format!("coin-{}", index);
+            node.object_store.put_object(coin(&id, "alice", 10)).await.unwrap();
+            let node = node.clone();
+            handles.push(tokio::spawn(async move { node.handle_transaction(transfer("alice", &id, "bob")).await }));
+        }
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+        node.flush_checkpoints().await.unwrap();
+
+        assert_eq!(node.checkpoint_sequence(), 16);
+        let mut previous = None;
+        for sequence in 1..=16 {
+            let checkpoint = node.checkpoint_store.get_checkpoint_typed(sequence).await.unwrap().unwrap();
+            assert_eq!(checkpoint.sequence_number, sequence);
+            assert_eq!(checkpoint.previous_digest, previous);
+            previous = Some(checkpoint.digest());
+        }
+    }
+
+    #[tokio::test]
+    async fn effects_batch_keeps_request_order_and_flags_missing_digests() {
+        let node = node("v1").await;
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        node.object_store.put_object(coin("coin-b", "alice", 10)).await.unwrap();
+        let first = transfer("alice", "coin-a", "bob");
+        let second = transfer("alice", "coin-b", "bob");
+        let (first_digest, second_digest) = (first.digest.clone(), second.digest.clone());
+        node.handle_transaction(first).await.unwrap();
+        node.handle_transaction(second).await.unwrap();
+        let client = serve(&node).await;
+        let missing = TransactionDigest::random();
+
+        let results = client
+            .get_effects_batch(vec![second_digest.clone(), missing.clone(), first_digest.clone()])
+            .await
+            .unwrap();
+
+        let digests: Vec<_> = results.iter().map(|lookup| lookup.digest.clone()).collect();
+        assert_eq!(digests, vec![second_digest.clone(), missing, first_digest]);
+        let found: Vec<_> = results.iter().map(|lookup| lookup.found).collect();
+        assert_eq!(found, vec![true, false, true]);
+        assert!(results[1].effects.is_none());
+        assert_eq!(results[0].effects.as_ref().unwrap()["digest"], json!(second_digest.0));
+    }
+
+    #[tokio::test]
+    async fn oversized_effects_batch_is_refused() {
+        let node = node("v1").await;
+        let client = serve(&node).await;
+        let digests = (0..=sui_network::MAX_EFFECTS_BATCH_SIZE).map(|_| TransactionDigest::random()).collect();
+
+        assert!(client.get_effects_batch(digests).await.is_err());
+    }
+
+    #[cfg(feature = "fault-injection")]
+    async fn faulty_node(stage: sui_vm::FaultStage, target: &ExecutionRequest) -> ValidatorNode {
+        let injector = Arc::new(sui_vm::FaultInjector::new());
+        injector.inject(
+            sui_vm::FaultTarget::Digest(target.digest.clone()),
+            stage,
+            sui_vm::VmAbort::new(99, "injected"),
+        );
+        let node = node("v1").await.with_vm_config(VmConfig { fault_injector: Some(injector), ..Default::default() });
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        node.object_store.put_object(coin("coin-b", "alice", 10)).await.unwrap();
+        node
+    }
+
+    #[cfg(feature = "fault-injection")]
+    async fn owner_of(node: &ValidatorNode, id: &str) -> Owner {
+        node.object_store.get_object(id).await.unwrap().unwrap().owner
+    }
+
+    #[cfg(feature = "fault-injection")]
+    #[tokio::test]
+    async fn injected_execution_abort_fails_only_the_targeted_transaction() {
+        let failing = transfer("alice", "coin-a", "bob");
+        let node = faulty_node(sui_vm::FaultStage::Execution, &failing).await;
+
+        let failed = node.handle_transaction(failing).await.unwrap();
+        let succeeded = node.handle_transaction(transfer("alice", "coin-b", "bob")).await.unwrap();
+
+        assert_eq!(failed.status, ExecutionStatus::Failure { code: 99, message: "injected".to_string() });
+        assert!(failed.created.is_empty() && failed.mutated.is_empty() && failed.deleted.is_empty());
+        assert!(succeeded.status.is_success());
+        assert_eq!(owner_of(&node, "coin-a").await, Owner::Address("alice".to_string()));
+        assert_eq!(owner_of(&node, "coin-b").await, Owner::Address("bob".to_string()));
+    }
+
+    #[cfg(feature = "fault-injection")]
+    #[tokio::test]
+    async fn injected_precheck_and_commit_faults_persist_nothing() {
+        for stage in [sui_vm::FaultStage::PreCheck, sui_vm::FaultStage::Commit] {
+            let failing = transfer("alice", "coin-a", "bob");
+            let digest = failing.digest.clone();
+            let node = faulty_node(stage, &failing).await;
+
+            let err = node.handle_transaction(failing).await.unwrap_err();
+
+            assert!(err.to_string().contains("injected fault"), "{stage:?}: {err}");
+            assert!(node.effects_store.get_effects(&digest).await.unwrap().is_none());
+            assert_eq!(owner_of(&node, "coin-a").await, Owner::Address("alice".to_string()));
+            assert!(node.handle_transaction(transfer("alice", "coin-b", "bob")).await.unwrap().status.is_success());
+        }
+    }
+
+    #[tokio::test]
+    async fn transaction_log_is_queryable_by_signer_status_and_time() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let node = node("v1").await.with_clock(clock.clone());
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        node.object_store.put_object(coin("coin-b", "bob", 10)).await.unwrap();
+        let burn_bobs_coin = payload(call("coin", "burn", vec![Argument::Object(ObjectID::new("coin-b"))]), Vec::new());
+
+        node.handle_transaction(transfer("alice", "coin-a", "carol")).await.unwrap();
+        clock.advance(1_000);
+        node.handle_transaction(transfer("bob", "coin-b", "carol")).await.unwrap();
+        clock.advance(1_000);
+        let failed = node.handle_transaction(request("alice", burn_bobs_coin)).await.unwrap();
+        assert!(!failed.status.is_success());
+
+        let log = node.transaction_log();
+        assert_eq!(log.len(), 3);
+        let alice = log.query(&TxLogFilter { signer: Some("alice".to_string()), ..Default::default() });
+        assert_eq!(alice.len(), 2);
+        assert!(alice.iter().all(|entry| entry.signer == "alice"));
+        let succeeded = TxLogFilter {
+            signer: Some("alice".to_string()),
+            status: Some(TxLogStatus::Success),
+            ..Default::default()
+        };
+        let succeeded = log.query(&succeeded);
+        assert_eq!(succeeded.len(), 1);
+        assert_eq!(succeeded[0].timestamp_ms, 1_000);
+        let later = log.query(&TxLogFilter { from_ms: Some(2_000), to_ms: Some(2_000), ..Default::default() });
+        assert_eq!(later.iter().map(|entry| entry.signer.as_str()).collect::<Vec<_>>(), vec!["bob"]);
+
+        let client = serve(&node).await;
+        let filter = TxLogFilter {
+            signer: Some("alice".to_string()),
+            status: Some(TxLogStatus::Failure),
+            ..Default::default()
+        };
+        let served = client.query_transactions(&filter).await.unwrap();
+        assert_eq!(served.len(), 1);
+        assert_eq!(served[0].digest, failed.digest);
+    }
+
+    #[tokio::test]
+    async fn validators_report_identical_input_versions() {
+        let mut source = coin("coin-a", "alice", 10);
+        source.version = 3;
+        let transaction = transfer("alice", "coin-a", "bob");
+        let mut reported = Vec::new();
+
+        for name in ["v1", "v2"] {
+            let node = node(name).await;
+            node.object_store.put_object(source.clone()).await.unwrap();
+            let effects = node.handle_transaction(transaction.clone()).await.unwrap();
+            let stored = node.effects_store.get_effects_typed(&transaction.digest).await.unwrap().unwrap();
+            assert_eq!(stored.input_versions, effects.input_versions);
+            reported.push(effects.input_versions);
+        }
+
+        assert_eq!(reported[0], vec![(ObjectID::new("coin-a"), 3)]);
+        assert_eq!(reported[0], reported[1]);
+    }
+
+    #[tokio::test]
+    async fn burning_a_coin_removes_it_and_reduces_the_supply() {
+        let node = node("v1").await;
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        node.object_store.put_object(coin("coin-b", "alice", 25)).await.unwrap();
+        node.coin_supply().set_supply(NATIVE_COIN_TYPE, 35);
+        let burn = payload(call("coin", "burn", vec![Argument::Object(ObjectID::new("coin-b"))]), Vec::new());
+
+        let effects = node.handle_transaction(request("alice", burn)).await.unwrap();
+
+        assert!(effects.status.is_success());
+        assert_eq!(effects.deleted, vec![ObjectID::new("coin-b")]);
+        assert!(node.object_store.get_object("coin-b").await.unwrap().is_none());
+        assert_eq!(node.coin_supply().supply(NATIVE_COIN_TYPE), 10);
+
+        let burn = payload(call("coin", "burn", vec![Argument::Object(ObjectID::new("coin-a"))]), Vec::new());
+        let rejected = node.handle_transaction(request("bob", burn)).await.unwrap();
+        assert!(!rejected.status.is_success());
+        assert!(node.object_store.get_object("coin-a").await.unwrap().is_some());
+        assert_eq!(node.coin_supply().supply(NATIVE_COIN_TYPE), 10);
+    }
+
+    fn approval(function: &str, object: &str, operator: &str) -> sui_core::transaction::TransactionPayload {
+        let arguments = vec![Argument::Object(ObjectID::new(object)), Argument::Value(json!(operator))];
+        payload(call("object", function, arguments), Vec::new())
+    }
+
+    #[tokio::test]
+    async fn approved_operators_can_transfer_until_revoked() {
+        let node = node("v1").await;
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        node.object_store.put_object(coin("coin-b", "alice", 10)).await.unwrap();
+        let owner_of = |id: &'static str| {
+            let node = node.clone();
+            async move { node.object_store.get_object(id).await.unwrap().unwrap().owner }
+        };
+
+        let approved = node.handle_transaction(request("alice", approval("approve", "coin-a", "bob"))).await.unwrap();
+        assert!(approved.status.is_success());
+        let unapproved = node.handle_transaction(transfer("carol", "coin-a", "carol")).await.unwrap();
+        assert!(!unapproved.status.is_success());
+        let moved = node.handle_transaction(transfer("bob", "coin-a", "dave")).await.unwrap();
+        assert!(moved.status.is_success());
+        assert_eq!(owner_of("coin-a").await, Owner::Address("dave".to_string()));
+
+        node.handle_transaction(request("alice", approval("approve", "coin-b", "bob"))).await.unwrap();
+        node.handle_transaction(request("alice", approval("revoke", "coin-b", "bob"))).await.unwrap();
+        let revoked = node.handle_transaction(transfer("bob", "coin-b", "bob")).await.unwrap();
+        assert!(!revoked.status.is_success());
+        assert_eq!(owner_of("coin-b").await, Owner::Address("alice".to_string()));
+    }
+
+    #[tokio::test]
+    async fn validator_executes_in_simulated_consensus_order() {
+        let node = node("v1").await.with_consensus(Arc::new(SimulatedConsensus::new(Duration::from_secs(60), 3)));
+        let mut requests = Vec::new();
+        for (coin_id, digest) in [("coin-a", "tx-c"), ("coin-b", "tx-a"), ("coin-c", "tx-b")] {
+            node.object_store.put_object(coin(coin_id, "alice", 10)).await.unwrap();
+            let mut request = transfer("alice", coin_id, "bob");
+            request.digest = sui_core::transaction::TransactionDigest(digest.to_string());
+            requests.push(request);
+        }
+
+        let handles: Vec<_> = requests
+            .into_iter()
+            .map(|request| {
+                let node = node.clone();
+                tokio::spawn(async move { node.handle_transaction(request).await })
+            })
+            .collect();
+        for handle in handles {
+            assert!(handle.await.unwrap().unwrap().status.is_success());
+        }
+
+        let log = node.transaction_log().query(&TxLogFilter::default());
+        let executed: Vec<(&str, u64)> = log.iter().map(|entry| (entry.digest.0.as_str(), entry.sequence)).collect();
+        assert_eq!(executed, vec![("tx-a", 1), ("tx-b", 2), ("tx-c", 3)]);
+        for sequence in 1..=3 {
+            let checkpoint = node.checkpoint_store.get_checkpoint_typed(sequence).await.unwrap().unwrap();
+            assert_eq!(checkpoint.consensus_position, Some(sequence));
+        }
+    }
+
+    #[tokio::test]
+    async fn effects_record_the_refund_and_the_gas_coins_final_balance() {
+        let node = node("v1").await;
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        node.object_store.put_object(coin("gas", "alice", 10_000)).await.unwrap();
+        let mut paid = transfer("alice", "coin-a", "bob");
+        paid.tx.payload.gas_payment = Some(ObjectID::new("gas"));
+        (paid.tx.payload.gas_budget, paid.tx.payload.gas_price) = (1_000, 2);
+
+        let used = node.dry_run(&paid, false).await.unwrap().gas_used;
+
+        let effects = node.handle_transaction(paid).await.unwrap();
+
+        assert!(effects.status.is_success());
+        assert_eq!(effects.gas_refund, (1_000 - used) * 2);
+        assert_eq!(effects.gas_coin, Some((ObjectID::new("gas"), 10_000 - used * 2)));
+        let stored = node.object_store.get_object("gas").await.unwrap().unwrap();
+        assert!(matches!(stored.data, ObjectData::Coin { balance } if balance == 10_000 - used * 2));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn checkpoints_stay_gap_free_under_concurrent_load() {
+        let node = node("v1").await;
+        let mut handles = Vec::new();
+        for index in 0..64 {
+            let id = format!("coin-{}", index);
+            node.object_store.put_object(coin(&id, "alice", 10)).await.unwrap();
+            let node = node.clone();
+            handles.push(tokio::spawn(async move { node.handle_transaction(transfer("alice", &id, "bob")).await }));
+        }
+        for handle in handles {
+            assert!(handle.await.unwrap().unwrap().status.is_success());
+        }
+
+        assert_eq!(node.checkpoint_sequence(), 64);
+        assert_eq!(node.checkpoint_store.get_latest_sequence().await.unwrap(), Some(64));
+        for sequence in 1..=64 {
+            let checkpoint = node.checkpoint_store.get_checkpoint_typed(sequence).await.unwrap().unwrap();
+            assert_eq!((checkpoint.sequence_number, checkpoint.transaction_count), (sequence, 1));
+        }
+        let checkpoints = node.checkpoints.lock().await;
+        assert_eq!(checkpoints.total_transactions(), 64);
+        assert!(checkpoints.verify_chain());
+    }
+
+    /// Refuses transfers of coins holding more than 1000.
+    fn cautious(request: &ExecutionRequest, inputs: &[SuiObject]) -> Result<(), String> {
+        let TransactionKind::Transfer { object, .. } = &request.tx.payload.kind else {
+            return Ok(());
+        };
+        match inputs.iter().find(|input| &input.id == object).map(|input| &input.data) {
+            Some(ObjectData::Coin { balance }) if *balance > 1_000 => {
+                Err(format!("transfer of {} is too large", balance))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    #[tokio::test]
+    async fn admission_policies_differ_between_validators() {
+        let strict = node("v1").await.with_admission_policy(Arc::new(cautious));
+        let lenient = node("v2").await;
+        for node in [&strict, &lenient] {
+            node.object_store.put_object(coin("whale", "alice", 5_000)).await.unwrap();
+            node.object_store.put_object(coin("minnow", "alice", 10)).await.unwrap();
+        }
+
+        let rejected = strict.handle_transaction(transfer("alice", "whale", "bob")).await;
+        let accepted = lenient.handle_transaction(transfer("alice", "whale", "bob")).await.unwrap();
+
+        assert!(rejected.unwrap_err().to_string().contains("rejected by admission policy"));
+        assert!(accepted.status.is_success());
+        let whale = strict.object_store.get_object("whale").await.unwrap().unwrap();
+        assert_eq!(whale.owner, Owner::Address("alice".to_string()));
+        assert!(strict.handle_transaction(transfer("alice", "minnow", "bob")).await.unwrap().status.is_success());
+    }
+
+    /// Object store that records every read it serves.
+    #[derive(Default)]
+    struct CountingStore {
+        inner: sui_storage::InMemoryObjectStore,
+        single_reads: std::sync::Mutex<Vec<String>>,
+        batched_reads: std::sync::Mutex<Vec<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl ObjectStore for CountingStore {
+        async fn get_object(&self, id: &str) -> Result<Option<SuiObject>> {
+            self.single_reads.lock().unwrap().push(id.to_string());
+            self.inner.get_object(id).await
+        }
+
+        async fn get_objects(&self, ids: &[String]) -> Result<Vec<Option<SuiObject>>> {
+            self.batched_reads.lock().unwrap().push(ids.to_vec());
+            self.inner.get_objects(ids).await
+        }
+
+        async fn put_object(&self, object: SuiObject) -> Result<()> {
+            self.inner.put_object(object).await
+        }
+
+        async fn create_object(&self, object: SuiObject) -> Result<()> {
+            self.inner.create_object(object).await
+        }
+
+        async fn delete_object(&self, id: &str) -> Result<()> {
+            self.inner.delete_object(id).await
+        }
+
+        async fn list_objects(&self, owner: Option<&str>) -> Result<Vec<SuiObject>> {
+            self.inner.list_objects(owner).await
+        }
+    }
+
+    #[tokio::test]
+    async fn declared_inputs_are_read_in_one_batch_instead_of_one_by_one() {
+        let store = Arc::new(CountingStore::default());
+        let node = ValidatorNode::new(
+            "v1",
+            store.clone(),
+            Arc::new(sui_storage::InMemoryEffectsStore::new()),
+            Arc::new(sui_storage::InMemoryCheckpointStore::new()),
+        )
+        .await
+        .unwrap();
+        let ids = ["coin-a", "coin-b", "coin-c"];
+        let mut commands = Vec::new();
+        let mut inputs = Vec::new();
+        for id in ids {
+            store.put_object(coin(id, "alice", 10)).await.unwrap();
+            commands.push(TransactionKind::Transfer { object: ObjectID::new(id), recipient: "bob".to_string() });
+            inputs.push(InputObject { id: ObjectID::new(id), mutable: true, version: None });
+        }
+        let batch = request("alice", payload(TransactionKind::Batch { commands }, inputs));
+
+        let effects = node.handle_transaction(batch).await.unwrap();
+
+        assert!(effects.status.is_success());
+        assert!(store.single_reads.lock().unwrap().is_empty());
+        let batched = store.batched_reads.lock().unwrap();
+        assert_eq!(batched[0], ids.map(str::to_string).to_vec());
+    }
+
+    /// Holds every transaction for a moment, recording how many overlap.
+    #[derive(Default)]
+    struct OverlapProbe {
+        running: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl TxMiddleware for OverlapProbe {
+        async fn before(&self, _request: &ExecutionRequest) -> Result<()> {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn parallel_execution_overlaps_disjoint_transactions_and_orders_conflicts() {
+        let mut node = node("v1").await.with_parallel_execution();
+        let probe = Arc::new(OverlapProbe::default());
+        node.add_middleware(probe.clone());
+        for (id, owner) in [("coin-a", "alice"), ("coin-b", "bob"), ("coin-c", "carol")] {
+            node.object_store.put_object(coin(id, owner, 10)).await.unwrap();
+        }
+        let receivers = [
+            node.enqueue_transaction(transfer("alice", "coin-a", "dave")),
+            node.enqueue_transaction(transfer("bob", "coin-b", "erin")),
+            node.enqueue_transaction(transfer("carol", "coin-c", "frank")),
+            node.enqueue_transaction(transfer("dave", "coin-a", "grace")),
+        ];
+
+        assert_eq!(node.process_mempool().await, 4);
+
+        for receiver in receivers {
+            assert!(receiver.await.unwrap().unwrap().status.is_success());
+        }
+        assert_eq!(probe.peak.load(Ordering::SeqCst), 3);
+        let coin_a = node.object_store.get_object("coin-a").await.unwrap().unwrap();
+        assert_eq!((coin_a.owner, coin_a.version), (Owner::Address("grace".to_string()), 3));
+        let stats = node.scheduling_stats().unwrap();
+        assert_eq!((stats.transactions, stats.waves, stats.max_parallelism), (4, 2, 3));
+    }
+
+    #[tokio::test]
+    async fn clock_skew_tolerance_survives_later_configuration() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let node = node("v1")
+            .await
+            .with_clock_skew_tolerance(200)
+            .with_clock(clock.clone())
+            .with_mempool_max_age(500)
+            .with_admission_limit(10)
+            .with_attestation_ttl(1_000);
+        let _late = node.mempool().submit_with_deadline(transfer("alice", "coin-a", "bob"), 1_000);
+
+        clock.set(1_200);
+
+        assert_eq!(node.mempool().skew_tolerance_ms(), 200);
+        assert_eq!(node.attestation.skew_tolerance_ms(), 200);
+        assert!(node.attestation.validate_timestamp(0).is_ok());
+        assert!(node.mempool().sweep_expired().is_empty());
+        clock.advance(1);
+        assert_eq!(node.mempool().sweep_expired().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn effects_report_balance_changes_net_of_gas() {
+        let node = node("v1").await;
+        node.object_store.put_object(coin("coin-a", "alice", 1_000)).await.unwrap();
+        node.object_store.put_object(coin("gas", "alice", 10_000)).await.unwrap();
+        let mut paid = transfer("alice", "coin-a", "bob");
+        paid.tx.payload.gas_payment = Some(ObjectID::new("gas"));
+        (paid.tx.payload.gas_budget, paid.tx.payload.gas_price) = (1_000, 2);
+        let fee = node.dry_run(&paid, false).await.unwrap().gas_used as i128 * 2;
+
+        let effects = node.handle_transaction(paid).await.unwrap();
+
+        let changes = effects.balance_changes();
+        assert_eq!(changes.get("alice"), Some(&(-1_000 - fee)));
+        assert_eq!(changes.get("bob"), Some(&1_000));
+        assert_eq!(changes.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn a_transaction_replayed_to_another_validator_is_rejected() {
+        let executed = Arc::new(ExecutedDigests::new());
+        let first = node("v1").await.with_executed_digests(executed.clone());
+        let second = node("v2").await.with_executed_digests(executed.clone());
+        for node in [&first, &second] {
+            node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        }
+        let request = transfer("alice", "coin-a", "bob");
+
+        first.handle_transaction(request.clone()).await.unwrap();
+        let replayed = second.handle_transaction(request.clone()).await;
+
+        assert!(replayed.unwrap_err().to_string().contains("already executed by v1"));
+        let untouched = second.object_store.get_object("coin-a").await.unwrap().unwrap();
+        assert_eq!((untouched.owner, untouched.version), (Owner::Address("alice".to_string()), 1));
+        assert_eq!(executed.executed_by(&request.digest).as_deref(), Some("v1"));
+    }
+
+    #[tokio::test]
+    async fn a_failed_attempt_leaves_the_transaction_to_other_validators() {
+        let executed = Arc::new(ExecutedDigests::new());
+        let first = node("v1").await.with_executed_digests(executed.clone());
+        let second = node("v2").await.with_executed_digests(executed.clone());
+        let held = coin("coin-a", "alice", 10);
+        for node in [&first, &second] {
+            node.object_store.put_object(held.clone()).await.unwrap();
+        }
+        assert!(first.lock_manager.acquire(&held, LockMode::Exclusive));
+        let request = transfer("alice", "coin-a", "bob");
+
+        assert!(first.handle_transaction(request.clone()).await.is_err());
+        assert!(executed.is_empty());
+
+        assert!(second.handle_transaction(request.clone()).await.unwrap().status.is_success());
+        assert_eq!(executed.executed_by(&request.digest).as_deref(), Some("v2"));
+    }
 }
@@ -0,0 +1,301 @@
+use anyhow::Result;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sui_core::{
+    clock::{is_past, Clock},
+    messages::ExecutionRequest,
+    transaction::TransactionDigest,
+};
+use sui_effects::TransactionEffects;
+use thiserror::Error;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// Transactions waiting longer than this are evicted by default.
+pub const DEFAULT_MEMPOOL_MAX_AGE_MS: u64 = 30_000;
+
+#[derive(Debug, Error)]
+pub enum MempoolError {
+    #[error("transaction {0} expired before it was executed")]
+    Expired(String),
+    #[error("mempool is closed; transaction {0} was not accepted")]
+    Closed(String),
+    #[error("mempool is full; transaction {0} was not accepted")]
+    Full(String),
+}
+
+/// Outcome delivered to whoever submitted a transaction to the mempool.
+pub type MempoolReceiver = oneshot::Receiver<Result<TransactionEffects>>;
+
+pub struct PendingTransaction {
+    pub request: ExecutionRequest,
+    pub submitted_at: u64,
+    pub deadline: u64,
+    responder: oneshot::Sender<Result<TransactionEffects>>,
+}
+
+impl PendingTransaction {
+    /// Hands the outcome to the submitter. A submitter that stopped waiting
+    /// is not an error.
+    pub fn respond(self, outcome: Result<TransactionEffects>) {
+        let _ = self.responder.send(outcome);
+    }
+
+    fn expire(self) {
+        let digest = self.request.digest.0.clone();
+        self.respond(Err(MempoolError::Expired(digest).into()));
+    }
+}
+
+/// FIFO of admitted-but-not-yet-executed transactions.
+pub struct Mempool {
+    pending: Mutex<VecDeque<PendingTransaction>>,
+    clock: Arc<dyn Clock>,
+    max_age_ms: u64,
+    /// Grace period past a deadline before a transaction expires.
+    skew_tolerance_ms: u64,
+    capacity: Option<usize>,
+    closed: AtomicBool,
+}
+
+impl Mempool {
+    pub fn new(clock: Arc<dyn Clock>, max_age_ms: u64) -> Self {
+        Self {
+            pending: Mutex::new(VecDeque::new()),
+            clock,
+            max_age_ms,
+            skew_tolerance_ms: 0,
+            capacity: None,
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Bounds the number of queued transactions; unbounded when `None`.
+    pub fn with_capacity(mut self, capacity: Option<usize>) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Keeps transactions alive for up to `tolerance_ms` past their
+    /// deadline, so a submitter whose clock runs behind ours does not see
+    /// its transaction expire early.
+    pub fn with_skew_tolerance(mut self, tolerance_ms: u64) -> Self {
+        self.skew_tolerance_ms = tolerance_ms;
+        self
+    }
+
+    pub fn skew_tolerance_ms(&self) -> u64 {
+        self.skew_tolerance_ms
+    }
+
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.capacity.is_some_and(|capacity| self.len() >= capacity)
+    }
+
+    /// Stops accepting submissions. Already queued transactions stay queued.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    pub fn max_age_ms(&self) -> u64 {
+        self.max_age_ms
+    }
+
+    /// Queues `request`, expiring it if it is still pending after the
+    /// mempool's maximum age.
+    pub fn submit(&self, request: ExecutionRequest) -> MempoolReceiver {
+        let deadline = self.clock.now() + self.max_age_ms;
+        self.submit_with_deadline(request, deadline)
+    }
+
+    /// Queues `request`, expiring it once the clock passes `deadline` plus
+    /// the skew tolerance. A closed or full mempool answers immediately
+    /// with [`MempoolError::Closed`] or [`MempoolError::Full`].
+    pub fn submit_with_deadline(&self, request: ExecutionRequest, deadline: u64) -> MempoolReceiver {
+        let (responder, receiver) = oneshot::channel();
+        let digest = request.digest.0.clone();
+        if self.is_closed() {
+            let _ = responder.send(Err(MempoolError::Closed(digest).into()));
+            return receiver;
+        }
+
+        let mut pending = self.pending.lock().unwrap();
+        if self.capacity.is_some_and(|capacity| pending.len() >= capacity) {
+            drop(pending);
+            let _ = responder.send(Err(MempoolError::Full(digest).into()));
+            return receiver;
+        }
+        pending.push_back(PendingTransaction {
+            request,
+            submitted_at: self.clock.now(),
+            deadline,
+            responder,
+        });
+        receiver
+    }
+
+    /// Pops the oldest live transaction, expiring any stale ones on the way.
+    pub fn take_next(&self) -> Option<PendingTransaction> {
+        let now = self.clock.now();
+        loop {
+            let next = self.pending.lock().unwrap().pop_front()?;
+            if is_past(now, next.deadline, self.skew_tolerance_ms) {
+                next.expire();
+            } else {
+                return Some(next);
+            }
+        }
+    }
+
+    /// Pops up to `max` live transactions, oldest first, expiring stale ones
+    /// on the way.
+    pub fn take_up_to(&self, max: usize) -> Vec<PendingTransaction> {
+        let mut taken = Vec::new();
+        while taken.len() < max {
+            match self.take_next() {
+                Some(next) => taken.push(next),
+                None => break,
+            }
+        }
+        taken
+    }
+
+    /// Evicts every transaction past its deadline, notifying its submitter,
+    /// and returns the evicted digests.
+    pub fn sweep_expired(&self) -> Vec<TransactionDigest> {
+        let now = self.clock.now();
+        let expired: Vec<PendingTransaction> = {
+            let mut pending = self.pending.lock().unwrap();
+            let (expired, live): (Vec<_>, Vec<_>) =
+                pending
+                    .drain(..)
+                    .partition(|tx| is_past(now, tx.deadline, self.skew_tolerance_ms));
+            *pending = live.into();
+            expired
+        };
+
+        expired
+            .into_iter()
+            .map(|tx| {
+                let digest = tx.request.digest.clone();
+                tx.expire();
+                digest
+            })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Runs [`Mempool::sweep_expired`] every `interval` until the task is aborted.
+pub fn spawn_sweeper(mempool: Arc<Mempool>, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            mempool.sweep_expired();
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::transfer;
+    use sui_core::clock::MockClock;
+
+    #[tokio::test]
+    async fn transaction_expires_once_the_clock_passes_its_max_age() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let mempool = Mempool::new(clock.clone(), 500);
+        let receiver = mempool.submit(transfer("alice", "coin-a", "bob"));
+
+        clock.advance(500);
+        assert!(mempool.sweep_expired().is_empty());
+        clock.advance(1);
+        assert_eq!(mempool.sweep_expired().len(), 1);
+        assert!(mempool.is_empty());
+        let outcome = receiver.await.unwrap();
+        assert!(outcome.unwrap_err().to_string().contains("expired"));
+    }
+
+    #[test]
+    fn take_next_skips_expired_transactions() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let mempool = Mempool::new(clock.clone(), 500);
+        let _stale = mempool.submit(transfer("alice", "coin-a", "bob"));
+        clock.advance(400);
+        let fresh = transfer("alice", "coin-b", "bob");
+        let digest = fresh.digest.clone();
+        let _fresh = mempool.submit(fresh);
+
+        clock.advance(200);
+        let next = mempool.take_next().unwrap();
+        assert_eq!(next.request.digest, digest);
+        assert!(mempool.take_next().is_none());
+    }
+
+    #[tokio::test]
+    async fn sweeper_expires_stale_transactions_and_keeps_fresh_ones() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let mempool = Arc::new(Mempool::new(clock.clone(), 500));
+        let stale = mempool.submit(transfer("alice", "coin-a", "bob"));
+        clock.advance(400);
+        let fresh = transfer("alice", "coin-b", "bob");
+        let fresh_digest = fresh.digest.clone();
+        let _fresh = mempool.submit(fresh);
+        clock.advance(200);
+
+        let sweeper = spawn_sweeper(Arc::clone(&mempool), Duration::from_millis(5));
+        let outcome = tokio::time::timeout(Duration::from_secs(5), stale).await.unwrap().unwrap();
+        sweeper.abort();
+
+        let err = outcome.unwrap_err();
+        assert!(matches!(err.downcast_ref::<MempoolError>(), Some(MempoolError::Expired(_))));
+        assert_eq!(mempool.len(), 1);
+        assert_eq!(mempool.take_next().unwrap().request.digest, fresh_digest);
+    }
+
+    #[test]
+    fn deadlines_missed_within_the_skew_tolerance_are_forgiven() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let mempool = Mempool::new(clock.clone(), 500).with_skew_tolerance(100);
+        let late = transfer("alice", "coin-a", "bob");
+        let digest = late.digest.clone();
+        let _late = mempool.submit_with_deadline(late, 1_200);
+
+        clock.set(1_300);
+
+        assert!(mempool.sweep_expired().is_empty());
+        assert_eq!(mempool.take_next().unwrap().request.digest, digest);
+    }
+
+    #[tokio::test]
+    async fn deadlines_missed_beyond_the_skew_tolerance_expire() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let mempool = Mempool::new(clock.clone(), 500).with_skew_tolerance(100);
+        let late = mempool.submit_with_deadline(transfer("alice", "coin-a", "bob"), 1_200);
+
+        clock.set(1_301);
+
+        assert!(mempool.take_next().is_none());
+        let err = late.await.unwrap().unwrap_err();
+        assert!(matches!(err.downcast_ref::<MempoolError>(), Some(MempoolError::Expired(_))));
+    }
+}
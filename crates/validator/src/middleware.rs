@@ -0,0 +1,22 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use sui_core::messages::ExecutionRequest;
+use sui_effects::TransactionEffects;
+
+/// Custom logic run around every transaction the validator handles.
+///
+/// Middleware runs in the order it was added. `before` runs once the
+/// built-in pre-checks pass and may reject the transaction by returning an
+/// error. `after` sees the effects of every executed transaction, aborted
+/// ones included; by then the effects are committed, so an error from it is
+/// only logged and the caller still gets the effects.
+#[async_trait]
+pub trait TxMiddleware: Send + Sync {
+    async fn before(&self, _request: &ExecutionRequest) -> Result<()> {
+        Ok(())
+    }
+
+    async fn after(&self, _request: &ExecutionRequest, _effects: &TransactionEffects) -> Result<()> {
+        Ok(())
+    }
+}
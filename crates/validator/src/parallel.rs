@@ -0,0 +1,202 @@
+use std::sync::Mutex;
+
+use sui_core::messages::ExecutionRequest;
+use sui_core::object::ObjectID;
+
+use crate::mempool::PendingTransaction;
+
+/// Objects a transaction declares, split by how it uses them.
+struct Access<'a> {
+    signer: &'a str,
+    reads: Vec<&'a ObjectID>,
+    writes: Vec<&'a ObjectID>,
+}
+
+impl<'a> Access<'a> {
+    fn of(request: &'a ExecutionRequest) -> Self {
+        let payload = &request.tx.payload;
+        let mut access = Access {
+            signer: &request.tx.signer,
+            reads: Vec::new(),
+            writes: Vec::new(),
+        };
+        for input in &payload.input_objects {
+            if input.mutable && !payload.read_only {
+                access.writes.push(&input.id);
+            } else {
+                access.reads.push(&input.id);
+            }
+        }
+        // Gas is charged even to read-only transactions.
+        access.writes.extend(payload.gas_payment.as_ref());
+        access
+    }
+
+    /// Two transactions conflict when one writes an object the other uses.
+    /// Transactions from the same signer always conflict so that their
+    /// sequence numbers are consumed in order.
+    fn conflicts_with(&self, other: &Access) -> bool {
+        self.signer == other.signer
+            || self
+                .writes
+                .iter()
+                .any(|id| other.writes.contains(id) || other.reads.contains(id))
+            || other.writes.iter().any(|id| self.reads.contains(id))
+    }
+}
+
+/// Parallelism achieved by a [`ParallelScheduler`] so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SchedulingStats {
+    pub transactions: u64,
+    /// Groups of mutually independent transactions run one after another.
+    pub waves: u64,
+    /// Size of the largest wave.
+    pub max_parallelism: usize,
+}
+
+impl SchedulingStats {
+    /// Transactions per wave; 1.0 means everything ran serially.
+    pub fn average_parallelism(&self) -> f64 {
+        if self.waves == 0 {
+            return 0.0;
+        }
+        self.transactions as f64 / self.waves as f64
+    }
+}
+
+/// Splits a batch of transactions into waves using their declared inputs:
+/// transactions in the same wave touch disjoint objects and may run
+/// concurrently, while a transaction conflicting with an earlier one lands
+/// in a later wave, so conflicting transactions keep their batch order.
+///
+/// Only declared inputs are considered; a transaction reaching objects it
+/// did not declare still runs under the validator's object locks.
+#[derive(Debug, Default)]
+pub struct ParallelScheduler {
+    stats: Mutex<SchedulingStats>,
+}
+
+impl ParallelScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wave of each request: one past the latest earlier request it
+    /// conflicts with, or 0.
+    pub fn waves_of(requests: &[&ExecutionRequest]) -> Vec<usize> {
+        let accesses: Vec<Access> = requests.iter().map(|request| Access::of(request)).collect();
+        let mut waves: Vec<usize> = Vec::with_capacity(requests.len());
+        for (i, access) in accesses.iter().enumerate() {
+            let wave = (0..i)
+                .filter(|&j| access.conflicts_with(&accesses[j]))
+                .map(|j| waves[j] + 1)
+                .max()
+                .unwrap_or(0);
+            waves.push(wave);
+        }
+        waves
+    }
+
+    /// Groups `batch` into waves, in execution order, and records them in
+    /// the stats.
+    pub fn schedule(&self, batch: Vec<PendingTransaction>) -> Vec<Vec<PendingTransaction>> {
+        let levels = {
+            let requests: Vec<&ExecutionRequest> = batch.iter().map(|pending| &pending.request).collect();
+            Self::waves_of(&requests)
+        };
+        let count = levels.iter().max().map_or(0, |max| max + 1);
+        let mut waves: Vec<Vec<PendingTransaction>> = (0..count).map(|_| Vec::new()).collect();
+        for (pending, level) in batch.into_iter().zip(levels) {
+            waves[level].push(pending);
+        }
+
+        let mut stats = self.stats.lock().unwrap();
+        for wave in &waves {
+            stats.transactions += wave.len() as u64;
+            stats.waves += 1;
+            stats.max_parallelism = stats.max_parallelism.max(wave.len());
+        }
+        waves
+    }
+
+    pub fn stats(&self) -> SchedulingStats {
+        *self.stats.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mempool::Mempool;
+    use crate::testing::transfer;
+    use std::sync::Arc;
+    use sui_core::clock::MockClock;
+
+    /// A request that only reads `object`.
+    fn reading(signer: &str, object: &str) -> ExecutionRequest {
+        let mut request = transfer(signer, object, "bob");
+        request.tx.payload.input_objects[0].mutable = false;
+        request
+    }
+
+    fn waves(requests: &[ExecutionRequest]) -> Vec<usize> {
+        ParallelScheduler::waves_of(&requests.iter().collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn disjoint_transactions_share_a_wave() {
+        let requests = [transfer("alice", "coin-a", "bob"), transfer("bob", "coin-b", "carol")];
+
+        assert_eq!(waves(&requests), vec![0, 0]);
+    }
+
+    #[test]
+    fn conflicting_transactions_keep_their_order() {
+        let requests = [
+            transfer("alice", "coin-a", "bob"),
+            transfer("bob", "coin-b", "carol"),
+            transfer("carol", "coin-a", "dave"),
+            transfer("dave", "coin-a", "erin"),
+        ];
+
+        assert_eq!(waves(&requests), vec![0, 0, 1, 2]);
+    }
+
+    #[test]
+    fn readers_share_a_wave_until_someone_writes() {
+        let requests = [reading("alice", "pool"), reading("bob", "pool"), transfer("carol", "pool", "dave")];
+
+        assert_eq!(waves(&requests), vec![0, 0, 1]);
+    }
+
+    #[test]
+    fn one_signers_transactions_never_share_a_wave() {
+        let requests = [transfer("alice", "coin-a", "bob"), transfer("alice", "coin-b", "bob")];
+
+        assert_eq!(waves(&requests), vec![0, 1]);
+    }
+
+    #[test]
+    fn scheduling_records_waves_and_parallelism() {
+        let mempool = Mempool::new(Arc::new(MockClock::new(0)), 60_000);
+        for request in [
+            transfer("alice", "coin-a", "bob"),
+            transfer("bob", "coin-b", "carol"),
+            transfer("carol", "coin-c", "dave"),
+            transfer("dave", "coin-a", "erin"),
+        ] {
+            drop(mempool.submit(request));
+        }
+        let scheduler = ParallelScheduler::new();
+
+        let scheduled = scheduler.schedule(mempool.take_up_to(10));
+
+        let sizes: Vec<usize> = scheduled.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![3, 1]);
+        assert_eq!(scheduled[1][0].request.tx.signer, "dave");
+        let stats = scheduler.stats();
+        assert_eq!((stats.transactions, stats.waves, stats.max_parallelism), (4, 2, 3));
+        assert_eq!(stats.average_parallelism(), 2.0);
+    }
+}
@@ -0,0 +1,21 @@
+use sui_core::{messages::ExecutionRequest, object::SuiObject};
+
+/// A validator's own rules for which transactions it accepts, on top of the
+/// built-in pre-checks. Lets validators in one simulation behave
+/// differently, e.g. one refusing high-value transfers.
+///
+/// Consulted once the transaction's declared inputs are loaded; `inputs`
+/// holds those that exist. Returning `Err` rejects the transaction with the
+/// given reason before anything executes.
+pub trait AdmissionPolicy: Send + Sync {
+    fn admit(&self, request: &ExecutionRequest, inputs: &[SuiObject]) -> Result<(), String>;
+}
+
+impl<F> AdmissionPolicy for F
+where
+    F: Fn(&ExecutionRequest, &[SuiObject]) -> Result<(), String> + Send + Sync,
+{
+    fn admit(&self, request: &ExecutionRequest, inputs: &[SuiObject]) -> Result<(), String> {
+        self(request, inputs)
+    }
+}
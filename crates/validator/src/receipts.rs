@@ -0,0 +1,81 @@
+use std::collections::{HashMap, VecDeque};
+use sui_core::{messages::TransactionReceipt, transaction::TransactionDigest};
+
+/// Receipts kept unless configured otherwise.
+pub const DEFAULT_RECEIPT_CAPACITY: usize = 10_000;
+
+/// The most recently issued receipts. Once full, the oldest receipt is
+/// dropped for each new one; the effects of its transaction stay in the
+/// effects store.
+pub(crate) struct ReceiptCache {
+    capacity: usize,
+    receipts: HashMap<TransactionDigest, TransactionReceipt>,
+    /// Issue order, for dropping the oldest receipts first.
+    order: VecDeque<TransactionDigest>,
+}
+
+impl ReceiptCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            receipts: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn insert(&mut self, receipt: TransactionReceipt) {
+        let digest = receipt.digest.clone();
+        if self.receipts.insert(digest.clone(), receipt).is_none() {
+            self.order.push_back(digest);
+        }
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.receipts.remove(&oldest);
+            }
+        }
+    }
+
+    pub(crate) fn get(&self, digest: &TransactionDigest) -> Option<&TransactionReceipt> {
+        self.receipts.get(digest)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.receipts.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_core::crypto::KeyPair;
+
+    fn receipt(digest: &str, keypair: &KeyPair) -> TransactionReceipt {
+        TransactionReceipt::new_signed(TransactionDigest(digest.to_string()), "v1", 1, true, 0, keypair)
+    }
+
+    #[test]
+    fn full_cache_drops_the_oldest_receipt() {
+        let keypair = KeyPair::from_seed([1; 32]);
+        let mut cache = ReceiptCache::new(2);
+        for digest in ["tx-1", "tx-2", "tx-3"] {
+            cache.insert(receipt(digest, &keypair));
+        }
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&TransactionDigest("tx-1".to_string())).is_none());
+        assert!(cache.get(&TransactionDigest("tx-2".to_string())).is_some());
+        assert!(cache.get(&TransactionDigest("tx-3".to_string())).is_some());
+    }
+
+    #[test]
+    fn reinserting_a_receipt_does_not_evict_another() {
+        let keypair = KeyPair::from_seed([1; 32]);
+        let mut cache = ReceiptCache::new(2);
+        cache.insert(receipt("tx-1", &keypair));
+        cache.insert(receipt("tx-2", &keypair));
+        cache.insert(receipt("tx-2", &keypair));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&TransactionDigest("tx-1".to_string())).is_some());
+    }
+}
@@ -0,0 +1,125 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, VecDeque};
+use sui_core::messages::ExecutionRequest;
+
+use crate::mempool::{Mempool, PendingTransaction};
+
+/// Transactions ordered together by default.
+pub const DEFAULT_SCHEDULER_WINDOW: usize = 256;
+
+/// Orders admitted transactions canonically so that every validator given
+/// the same set executes, and checkpoints, them in the same order.
+///
+/// Up to `window` transactions are drained from the mempool at a time and
+/// sorted by gas price, highest first, then by digest, except that each
+/// signer's transactions keep their sequence number order: a signer's next
+/// transaction competes on its own price only once the ones before it have
+/// been placed. Ordering only holds
+/// within a window, so validators agree exactly when their windows hold the
+/// same transactions; a larger window trades latency for that agreement.
+#[derive(Debug, Clone, Copy)]
+pub struct DeterministicScheduler {
+    window: usize,
+}
+
+impl Default for DeterministicScheduler {
+    fn default() -> Self {
+        Self::new(DEFAULT_SCHEDULER_WINDOW)
+    }
+}
+
+impl DeterministicScheduler {
+    pub fn new(window: usize) -> Self {
+        Self { window: window.max(1) }
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Priority between the next transactions of two signers: higher gas
+    /// price first, then ascending digest.
+    pub fn canonical_cmp(a: &ExecutionRequest, b: &ExecutionRequest) -> Ordering {
+        b.tx.payload
+            .gas_price
+            .cmp(&a.tx.payload.gas_price)
+            .then_with(|| a.digest.0.cmp(&b.digest.0))
+    }
+
+    /// Sorts `requests` into canonical order.
+    pub fn order(&self, requests: &mut [ExecutionRequest]) {
+        let ordered = canonical_order(requests.to_vec(), |request| request);
+        requests.clone_from_slice(&ordered);
+    }
+
+    /// Drains the next window from the mempool in canonical order.
+    pub fn next_window(&self, mempool: &Mempool) -> Vec<PendingTransaction> {
+        canonical_order(mempool.take_up_to(self.window), |pending| &pending.request)
+    }
+}
+
+/// Queues each signer's transactions in sequence number order, then
+/// repeatedly takes the queue head that comes first by
+/// [`DeterministicScheduler::canonical_cmp`].
+fn canonical_order<T>(items: Vec<T>, request: impl Fn(&T) -> &ExecutionRequest) -> Vec<T> {
+    let total = items.len();
+    let mut queues: BTreeMap<String, VecDeque<T>> = BTreeMap::new();
+    for item in items {
+        let signer = request(&item).tx.signer.clone();
+        queues.entry(signer).or_default().push_back(item);
+    }
+    for queue in queues.values_mut() {
+        queue.make_contiguous().sort_by(|a, b| {
+            let (a, b) = (request(a), request(b));
+            a.tx.payload
+                .sequence_number
+                .cmp(&b.tx.payload.sequence_number)
+                .then_with(|| a.digest.0.cmp(&b.digest.0))
+        });
+    }
+
+    let mut ordered = Vec::with_capacity(total);
+    while let Some(signer) = queues
+        .iter()
+        .min_by(|(_, a), (_, b)| DeterministicScheduler::canonical_cmp(request(&a[0]), request(&b[0])))
+        .map(|(signer, _)| signer.clone())
+    {
+        let queue = queues.get_mut(&signer).expect("signer has a queue");
+        ordered.extend(queue.pop_front());
+        if queue.is_empty() {
+            queues.remove(&signer);
+        }
+    }
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::sequenced_transfer;
+
+    fn priced(signer: &str, sequence_number: u64, gas_price: u64) -> ExecutionRequest {
+        let mut request = sequenced_transfer(signer, "coin", "bob", sequence_number);
+        request.tx.payload.gas_price = gas_price;
+        request
+    }
+
+    fn sequences(requests: &[ExecutionRequest]) -> Vec<(String, u64)> {
+        requests
+            .iter()
+            .map(|request| (request.tx.signer.clone(), request.tx.payload.sequence_number))
+            .collect()
+    }
+
+    #[test]
+    fn a_signers_transactions_keep_their_sequence_order() {
+        let mut requests = vec![priced("alice", 1, 1), priced("alice", 2, 5), priced("bob", 1, 3)];
+
+        DeterministicScheduler::default().order(&mut requests);
+
+        assert_eq!(
+            sequences(&requests),
+            vec![("bob".to_string(), 1), ("alice".to_string(), 1), ("alice".to_string(), 2)]
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::time::Duration;
+use sui_effects::TransactionEffects;
+use tokio::sync::mpsc;
+
+/// Timeout applied to each webhook delivery.
+pub const DEFAULT_WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Destination for the effects of every committed transaction.
+///
+/// The validator logs and otherwise ignores emit failures: a sink that is
+/// down never holds up transaction processing.
+#[async_trait]
+pub trait EffectsSink: Send + Sync {
+    async fn emit(&self, effects: &TransactionEffects) -> Result<()>;
+}
+
+/// Forwards effects into a channel.
+pub struct ChannelSink {
+    sender: mpsc::UnboundedSender<TransactionEffects>,
+}
+
+impl ChannelSink {
+    pub fn new(sender: mpsc::UnboundedSender<TransactionEffects>) -> Self {
+        Self { sender }
+    }
+
+    /// A sink along with the receiving end of its channel.
+    pub fn channel() -> (Self, mpsc::UnboundedReceiver<TransactionEffects>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (Self::new(sender), receiver)
+    }
+}
+
+#[async_trait]
+impl EffectsSink for ChannelSink {
+    async fn emit(&self, effects: &TransactionEffects) -> Result<()> {
+        self.sender
+            .send(effects.clone())
+            .map_err(|_| anyhow!("effects channel receiver was dropped"))
+    }
+}
+
+/// POSTs each transaction's effects as JSON to a URL.
+pub struct WebhookSink {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl WebhookSink {
+    pub fn new(url: impl Into<String>) -> Result<Self> {
+        Self::with_timeout(url, DEFAULT_WEBHOOK_TIMEOUT)
+    }
+
+    pub fn with_timeout(url: impl Into<String>, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder().timeout(timeout).build()?;
+        Ok(Self { url: url.into(), client })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[async_trait]
+impl EffectsSink for WebhookSink {
+    async fn emit(&self, effects: &TransactionEffects) -> Result<()> {
+        self.client
+            .post(&self.url)
+            .json(effects)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{coin, node, transfer};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn channel_sink_receives_each_committed_transaction() {
+        let mut node = node("v1").await;
+        let (sink, mut receiver) = ChannelSink::channel();
+        node.set_effects_sink(Arc::new(sink));
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        node.object_store.put_object(coin("coin-b", "alice", 10)).await.unwrap();
+        let first = transfer("alice", "coin-a", "bob");
+        let second = transfer("alice", "coin-b", "bob");
+        let digests = [first.digest.clone(), second.digest.clone()];
+
+        node.handle_transaction(first).await.unwrap();
+        node.handle_transaction(second).await.unwrap();
+
+        for digest in digests {
+            assert_eq!(receiver.recv().await.unwrap().digest, digest);
+        }
+    }
+
+    #[tokio::test]
+    async fn unreachable_webhook_does_not_fail_the_transaction() {
+        let mut node = node("v1").await;
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let sink = WebhookSink::with_timeout(format!("http://127.0.0.1:{}/effects", port), Duration::from_millis(200))
+            .unwrap();
+        node.set_effects_sink(Arc::new(sink));
+        node.object_store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+
+        let effects = node.handle_transaction(transfer("alice", "coin-a", "bob")).await.unwrap();
+
+        assert!(effects.status.is_success());
+        let moved = node.object_store.get_object("coin-a").await.unwrap().unwrap();
+        assert_eq!(moved.owner, sui_core::object::Owner::Address("bob".to_string()));
+    }
+}
@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Coin type all `ObjectData::Coin` balances count towards; coins carry no
+/// type of their own yet.
+pub const NATIVE_COIN_TYPE: &str = "SUI";
+
+/// Circulating supply per coin type, moved by committed mints and burns.
+///
+/// Coins that exist before the validator starts, such as genesis objects,
+/// are not seen being minted; seed them with `set_supply`.
+#[derive(Debug, Default)]
+pub struct SupplyTracker {
+    supplies: RwLock<HashMap<String, u64>>,
+}
+
+impl SupplyTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_supply(&self, coin_type: &str, amount: u64) {
+        self.supplies.write().unwrap().insert(coin_type.to_string(), amount);
+    }
+
+    /// Applies one transaction's mints and burns.
+    pub fn apply(&self, coin_type: &str, minted: u64, burned: u64) {
+        if minted == 0 && burned == 0 {
+            return;
+        }
+        let mut supplies = self.supplies.write().unwrap();
+        let supply = supplies.entry(coin_type.to_string()).or_default();
+        *supply = supply.saturating_add(minted).saturating_sub(burned);
+    }
+
+    pub fn supply(&self, coin_type: &str) -> u64 {
+        self.supplies.read().unwrap().get(coin_type).copied().unwrap_or(0)
+    }
+
+    pub fn supplies(&self) -> HashMap<String, u64> {
+        self.supplies.read().unwrap().clone()
+    }
+}
@@ -0,0 +1,100 @@
+//! Fixtures shared by the validator's unit tests.
+
+use crate::ValidatorNode;
+use std::sync::Arc;
+use std::time::Duration;
+use sui_core::{
+    messages::ExecutionRequest,
+    object::{ObjectData, ObjectID, Owner, SuiObject},
+    transaction::{Argument, InputObject, SignedTransaction, TransactionDigest, TransactionKind, TransactionPayload},
+};
+use sui_network::NetworkClient;
+use sui_storage::{InMemoryCheckpointStore, InMemoryEffectsStore, InMemoryObjectStore};
+
+/// A validator over fresh in-memory stores.
+pub(crate) async fn node(name: &str) -> ValidatorNode {
+    ValidatorNode::new(
+        name,
+        Arc::new(InMemoryObjectStore::new()),
+        Arc::new(InMemoryEffectsStore::new()),
+        Arc::new(InMemoryCheckpointStore::new()),
+    )
+    .await
+    .expect("in-memory validator")
+}
+
+/// Serves `node` on a free local port, returning a client once it answers.
+pub(crate) async fn serve(node: &ValidatorNode) -> NetworkClient {
+    NetworkClient::new(serve_endpoint(node).await)
+}
+
+/// Serves `node` on a free local port, returning its base URL once it
+/// answers.
+pub(crate) async fn serve_endpoint(node: &ValidatorNode) -> String {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("free port")
+        .port();
+    let server = node.clone();
+    tokio::spawn(async move { server.start_network_server(port).await });
+
+    let endpoint = format!("http://127.0.0.1:{}", port);
+    let client = NetworkClient::new(endpoint.clone());
+    for _ in 0..100 {
+        if client.is_ready().await.is_ok() {
+            return endpoint;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("validator server on port {} never came up", port);
+}
+
+pub(crate) fn coin(id: &str, owner: &str, balance: u64) -> SuiObject {
+    SuiObject::new(
+        ObjectID::new(id),
+        Owner::Address(owner.to_string()),
+        ObjectData::Coin { balance },
+    )
+}
+
+pub(crate) fn payload(kind: TransactionKind, input_objects: Vec<InputObject>) -> TransactionPayload {
+    TransactionPayload {
+        kind,
+        gas_budget: 1_000,
+        input_objects,
+        sequence_number: 0,
+        gas_price: 0,
+        read_only: false,
+        gas_payment: None,
+    }
+}
+
+pub(crate) fn request(signer: &str, payload: TransactionPayload) -> ExecutionRequest {
+    ExecutionRequest {
+        tx: SignedTransaction::new(signer.to_string(), payload),
+        digest: TransactionDigest::random(),
+    }
+}
+
+pub(crate) fn transfer(signer: &str, object: &str, recipient: &str) -> ExecutionRequest {
+    ExecutionRequest {
+        tx: SignedTransaction::new_transfer(signer.to_string(), recipient.to_string(), object.to_string()),
+        digest: TransactionDigest::random(),
+    }
+}
+
+pub(crate) fn call(module: &str, function: &str, arguments: Vec<Argument>) -> TransactionKind {
+    TransactionKind::Call {
+        package: ObjectID::new("0x2"),
+        module: module.to_string(),
+        function: function.to_string(),
+        arguments,
+    }
+}
+
+/// A transfer carrying the signer's `sequence_number`.
+pub(crate) fn sequenced_transfer(signer: &str, object: &str, recipient: &str, sequence_number: u64) -> ExecutionRequest {
+    let mut request = transfer(signer, object, recipient);
+    request.tx.payload.sequence_number = sequence_number;
+    request
+}
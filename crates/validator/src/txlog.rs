@@ -0,0 +1,38 @@
+use std::sync::RwLock;
+use sui_core::messages::{TxLogEntry, TxLogFilter};
+
+/// Append-only record of every transaction the validator committed,
+/// aborted ones included, in commit order.
+#[derive(Debug, Default)]
+pub struct TransactionLog {
+    entries: RwLock<Vec<TxLogEntry>>,
+}
+
+impl TransactionLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn append(&self, entry: TxLogEntry) {
+        self.entries.write().unwrap().push(entry);
+    }
+
+    /// Entries matching `filter`, in commit order.
+    pub fn query(&self, filter: &TxLogFilter) -> Vec<TxLogEntry> {
+        self.entries
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|entry| filter.matches(entry))
+            .cloned()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,49 @@
+use std::fmt::Debug;
+use sui_core::object::{normalize_address, ObjectID};
+
+/// What an operator approval covers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ApprovalScope {
+    /// A single object of the owner's.
+    Object(ObjectID),
+    /// Every object the owner holds, now or later.
+    All,
+}
+
+/// An approval granted or withdrawn by `object::approve` / `object::revoke`.
+/// Staged during execution and applied by the validator on commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApprovalChange {
+    Approve {
+        owner: String,
+        scope: ApprovalScope,
+        operator: String,
+    },
+    Revoke {
+        owner: String,
+        scope: ApprovalScope,
+        operator: String,
+    },
+}
+
+impl ApprovalChange {
+    /// Whether `operator` holds `owner`'s approval for exactly `scope` after
+    /// this change, or `None` when the change is about something else.
+    pub(crate) fn decides(&self, owner: &str, scope: &ApprovalScope, operator: &str) -> Option<bool> {
+        let (change_owner, change_scope, change_operator, approved) = match self {
+            ApprovalChange::Approve { owner, scope, operator } => (owner, scope, operator, true),
+            ApprovalChange::Revoke { owner, scope, operator } => (owner, scope, operator, false),
+        };
+        let relevant = normalize_address(change_owner) == normalize_address(owner)
+            && normalize_address(change_operator) == normalize_address(operator)
+            && change_scope == scope;
+        relevant.then_some(approved)
+    }
+}
+
+/// Committed operator approvals, consulted when someone other than the
+/// owner transfers an object. An operator may move an object when approved
+/// for it or for all of the owner's objects.
+pub trait OperatorApprovals: Send + Sync + Debug {
+    fn is_approved(&self, owner: &str, scope: &ApprovalScope, operator: &str) -> bool;
+}
@@ -0,0 +1,427 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use sui_core::object::{ObjectData, ObjectID, Owner, SuiObject};
+use sui_core::transaction::TransactionDigest;
+use sui_storage::ObjectStore;
+
+use crate::{
+    abort_codes, ApprovalChange, ApprovalScope, ExecutionTrace, GasSchedule, OperatorApprovals, TraceEntry,
+    TypeSchemas, VmAbort, VmConfig,
+};
+
+/// Per-transaction view of the object store.
+///
+/// Reads are served from the prefetched inputs when present and otherwise
+/// fall through to the backing store, writes and deletions are staged
+/// so that an aborted transaction leaves the store untouched, and every
+/// access is counted against the configured read/write limits. A read-only
+/// context rejects every write.
+pub struct ExecutionContext<'a> {
+    store: Option<&'a dyn ObjectStore>,
+    /// Transaction being executed, from which new object ids are derived.
+    digest: TransactionDigest,
+    /// Ids derived so far by `fresh_id`.
+    derived_ids: AtomicU64,
+    staged: Mutex<BTreeMap<String, Option<SuiObject>>>,
+    /// Ids of the objects staged through `create_object`.
+    created_ids: Mutex<BTreeSet<String>>,
+    /// Inputs already loaded by the caller, read in place of the store.
+    prefetched: HashMap<String, SuiObject>,
+    /// Version of each object as first read from the store.
+    input_versions: Mutex<BTreeMap<String, u64>>,
+    /// Owner and balance of each coin as first read from the store.
+    input_coins: Mutex<BTreeMap<String, (Owner, u64)>>,
+    reads: AtomicU64,
+    writes: AtomicU64,
+    created: AtomicU64,
+    max_reads: Option<u64>,
+    max_writes: Option<u64>,
+    max_created: Option<u64>,
+    gas_schedule: GasSchedule,
+    type_schemas: TypeSchemas,
+    storage_gas: AtomicU64,
+    minted: AtomicU64,
+    burned: AtomicU64,
+    approvals: Option<Arc<dyn OperatorApprovals>>,
+    approval_changes: Mutex<Vec<ApprovalChange>>,
+    read_only: bool,
+    /// Interpreter steps, collected only when tracing.
+    trace: Option<Mutex<Vec<TraceEntry>>>,
+}
+
+impl<'a> ExecutionContext<'a> {
+    pub fn new(store: Option<&'a dyn ObjectStore>, config: &VmConfig) -> Self {
+        Self {
+            store,
+            digest: TransactionDigest(String::new()),
+            derived_ids: AtomicU64::new(0),
+            staged: Mutex::new(BTreeMap::new()),
+            created_ids: Mutex::new(BTreeSet::new()),
+            prefetched: HashMap::new(),
+            input_versions: Mutex::new(BTreeMap::new()),
+            input_coins: Mutex::new(BTreeMap::new()),
+            reads: AtomicU64::new(0),
+            writes: AtomicU64::new(0),
+            created: AtomicU64::new(0),
+            max_reads: config.max_object_reads,
+            max_writes: config.max_object_writes,
+            max_created: config.max_objects_created,
+            gas_schedule: config.gas_schedule.clone(),
+            type_schemas: config.type_schemas.clone(),
+            storage_gas: AtomicU64::new(0),
+            minted: AtomicU64::new(0),
+            burned: AtomicU64::new(0),
+            approvals: config.approvals.clone(),
+            approval_changes: Mutex::new(Vec::new()),
+            read_only: false,
+            trace: None,
+        }
+    }
+
+    /// Derives new object ids from `digest`; see [`fresh_id`](Self::fresh_id).
+    pub fn with_digest(mut self, digest: TransactionDigest) -> Self {
+        self.digest = digest;
+        self
+    }
+
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Serves reads of `objects` from memory instead of the store. They still
+    /// count against the read limit and record their input version.
+    pub fn with_prefetched(mut self, objects: impl IntoIterator<Item = SuiObject>) -> Self {
+        self.prefetched
+            .extend(objects.into_iter().map(|object| (object.id.0.clone(), object)));
+        self
+    }
+
+    /// Collects an [`ExecutionTrace`] of every interpreted instruction.
+    pub fn with_tracing(mut self, tracing: bool) -> Self {
+        self.trace = tracing.then(|| Mutex::new(Vec::new()));
+        self
+    }
+
+    pub fn is_tracing(&self) -> bool {
+        self.trace.is_some()
+    }
+
+    /// Records the interpreter state after `instruction`. A no-op unless
+    /// tracing.
+    pub fn record_trace(&self, index: usize, instruction: String, stack: &[serde_json::Value], gas_used: u64) {
+        if let Some(trace) = &self.trace {
+            trace.lock().unwrap().push(TraceEntry {
+                index,
+                instruction,
+                stack: stack.to_vec(),
+                gas_used,
+            });
+        }
+    }
+
+    /// The steps recorded so far, or `None` when not tracing.
+    pub fn trace(&self) -> Option<ExecutionTrace> {
+        self.trace.as_ref().map(|trace| ExecutionTrace {
+            entries: trace.lock().unwrap().clone(),
+        })
+    }
+
+    pub fn has_store(&self) -> bool {
+        self.store.is_some()
+    }
+
+    pub async fn get_object(&self, id: &str) -> Result<Option<SuiObject>, VmAbort> {
+        let reads = self.reads.fetch_add(1, Ordering::SeqCst) + 1;
+        if let Some(max) = self.max_reads {
+            if reads > max {
+                return Err(VmAbort::new(
+                    abort_codes::RESOURCE_LIMIT_EXCEEDED,
+                    format!("object read limit of {} exceeded", max),
+                ));
+            }
+        }
+
+        let staged = self.staged.lock().unwrap().get(id).cloned();
+        let object = match staged {
+            Some(staged) => staged,
+            None => {
+                let object = self.load(id).await?;
+                if let Some(object) = &object {
+                    self.input_versions
+                        .lock()
+                        .unwrap()
+                        .entry(object.id.0.clone())
+                        .or_insert(object.version);
+                    if let ObjectData::Coin { balance } = object.data {
+                        self.input_coins
+                            .lock()
+                            .unwrap()
+                            .entry(object.id.0.clone())
+                            .or_insert((object.owner.clone(), balance));
+                    }
+                }
+                object
+            }
+        };
+        if let Some(object) = &object {
+            self.charge(self.gas_schedule.read_cost(object));
+        }
+        Ok(object)
+    }
+
+    /// Whether `owner` holds at least one object, taking staged writes into
+    /// account. Not counted as a read.
+    pub async fn owns_any(&self, owner: &str) -> Result<bool, VmAbort> {
+        let is_owner = |object: &SuiObject| object.owner.matches_address(owner);
+
+        let (staged_owned, staged_ids): (bool, Vec<String>) = {
+            let staged = self.staged.lock().unwrap();
+            (
+                staged.values().flatten().any(is_owner),
+                staged.keys().cloned().collect(),
+            )
+        };
+        if staged_owned {
+            return Ok(true);
+        }
+
+        let Some(store) = self.store else {
+            return Ok(false);
+        };
+        let stored = store
+            .list_objects(Some(owner))
+            .await
+            .map_err(|e| VmAbort::new(abort_codes::STORE_ERROR, e.to_string()))?;
+        // Objects staged for deletion or re-assignment no longer count.
+        Ok(stored.iter().any(|object| !staged_ids.contains(&object.id.0)))
+    }
+
+    pub fn put_object(&self, object: SuiObject) -> Result<(), VmAbort> {
+        self.count_write()?;
+        self.charge(self.gas_schedule.write_cost(&object));
+        self.staged.lock().unwrap().insert(object.id.0.clone(), Some(object));
+        Ok(())
+    }
+
+    /// Stages a newly created object, aborting if its id is already taken
+    /// in the store or by an earlier write in this transaction, or if the
+    /// transaction has used up its creation quota, or if a struct's fields
+    /// do not match its registered schema.
+    ///
+    /// A shared object gets its creation version as its initial shared
+    /// version.
+    pub async fn create_object(&self, mut object: SuiObject) -> Result<(), VmAbort> {
+        if object.is_shared() && object.initial_shared_version.is_none() {
+            object.initial_shared_version = Some(object.version);
+        }
+        self.type_schemas
+            .validate(&object.data)
+            .map_err(|reason| VmAbort::new(abort_codes::SCHEMA_MISMATCH, reason))?;
+        let created = self.created.fetch_add(1, Ordering::SeqCst) + 1;
+        if let Some(max) = self.max_created {
+            if created > max {
+                return Err(VmAbort::new(
+                    abort_codes::CREATION_QUOTA_EXCEEDED,
+                    format!("object creation quota of {} exceeded", max),
+                ));
+            }
+        }
+
+        let id = object.id.0.clone();
+        let staged = self.staged.lock().unwrap().get(&id).cloned();
+        let exists = match staged {
+            Some(staged) => staged.is_some(),
+            None => self.load(&id).await?.is_some(),
+        };
+        if exists {
+            return Err(VmAbort::new(
+                abort_codes::OBJECT_ALREADY_EXISTS,
+                format!("object {} already exists", id),
+            ));
+        }
+        self.put_object(object)?;
+        self.created_ids.lock().unwrap().insert(id);
+        Ok(())
+    }
+
+    /// Whether `id` was created earlier in this transaction.
+    pub fn was_created(&self, id: &str) -> bool {
+        self.created_ids.lock().unwrap().contains(id)
+    }
+
+    /// An id for an object this transaction is about to create, derived
+    /// from the transaction digest and a counter rather than drawn at
+    /// random, so that honest validators create identical objects.
+    pub fn fresh_id(&self) -> ObjectID {
+        ObjectID::derive(&self.digest, self.derived_ids.fetch_add(1, Ordering::SeqCst))
+    }
+
+    /// Reads `id` from the prefetched inputs, falling back to the store.
+    async fn load(&self, id: &str) -> Result<Option<SuiObject>, VmAbort> {
+        if let Some(object) = self.prefetched.get(id) {
+            return Ok(Some(object.clone()));
+        }
+        match self.store {
+            Some(store) => store
+                .get_object(id)
+                .await
+                .map_err(|e| VmAbort::new(abort_codes::STORE_ERROR, e.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    pub fn delete_object(&self, id: &ObjectID) -> Result<(), VmAbort> {
+        self.count_write()?;
+        self.staged.lock().unwrap().insert(id.0.clone(), None);
+        Ok(())
+    }
+
+    fn count_write(&self) -> Result<(), VmAbort> {
+        if self.read_only {
+            return Err(VmAbort::new(
+                abort_codes::READ_ONLY_VIOLATION,
+                "read-only transaction attempted a write",
+            ));
+        }
+        let writes = self.writes.fetch_add(1, Ordering::SeqCst) + 1;
+        match self.max_writes {
+            Some(max) if writes > max => Err(VmAbort::new(
+                abort_codes::RESOURCE_LIMIT_EXCEEDED,
+                format!("object write limit of {} exceeded", max),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn charge(&self, gas: u64) {
+        self.storage_gas.fetch_add(gas, Ordering::SeqCst);
+    }
+
+    /// Gas charged by the gas schedule for the objects read and written.
+    pub fn storage_gas(&self) -> u64 {
+        self.storage_gas.load(Ordering::SeqCst)
+    }
+
+    /// Counts coin balance created by this transaction.
+    pub fn record_mint(&self, amount: u64) {
+        self.minted.fetch_add(amount, Ordering::SeqCst);
+    }
+
+    /// Counts coin balance destroyed by this transaction.
+    pub fn record_burn(&self, amount: u64) {
+        self.burned.fetch_add(amount, Ordering::SeqCst);
+    }
+
+    /// Stages an operator approval change; it counts as a write.
+    pub fn record_approval(&self, change: ApprovalChange) -> Result<(), VmAbort> {
+        self.count_write()?;
+        self.approval_changes.lock().unwrap().push(change);
+        Ok(())
+    }
+
+    /// Whether `operator` may move `owner`'s `object`, through an approval
+    /// for the object or for all of the owner's objects. Changes staged by
+    /// this transaction take precedence over the committed approvals.
+    pub fn is_approved(&self, owner: &str, object: &ObjectID, operator: &str) -> bool {
+        let changes = self.approval_changes.lock().unwrap();
+        [ApprovalScope::Object(object.clone()), ApprovalScope::All]
+            .iter()
+            .any(|scope| {
+                let staged = changes
+                    .iter()
+                    .rev()
+                    .find_map(|change| change.decides(owner, scope, operator));
+                match staged {
+                    Some(approved) => approved,
+                    None => self
+                        .approvals
+                        .as_ref()
+                        .is_some_and(|approvals| approvals.is_approved(owner, scope, operator)),
+                }
+            })
+    }
+
+    pub fn approval_changes(&self) -> Vec<ApprovalChange> {
+        self.approval_changes.lock().unwrap().clone()
+    }
+
+    pub fn minted(&self) -> u64 {
+        self.minted.load(Ordering::SeqCst)
+    }
+
+    pub fn burned(&self) -> u64 {
+        self.burned.load(Ordering::SeqCst)
+    }
+
+    pub fn reads(&self) -> u64 {
+        self.reads.load(Ordering::SeqCst)
+    }
+
+    pub fn writes(&self) -> u64 {
+        self.writes.load(Ordering::SeqCst)
+    }
+
+    /// Pre-execution versions of the objects read from the store, ordered by
+    /// object id.
+    pub fn input_versions(&self) -> Vec<(ObjectID, u64)> {
+        self.input_versions
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, version)| (ObjectID(id.clone()), *version))
+            .collect()
+    }
+
+    /// Pre-execution owner and balance of the coins read from the store,
+    /// ordered by object id.
+    pub fn input_coins(&self) -> Vec<(ObjectID, Owner, u64)> {
+        self.input_coins
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, (owner, balance))| (ObjectID(id.clone()), owner.clone(), *balance))
+            .collect()
+    }
+
+    /// Splits the staged changes into written objects and deleted ids, and
+    /// notes which of the written objects this transaction created. An
+    /// object created and deleted again leaves no change at all.
+    pub fn into_changes(self) -> StagedChanges {
+        let staged = self
+            .staged
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let created_ids = self
+            .created_ids
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut changes = StagedChanges::default();
+        for (id, object) in staged {
+            let created = created_ids.contains(&id);
+            match object {
+                Some(object) => {
+                    if created {
+                        changes.created.push(object.id.clone());
+                    }
+                    changes.written.push(object);
+                }
+                None if created => {}
+                None => changes.deleted.push(ObjectID(id)),
+            }
+        }
+        changes
+    }
+}
+
+/// What a transaction leaves behind, each list ordered by object id.
+#[derive(Debug, Clone, Default)]
+pub struct StagedChanges {
+    pub written: Vec<SuiObject>,
+    /// Ids of the written objects that did not exist before the
+    /// transaction, however many times it wrote them.
+    pub created: Vec<ObjectID>,
+    pub deleted: Vec<ObjectID>,
+}
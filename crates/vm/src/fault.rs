@@ -0,0 +1,72 @@
+use std::sync::Mutex;
+use sui_core::{messages::ExecutionRequest, transaction::TransactionDigest};
+
+use crate::VmAbort;
+
+/// Point in the transaction pipeline at which a fault fires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultStage {
+    /// Rejected during the validator's pre-checks, before anything runs.
+    PreCheck,
+    /// Aborted by the VM; the failure is committed like any other abort.
+    Execution,
+    /// Executed, then failed before anything is persisted.
+    Commit,
+}
+
+/// Which transactions a fault applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FaultTarget {
+    Digest(TransactionDigest),
+    Signer(String),
+}
+
+impl FaultTarget {
+    fn matches(&self, request: &ExecutionRequest) -> bool {
+        match self {
+            FaultTarget::Digest(digest) => &request.digest == digest,
+            FaultTarget::Signer(signer) => &request.tx.signer == signer,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Fault {
+    target: FaultTarget,
+    stage: FaultStage,
+    abort: VmAbort,
+}
+
+/// Forces chosen transactions to fail at a chosen stage, for exercising
+/// failure paths deterministically. Only compiled with the
+/// `fault-injection` feature.
+#[derive(Debug, Default)]
+pub struct FaultInjector {
+    faults: Mutex<Vec<Fault>>,
+}
+
+impl FaultInjector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes every transaction matching `target` fail at `stage` with
+    /// `abort`. Faults stay armed until cleared.
+    pub fn inject(&self, target: FaultTarget, stage: FaultStage, abort: VmAbort) {
+        self.faults.lock().unwrap().push(Fault { target, stage, abort });
+    }
+
+    pub fn clear(&self) {
+        self.faults.lock().unwrap().clear();
+    }
+
+    /// The abort of the first fault armed for `request` at `stage`.
+    pub fn check(&self, stage: FaultStage, request: &ExecutionRequest) -> Option<VmAbort> {
+        self.faults
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|fault| fault.stage == stage && fault.target.matches(request))
+            .map(|fault| fault.abort.clone())
+    }
+}
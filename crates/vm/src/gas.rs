@@ -0,0 +1,84 @@
+use sui_core::object::{ObjectData, SuiObject};
+use sui_storage::serialized_size;
+
+use crate::MoveInstruction;
+
+/// Flat gas charged for interpreting each kind of bytecode instruction.
+/// A transfer's storage cost is charged on top, per byte written, by the
+/// rest of the [`GasSchedule`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstructionCosts {
+    pub load_const: u64,
+    pub call_function: u64,
+    pub transfer: u64,
+    pub function_return: u64,
+}
+
+impl Default for InstructionCosts {
+    /// Every instruction costs 50.
+    fn default() -> Self {
+        Self {
+            load_const: 50,
+            call_function: 50,
+            transfer: 50,
+            function_return: 50,
+        }
+    }
+}
+
+impl InstructionCosts {
+    pub fn cost(&self, instruction: &MoveInstruction) -> u64 {
+        match instruction {
+            MoveInstruction::LoadConst(_) => self.load_const,
+            MoveInstruction::CallFunction { .. } => self.call_function,
+            MoveInstruction::Transfer { .. } => self.transfer,
+            MoveInstruction::Return => self.function_return,
+        }
+    }
+}
+
+/// Read and write multipliers for one kind of object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeMultipliers {
+    pub read: u64,
+    pub write: u64,
+}
+
+impl Default for TypeMultipliers {
+    fn default() -> Self {
+        Self { read: 1, write: 1 }
+    }
+}
+
+/// Storage gas charged per object accessed, on top of instruction gas:
+/// `serialized size * per-byte price * multiplier for the object's type`.
+///
+/// The default prices bytes at zero, so no storage gas is charged, and
+/// charges 50 for every instruction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GasSchedule {
+    pub instructions: InstructionCosts,
+    pub read_gas_per_byte: u64,
+    pub write_gas_per_byte: u64,
+    pub coin: TypeMultipliers,
+    pub package: TypeMultipliers,
+    pub move_struct: TypeMultipliers,
+}
+
+impl GasSchedule {
+    fn multipliers(&self, object: &SuiObject) -> TypeMultipliers {
+        match object.data {
+            ObjectData::Coin { .. } => self.coin,
+            ObjectData::Package { .. } => self.package,
+            ObjectData::MoveStruct { .. } => self.move_struct,
+        }
+    }
+
+    pub fn read_cost(&self, object: &SuiObject) -> u64 {
+        serialized_size(object) as u64 * self.read_gas_per_byte * self.multipliers(object).read
+    }
+
+    pub fn write_cost(&self, object: &SuiObject) -> u64 {
+        serialized_size(object) as u64 * self.write_gas_per_byte * self.multipliers(object).write
+    }
+}
@@ -1,16 +1,209 @@
+mod approval;
+mod context;
+#[cfg(feature = "fault-injection")]
+mod fault;
+mod gas;
+mod schedule;
+mod schema;
+mod trace;
+
+pub use approval::{ApprovalChange, ApprovalScope, OperatorApprovals};
+pub use context::{ExecutionContext, StagedChanges};
+#[cfg(feature = "fault-injection")]
+pub use fault::{FaultInjector, FaultStage, FaultTarget};
+pub use gas::{GasSchedule, InstructionCosts, TypeMultipliers};
+pub use schema::{FieldType, StructSchema, TypeSchemas};
+pub use trace::{ExecutionTrace, TraceEntry};
+
+use futures::future::join_all;
+use schedule::execution_levels;
 use serde_json::Value;
+use std::collections::HashSet;
 use sui_core::{
-    messages::ExecutionRequest,
+    events::ObjectEvent,
+    messages::{BatchResult, CommandResult, CommandStatus, ExecutionRequest, ExecutionStatus},
     object::{ObjectData, ObjectID, Owner, SuiObject},
-    transaction::TransactionKind,
+    transaction::{Argument, StateCondition, TransactionKind, TransactionPayload},
 };
 use sui_storage::ObjectStore;
 
-#[derive(Debug, Clone)]
+/// Abort codes reported through `ExecutionStatus::Failure`.
+pub mod abort_codes {
+    pub const OBJECT_NOT_FOUND: u64 = 1;
+    pub const NOT_OWNER: u64 = 2;
+    pub const INVALID_OBJECT_TYPE: u64 = 3;
+    pub const TYPE_NOT_MIGRATABLE: u64 = 4;
+    pub const STORE_ERROR: u64 = 5;
+    pub const IMMUTABLE_OBJECT: u64 = 6;
+    pub const RESOURCE_LIMIT_EXCEEDED: u64 = 7;
+    pub const INVALID_COMMAND: u64 = 8;
+    pub const FUNCTION_NOT_FOUND: u64 = 9;
+    pub const UNKNOWN_RECIPIENT: u64 = 10;
+    pub const OBJECT_ALREADY_EXISTS: u64 = 11;
+    pub const INVALID_OWNERSHIP_TRANSITION: u64 = 12;
+    pub const READ_ONLY_VIOLATION: u64 = 13;
+    pub const CREATION_QUOTA_EXCEEDED: u64 = 14;
+    pub const INSUFFICIENT_BALANCE: u64 = 15;
+    pub const OBJECT_WRAPPED: u64 = 16;
+    pub const NOT_WRAPPED: u64 = 17;
+    pub const SCHEMA_MISMATCH: u64 = 18;
+    pub const INSUFFICIENT_GAS: u64 = 19;
+    pub const CONDITION_FAILED: u64 = 20;
+}
+
+/// Native functions the executor knows how to run.
+pub const BUILTIN_FUNCTIONS: &[(&str, &str)] = &[
+    ("coin", "transfer"),
+    ("coin", "mint"),
+    ("coin", "burn"),
+    ("object", "approve"),
+    ("object", "revoke"),
+    ("transfer", "freeze_object"),
+    ("transfer", "share_object"),
+];
+
+pub fn is_builtin_function(module: &str, function: &str) -> bool {
+    BUILTIN_FUNCTIONS
+        .iter()
+        .any(|(m, f)| *m == module && *f == function)
+}
+
+/// How calls to functions outside [`BUILTIN_FUNCTIONS`] are handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DispatchPolicy {
+    /// Unknown functions abort with `FUNCTION_NOT_FOUND`.
+    #[default]
+    Strict,
+    /// Unknown functions succeed with a generic result. Handy for demos.
+    Lenient,
+}
+
+/// Which addresses may receive transferred objects.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecipientPolicy {
+    #[default]
+    AnyAddress,
+    /// The recipient must already own at least one object.
+    MustExist,
+}
+
+/// How the commands of a batch are run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BatchExecution {
+    /// One after another, in order.
+    #[default]
+    Sequential,
+    /// Commands touching disjoint objects are polled together on the
+    /// executing task, so one waiting on the store lets the others make
+    /// progress. Nothing runs on another thread; this saves store round
+    /// trips, not CPU time.
+    Interleaved,
+}
+
+/// Tunables for the executor.
+#[derive(Debug, Clone, Default)]
+pub struct VmConfig {
+    /// `MoveStruct` type names that `Migrate` transactions may rewrite.
+    pub migratable_types: HashSet<String>,
+    /// Field schemas checked whenever a struct is created or migrated.
+    pub type_schemas: TypeSchemas,
+    /// Maximum object reads per transaction; unlimited when `None`.
+    pub max_object_reads: Option<u64>,
+    /// Maximum object writes per transaction; unlimited when `None`.
+    pub max_object_writes: Option<u64>,
+    /// Maximum objects a transaction, or a whole batch, may create;
+    /// unlimited when `None`.
+    pub max_objects_created: Option<u64>,
+    pub dispatch: DispatchPolicy,
+    pub recipient_policy: RecipientPolicy,
+    pub batch_execution: BatchExecution,
+    pub gas_schedule: GasSchedule,
+    /// Operators allowed to transfer objects on their owners' behalf. Only
+    /// owners may transfer when `None`.
+    pub approvals: Option<std::sync::Arc<dyn OperatorApprovals>>,
+    /// Delete coins left with a zero balance instead of persisting them
+    /// empty. The gas coin is always kept.
+    pub delete_empty_coins: bool,
+    #[cfg(feature = "fault-injection")]
+    pub fault_injector: Option<std::sync::Arc<FaultInjector>>,
+}
+
+/// An abort raised part-way through execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VmAbort {
+    pub code: u64,
+    pub message: String,
+}
+
+impl VmAbort {
+    pub fn new(code: u64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct ExecutionResult {
+    pub status: ExecutionStatus,
     pub gas_used: u64,
+    /// Objects written by the transaction. Only populated on success.
     pub touched_objects: Vec<SuiObject>,
+    /// Ids of the `touched_objects` the transaction created rather than
+    /// changed. Only populated on success.
+    pub created: Vec<ObjectID>,
+    /// Objects removed by the transaction. Only populated on success.
+    pub deleted: Vec<ObjectID>,
     pub logs: Vec<String>,
+    pub events: Vec<ObjectEvent>,
+    /// Values left on the interpreter stack when the call returned.
+    pub return_values: Vec<Value>,
+    /// Object reads performed, including ones that hit staged writes.
+    pub reads: u64,
+    /// Object writes and deletions performed.
+    pub writes: u64,
+    /// Per-command outcomes when the transaction was a batch.
+    pub batch: Option<BatchResult>,
+    /// Pre-execution version of each object read from the store, by id.
+    pub input_versions: Vec<(ObjectID, u64)>,
+    /// Pre-execution owner and balance of every coin read from the store,
+    /// the gas coin included, by id.
+    pub input_coins: Vec<(ObjectID, Owner, u64)>,
+    /// Coin balance created by minting. Only populated on success.
+    pub minted: u64,
+    /// Coin balance destroyed by burning. Only populated on success.
+    pub burned: u64,
+    /// Operator approvals granted or revoked, in call order. Only populated
+    /// on success.
+    pub approvals: Vec<ApprovalChange>,
+    /// Unused gas budget, at the gas price, credited back to the gas coin.
+    pub gas_refund: u64,
+    /// The gas coin after charging, kept out of `touched_objects` because it
+    /// is written even when the transaction aborts.
+    pub gas_coin: Option<SuiObject>,
+    /// Instruction-level trace, only collected by
+    /// [`MoveVMExecutor::execute_traced`].
+    pub trace: Option<ExecutionTrace>,
+}
+
+impl ExecutionResult {
+    pub fn abort(gas_used: u64, code: u64, message: impl Into<String>) -> Self {
+        let message = message.into();
+        Self {
+            status: ExecutionStatus::Failure {
+                code,
+                message: message.clone(),
+            },
+            gas_used,
+            logs: vec![format!("Aborted with code {}: {}", code, message)],
+            ..Default::default()
+        }
+    }
+
+    fn from_abort(gas_used: u64, abort: VmAbort) -> Self {
+        Self::abort(gas_used, abort.code, abort.message)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -28,25 +221,323 @@ pub enum MoveInstruction {
 
 pub struct MoveVMExecutor {
     object_store: Option<Box<dyn ObjectStore>>,
+    config: VmConfig,
 }
 
 impl MoveVMExecutor {
     pub fn new() -> Self {
         Self {
             object_store: None,
+            config: VmConfig::default(),
         }
     }
 
     pub fn with_object_store(store: Box<dyn ObjectStore>) -> Self {
         Self {
             object_store: Some(store),
+            config: VmConfig::default(),
         }
     }
 
+    pub fn with_config(mut self, config: VmConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn config(&self) -> &VmConfig {
+        &self.config
+    }
+
+    /// Executes the request against a staged view of the store. The store
+    /// itself is never written; on success the result lists the objects to
+    /// persist and delete.
     pub async fn execute(&self, request: &ExecutionRequest) -> ExecutionResult {
-        match &request.tx.payload.kind {
+        self.execute_with_inputs(request, &[]).await
+    }
+
+    /// Like [`execute`](Self::execute), but also records every interpreted
+    /// instruction with the stack and gas after it in `result.trace`.
+    /// Meant for debugging; tracing clones the stack at every step.
+    pub async fn execute_traced(&self, request: &ExecutionRequest) -> ExecutionResult {
+        self.run(request, &[], true).await
+    }
+
+    /// Like [`execute`](Self::execute), but reads `inputs` from memory
+    /// instead of the store. Callers that have already loaded the declared
+    /// inputs, e.g. with one batched read, pass them here to save the VM a
+    /// round-trip per object.
+    pub async fn execute_with_inputs(&self, request: &ExecutionRequest, inputs: &[SuiObject]) -> ExecutionResult {
+        self.run(request, inputs, false).await
+    }
+
+    async fn run(&self, request: &ExecutionRequest, inputs: &[SuiObject], trace: bool) -> ExecutionResult {
+        #[cfg(feature = "fault-injection")]
+        if let Some(abort) = self
+            .config
+            .fault_injector
+            .as_ref()
+            .and_then(|injector| injector.check(FaultStage::Execution, request))
+        {
+            return ExecutionResult::from_abort(0, abort);
+        }
+
+        let payload = &request.tx.payload;
+        let signer = request.tx.signer.as_str();
+        let gas_coin = match self.load_gas_coin(signer, payload, inputs).await {
+            Ok(gas_coin) => gas_coin,
+            Err(abort) => return ExecutionResult::from_abort(0, abort),
+        };
+        let ctx = ExecutionContext::new(self.object_store.as_deref(), &self.config)
+            .with_digest(request.digest.clone())
+            .with_read_only(payload.read_only)
+            .with_prefetched(inputs.iter().cloned())
+            .with_tracing(trace);
+
+        let mut result = match &payload.kind {
+            TransactionKind::Batch { commands } => self.execute_batch(&ctx, signer, commands).await,
+            kind => self.execute_command(&ctx, signer, kind, &[]).await,
+        };
+
+        result.reads = ctx.reads();
+        result.writes = ctx.writes();
+        result.input_versions = ctx.input_versions();
+        result.input_coins = ctx.input_coins();
+        // The gas coin is loaded before execution, outside the context.
+        if let Some(coin) = &gas_coin {
+            if let ObjectData::Coin { balance } = coin.data {
+                if !result.input_coins.iter().any(|(id, _, _)| *id == coin.id) {
+                    result.input_coins.push((coin.id.clone(), coin.owner.clone(), balance));
+                    result.input_coins.sort_by(|a, b| a.0 .0.cmp(&b.0 .0));
+                }
+            }
+        }
+        result.trace = ctx.trace();
+        if result.status.is_success() {
+            (result.minted, result.burned) = (ctx.minted(), ctx.burned());
+            result.approvals = ctx.approval_changes();
+        }
+        result.gas_used += ctx.storage_gas();
+        if payload.read_only {
+            result.gas_used = 0;
+        }
+        if result.status.is_success() {
+            let changes = ctx.into_changes();
+            result.touched_objects = changes.written;
+            result.created = changes.created;
+            result.deleted = changes.deleted;
+            if self.config.delete_empty_coins {
+                Self::delete_empty_coins(&mut result, payload);
+            }
+        }
+        if let Some(gas_coin) = gas_coin {
+            Self::settle_gas(&mut result, gas_coin, payload);
+        }
+        result
+    }
+
+    /// Turns writes of zero-balance coins into deletions. A coin created
+    /// empty by this transaction is simply never written.
+    fn delete_empty_coins(result: &mut ExecutionResult, payload: &TransactionPayload) {
+        let (empty, kept): (Vec<SuiObject>, Vec<SuiObject>) =
+            std::mem::take(&mut result.touched_objects)
+                .into_iter()
+                .partition(|object| {
+                    matches!(object.data, ObjectData::Coin { balance: 0 })
+                        && payload.gas_payment.as_ref() != Some(&object.id)
+                });
+        result.touched_objects = kept;
+        for coin in empty {
+            if result.input_versions.iter().any(|(id, _)| *id == coin.id) {
+                result.logs.push(format!("Deleted empty coin {}", coin.id.0));
+                result.deleted.push(coin.id);
+            } else {
+                result.created.retain(|id| *id != coin.id);
+            }
+        }
+    }
+
+    /// Loads the gas coin named by the payload, checking that the signer
+    /// owns it and that it covers the whole budget at the gas price.
+    async fn load_gas_coin(
+        &self,
+        signer: &str,
+        payload: &TransactionPayload,
+        inputs: &[SuiObject],
+    ) -> Result<Option<SuiObject>, VmAbort> {
+        let (Some(id), Some(store)) = (&payload.gas_payment, self.object_store.as_deref()) else {
+            return Ok(None);
+        };
+        let prefetched = inputs.iter().find(|object| &object.id == id).cloned();
+        let coin = match prefetched {
+            Some(coin) => Some(coin),
+            None => store
+                .get_object(&id.0)
+                .await
+                .map_err(|e| VmAbort::new(abort_codes::STORE_ERROR, e.to_string()))?,
+        };
+        let coin = coin
+            .ok_or_else(|| VmAbort::new(abort_codes::OBJECT_NOT_FOUND, format!("gas coin {} not found", id.0)))?;
+        ensure_not_wrapped(&coin)?;
+        if !coin.owner.matches_address(signer) {
+            return Err(VmAbort::new(
+                abort_codes::NOT_OWNER,
+                format!("{} does not own gas coin {}", signer, id.0),
+            ));
+        }
+        let ObjectData::Coin { balance } = coin.data else {
+            return Err(VmAbort::new(
+                abort_codes::INVALID_OBJECT_TYPE,
+                format!("gas payment {} is not a coin", id.0),
+            ));
+        };
+        let required = payload.gas_budget.saturating_mul(payload.gas_price);
+        if balance < required {
+            return Err(VmAbort::new(
+                abort_codes::INSUFFICIENT_GAS,
+                format!("gas coin {} holds {} but the budget needs {}", id.0, balance, required),
+            ));
+        }
+        Ok(Some(coin))
+    }
+
+    /// Charges the gas used, capped at the budget, to the gas coin; the rest
+    /// of the budget is the refund. Works from the coin as the transaction
+    /// left it, or as loaded if the transaction did not touch it. A gas coin
+    /// the transaction deleted is not charged.
+    fn settle_gas(result: &mut ExecutionResult, loaded: SuiObject, payload: &TransactionPayload) {
+        if result.deleted.contains(&loaded.id) {
+            return;
+        }
+        let charged = result.gas_used.min(payload.gas_budget);
+        result.gas_refund = (payload.gas_budget - charged).saturating_mul(payload.gas_price);
+
+        let mut coin = match result.touched_objects.iter().position(|object| object.id == loaded.id) {
+            Some(index) => result.touched_objects.remove(index),
+            None => SuiObject {
+                version: loaded.version + 1,
+                ..loaded
+            },
+        };
+        if let ObjectData::Coin { balance } = &mut coin.data {
+            *balance = balance.saturating_sub(charged.saturating_mul(payload.gas_price));
+        }
+        result.gas_coin = Some(coin);
+    }
+
+    /// Runs a batch against one context. The first abort aborts the whole
+    /// batch, discarding every staged change; the attached `BatchResult`
+    /// still reports which command failed and why. Each command's return
+    /// values are kept so later commands can reference them through
+    /// `Argument::Result`.
+    ///
+    /// Under `BatchExecution::Interleaved`, commands are grouped into levels
+    /// by `execution_levels` and the commands of a level are interleaved on
+    /// this task; results are merged in command order, and if several
+    /// commands in a level abort the lowest index is reported.
+    async fn execute_batch(&self, ctx: &ExecutionContext<'_>, signer: &str, commands: &[TransactionKind]) -> ExecutionResult {
+        let levels = match self.batch_execution() {
+            BatchExecution::Sequential => (0..commands.len()).collect(),
+            BatchExecution::Interleaved => execution_levels(commands),
+        };
+        let mut waves: Vec<Vec<usize>> = Vec::new();
+        for (index, level) in levels.into_iter().enumerate() {
+            if waves.len() <= level {
+                waves.resize_with(level + 1, Vec::new);
+            }
+            waves[level].push(index);
+        }
+
+        let mut combined = ExecutionResult::default();
+        let mut outputs: Vec<Vec<Value>> = vec![Vec::new(); commands.len()];
+        let mut executed = vec![false; commands.len()];
+
+        for wave in waves {
+            let results = {
+                let outputs = &outputs;
+                join_all(wave.iter().map(|&index| {
+                    self.execute_command(ctx, signer, &commands[index], &outputs[..index])
+                }))
+                .await
+            };
+
+            let mut failure = None;
+            for (index, result) in wave.into_iter().zip(results) {
+                executed[index] = true;
+                combined.gas_used += result.gas_used;
+                if let ExecutionStatus::Failure { code, message } = result.status {
+                    failure.get_or_insert((index, code, message));
+                    continue;
+                }
+                combined.logs.extend(result.logs);
+                combined.events.extend(result.events);
+                outputs[index] = result.return_values;
+            }
+
+            if let Some((index, code, message)) = failure {
+                let commands = (0..commands.len())
+                    .map(|i| CommandResult {
+                        index: i,
+                        executed: executed[i],
+                        status: if i == index {
+                            CommandStatus::Aborted {
+                                code,
+                                message: message.clone(),
+                            }
+                        } else {
+                            CommandStatus::RolledBack
+                        },
+                    })
+                    .collect();
+                return ExecutionResult {
+                    batch: Some(BatchResult { commands }),
+                    ..ExecutionResult::abort(
+                        combined.gas_used,
+                        code,
+                        format!("batch command {} failed: {}", index, message),
+                    )
+                };
+            }
+        }
+
+        combined.return_values = outputs.into_iter().flatten().collect();
+        combined.batch = Some(BatchResult {
+            commands: (0..commands.len())
+                .map(|index| CommandResult {
+                    index,
+                    executed: true,
+                    status: CommandStatus::Success,
+                })
+                .collect(),
+        });
+        combined
+    }
+
+    /// Interleaved batches fall back to sequential execution when a command's
+    /// outcome could depend on what runs beside it: recipient checks look
+    /// at every object, and read/write/creation limits are shared counters.
+    fn batch_execution(&self) -> BatchExecution {
+        let config = &self.config;
+        let order_sensitive = config.recipient_policy == RecipientPolicy::MustExist
+            || config.max_object_reads.is_some()
+            || config.max_object_writes.is_some()
+            || config.max_objects_created.is_some();
+        if order_sensitive {
+            BatchExecution::Sequential
+        } else {
+            config.batch_execution
+        }
+    }
+
+    async fn execute_command(
+        &self,
+        ctx: &ExecutionContext<'_>,
+        signer: &str,
+        kind: &TransactionKind,
+        prior_outputs: &[Vec<Value>],
+    ) -> ExecutionResult {
+        match kind {
             TransactionKind::Transfer { object, recipient } => {
-                self.execute_transfer(object, recipient).await
+                self.execute_transfer(ctx, signer, object, recipient).await
             }
             TransactionKind::Call {
                 package,
@@ -54,29 +545,425 @@ impl MoveVMExecutor {
                 function,
                 arguments,
             } => {
-                self.execute_move_call(package, module, function, arguments)
+                let arguments = match self.resolve_arguments(ctx, arguments, prior_outputs).await {
+                    Ok(arguments) => arguments,
+                    Err(abort) => return ExecutionResult::from_abort(0, abort),
+                };
+                self.execute_move_call(ctx, signer, package, module, function, arguments)
+                    .await
+            }
+            TransactionKind::Migrate { object, new_fields } => {
+                self.execute_migrate(ctx, signer, object, new_fields)
+                    .await
+            }
+            TransactionKind::ChangeOwner { object, new_owner } => {
+                self.execute_change_owner(ctx, signer, object, new_owner).await
+            }
+            TransactionKind::Wrap { object, wrapper } => self.execute_wrap(ctx, signer, object, wrapper).await,
+            TransactionKind::Unwrap { object } => self.execute_unwrap(ctx, signer, object).await,
+            TransactionKind::Conditional { condition, then } => {
+                let gas_used = 100;
+                match failed_condition(ctx, condition).await {
+                    Ok(None) => {}
+                    Ok(Some(failed)) => {
+                        return ExecutionResult::abort(
+                            gas_used,
+                            abort_codes::CONDITION_FAILED,
+                            format!("condition not met: {:?}", failed),
+                        )
+                    }
+                    Err(abort) => return ExecutionResult::from_abort(gas_used, abort),
+                }
+                let result = Box::pin(self.execute_command(ctx, signer, then, prior_outputs)).await;
+                ExecutionResult {
+                    gas_used: gas_used + result.gas_used,
+                    ..result
+                }
+            }
+            TransactionKind::Airdrop { source, distribution } => {
+                self.execute_airdrop(ctx, signer, source, distribution).await
+            }
+            TransactionKind::TransferToSender { object } => {
+                self.execute_transfer_to_sender(ctx, signer, object, prior_outputs)
                     .await
             }
+            TransactionKind::Batch { .. } => {
+                ExecutionResult::abort(0, abort_codes::INVALID_COMMAND, "batches cannot be nested")
+            }
+        }
+    }
+
+    async fn execute_change_owner(
+        &self,
+        ctx: &ExecutionContext<'_>,
+        signer: &str,
+        object: &ObjectID,
+        new_owner: &Owner,
+    ) -> ExecutionResult {
+        let gas_used = 500;
+        if let Owner::Address(recipient) = new_owner {
+            if let Err(abort) = self.check_recipient(ctx, recipient).await {
+                return ExecutionResult::from_abort(gas_used, abort);
+            }
+        }
+
+        match self.change_owner(ctx, signer, &object.0, new_owner.clone()).await {
+            Ok(Some(event)) => ExecutionResult {
+                gas_used,
+                logs: vec![format!("Owner of {} set to {:?}", object.0, new_owner)],
+                events: vec![event],
+                ..Default::default()
+            },
+            Ok(None) => ExecutionResult::abort(
+                gas_used,
+                abort_codes::OBJECT_NOT_FOUND,
+                format!("object {} not found", object.0),
+            ),
+            Err(abort) => ExecutionResult::from_abort(gas_used, abort),
+        }
+    }
+
+    async fn execute_wrap(
+        &self,
+        ctx: &ExecutionContext<'_>,
+        signer: &str,
+        object: &ObjectID,
+        wrapper: &ObjectID,
+    ) -> ExecutionResult {
+        let gas_used = 400;
+        match self.wrap_object(ctx, signer, object, wrapper).await {
+            Ok(event) => ExecutionResult {
+                gas_used,
+                logs: vec![format!("Wrapped {} in {}", object.0, wrapper.0)],
+                events: vec![event],
+                ..Default::default()
+            },
+            Err(abort) => ExecutionResult::from_abort(gas_used, abort),
+        }
+    }
+
+    async fn wrap_object(
+        &self,
+        ctx: &ExecutionContext<'_>,
+        signer: &str,
+        object: &ObjectID,
+        wrapper: &ObjectID,
+    ) -> Result<ObjectEvent, VmAbort> {
+        if object == wrapper {
+            return Err(VmAbort::new(
+                abort_codes::INVALID_OWNERSHIP_TRANSITION,
+                format!("object {} cannot wrap itself", object.0),
+            ));
+        }
+        let mut inner = Self::load_owned(ctx, signer, object).await?;
+        Self::load_owned(ctx, signer, wrapper).await?;
+
+        let event = ObjectEvent::OwnershipChanged {
+            object: inner.id.clone(),
+            from: inner.owner.clone(),
+            to: Owner::Object(wrapper.clone()),
+        };
+        inner.owner = Owner::Object(wrapper.clone());
+        inner.wrapped_by = Some(wrapper.clone());
+        inner.version += 1;
+        ctx.put_object(inner)?;
+        Ok(event)
+    }
+
+    async fn execute_unwrap(&self, ctx: &ExecutionContext<'_>, signer: &str, object: &ObjectID) -> ExecutionResult {
+        let gas_used = 400;
+        match self.unwrap_object(ctx, signer, object).await {
+            Ok(event) => ExecutionResult {
+                gas_used,
+                logs: vec![format!("Unwrapped {}", object.0)],
+                events: vec![event],
+                ..Default::default()
+            },
+            Err(abort) => ExecutionResult::from_abort(gas_used, abort),
+        }
+    }
+
+    async fn unwrap_object(&self, ctx: &ExecutionContext<'_>, signer: &str, object: &ObjectID) -> Result<ObjectEvent, VmAbort> {
+        let mut inner = ctx
+            .get_object(&object.0)
+            .await?
+            .ok_or_else(|| VmAbort::new(abort_codes::OBJECT_NOT_FOUND, format!("object {} not found", object.0)))?;
+        let Some(wrapper) = inner.wrapped_by.clone() else {
+            return Err(VmAbort::new(
+                abort_codes::NOT_WRAPPED,
+                format!("object {} is not wrapped", object.0),
+            ));
+        };
+        Self::load_owned(ctx, signer, &wrapper).await?;
+
+        let new_owner = Owner::Address(signer.to_string());
+        let event = ObjectEvent::OwnershipChanged {
+            object: inner.id.clone(),
+            from: inner.owner.clone(),
+            to: new_owner.clone(),
+        };
+        inner.owner = new_owner;
+        inner.wrapped_by = None;
+        inner.version += 1;
+        ctx.put_object(inner)?;
+        Ok(event)
+    }
+
+    /// Loads an unwrapped object owned by `signer`.
+    async fn load_owned(ctx: &ExecutionContext<'_>, signer: &str, id: &ObjectID) -> Result<SuiObject, VmAbort> {
+        let object = ctx
+            .get_object(&id.0)
+            .await?
+            .ok_or_else(|| VmAbort::new(abort_codes::OBJECT_NOT_FOUND, format!("object {} not found", id.0)))?;
+        ensure_not_wrapped(&object)?;
+        if !object.owner.matches_address(signer) {
+            return Err(VmAbort::new(
+                abort_codes::NOT_OWNER,
+                format!("{} does not own {}", signer, id.0),
+            ));
+        }
+        Ok(object)
+    }
+
+    /// Splits `source` into one coin per recipient. The amounts and the
+    /// recipients are checked in full before any coin is created, and the
+    /// source keeps the change.
+    async fn execute_airdrop(
+        &self,
+        ctx: &ExecutionContext<'_>,
+        signer: &str,
+        source: &ObjectID,
+        distribution: &[(String, u64)],
+    ) -> ExecutionResult {
+        let gas_used = 100 + 50 * distribution.len() as u64;
+
+        let mut coin = match ctx.get_object(&source.0).await {
+            Ok(Some(coin)) => coin,
+            Ok(None) => {
+                return ExecutionResult::abort(
+                    gas_used,
+                    abort_codes::OBJECT_NOT_FOUND,
+                    format!("object {} not found", source.0),
+                )
+            }
+            Err(abort) => return ExecutionResult::from_abort(gas_used, abort),
+        };
+        if let Err(abort) = ensure_not_wrapped(&coin) {
+            return ExecutionResult::from_abort(gas_used, abort);
+        }
+        if !coin.owner.matches_address(signer) {
+            return ExecutionResult::abort(
+                gas_used,
+                abort_codes::NOT_OWNER,
+                format!("{} does not own {}", signer, source.0),
+            );
+        }
+        let ObjectData::Coin { balance } = &mut coin.data else {
+            return ExecutionResult::abort(
+                gas_used,
+                abort_codes::INVALID_OBJECT_TYPE,
+                format!("object {} is not a coin", source.0),
+            );
+        };
+
+        if distribution.iter().any(|(_, amount)| *amount == 0) {
+            return ExecutionResult::abort(gas_used, abort_codes::INVALID_COMMAND, "airdrop amounts must be positive");
+        }
+        let total = distribution
+            .iter()
+            .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount));
+        let total = match total {
+            Some(total) if total <= *balance => total,
+            _ => {
+                return ExecutionResult::abort(
+                    gas_used,
+                    abort_codes::INSUFFICIENT_BALANCE,
+                    format!("airdrop exceeds the balance of {} ({})", source.0, balance),
+                )
+            }
+        };
+        for (recipient, _) in distribution {
+            if let Err(abort) = self.check_recipient(ctx, recipient).await {
+                return ExecutionResult::from_abort(gas_used, abort);
+            }
+        }
+        *balance -= total;
+        coin.version += 1;
+        if let Err(abort) = ctx.put_object(coin) {
+            return ExecutionResult::from_abort(gas_used, abort);
+        }
+
+        let mut return_values = Vec::with_capacity(distribution.len());
+        for (recipient, amount) in distribution {
+            let airdropped = SuiObject::new(
+                ctx.fresh_id(),
+                Owner::Address(recipient.clone()),
+                ObjectData::Coin { balance: *amount },
+            );
+            return_values.push(Value::String(airdropped.id.0.clone()));
+            if let Err(abort) = ctx.create_object(airdropped).await {
+                return ExecutionResult::from_abort(gas_used, abort);
+            }
+        }
+
+        ExecutionResult {
+            gas_used,
+            logs: vec![format!(
+                "Airdropped {} from {} to {} recipients",
+                total,
+                source.0,
+                distribution.len()
+            )],
+            return_values,
+            ..Default::default()
+        }
+    }
+
+    async fn execute_transfer_to_sender(
+        &self,
+        ctx: &ExecutionContext<'_>,
+        signer: &str,
+        object: &Argument,
+        prior_outputs: &[Vec<Value>],
+    ) -> ExecutionResult {
+        let gas_used = 500;
+        let resolved = match self
+            .resolve_arguments(ctx, std::slice::from_ref(object), prior_outputs)
+            .await
+        {
+            Ok(resolved) => resolved,
+            Err(abort) => return ExecutionResult::from_abort(gas_used, abort),
+        };
+        let Some(object_id) = resolved.first().and_then(object_id_of) else {
+            return ExecutionResult::abort(gas_used, abort_codes::INVALID_COMMAND, "argument is not an object");
+        };
+
+        let obj = match ctx.get_object(object_id).await {
+            Ok(Some(obj)) => obj,
+            Ok(None) => {
+                return ExecutionResult::abort(
+                    gas_used,
+                    abort_codes::OBJECT_NOT_FOUND,
+                    format!("object {} not found", object_id),
+                )
+            }
+            Err(abort) => return ExecutionResult::from_abort(gas_used, abort),
+        };
+        // Objects created earlier in this transaction have no previous
+        // owner to ask; anything else needs the signer's authority.
+        if !ctx.was_created(object_id) {
+            if let Err(abort) = authorize_transfer(ctx, signer, &obj).await {
+                return ExecutionResult::from_abort(gas_used, abort);
+            }
+        }
+
+        match Self::set_owner(ctx, obj, Owner::Address(signer.to_string())) {
+            Ok(event) => ExecutionResult {
+                gas_used,
+                logs: vec![format!("Returned {} to sender {}", object_id, signer)],
+                events: vec![event],
+                ..Default::default()
+            },
+            Err(abort) => ExecutionResult::from_abort(gas_used, abort),
+        }
+    }
+
+    async fn execute_migrate(
+        &self,
+        ctx: &ExecutionContext<'_>,
+        signer: &str,
+        object: &ObjectID,
+        new_fields: &Value,
+    ) -> ExecutionResult {
+        let gas_used = 300;
+        let mut obj = match ctx.get_object(&object.0).await {
+            Ok(Some(obj)) => obj,
+            Ok(None) => {
+                return ExecutionResult::abort(
+                    gas_used,
+                    abort_codes::OBJECT_NOT_FOUND,
+                    format!("object {} not found", object.0),
+                )
+            }
+            Err(abort) => return ExecutionResult::from_abort(gas_used, abort),
+        };
+        if let Err(abort) = ensure_not_wrapped(&obj) {
+            return ExecutionResult::from_abort(gas_used, abort);
+        }
+
+        if !obj.owner.matches_address(signer) {
+            return ExecutionResult::abort(
+                gas_used,
+                abort_codes::NOT_OWNER,
+                format!("{} does not own {}", signer, object.0),
+            );
+        }
+
+        let ObjectData::MoveStruct { type_name, fields } = &mut obj.data else {
+            return ExecutionResult::abort(
+                gas_used,
+                abort_codes::INVALID_OBJECT_TYPE,
+                format!("object {} is not a Move struct", object.0),
+            );
+        };
+        if !self.config.migratable_types.contains(type_name.as_str()) {
+            return ExecutionResult::abort(
+                gas_used,
+                abort_codes::TYPE_NOT_MIGRATABLE,
+                format!("type {} is not migratable", type_name),
+            );
+        }
+        let log = format!("Migrated {} ({})", object.0, type_name);
+        *fields = new_fields.clone();
+        if let Err(reason) = self.config.type_schemas.validate(&obj.data) {
+            return ExecutionResult::abort(gas_used, abort_codes::SCHEMA_MISMATCH, reason);
+        }
+        obj.version += 1;
+
+        if let Err(abort) = ctx.put_object(obj) {
+            return ExecutionResult::from_abort(gas_used, abort);
+        }
+
+        ExecutionResult {
+            gas_used,
+            logs: vec![log],
+            ..Default::default()
         }
     }
 
-    async fn execute_transfer(&self, object: &ObjectID, recipient: &str) -> ExecutionResult {
-        let mut touched_objects = Vec::new();
+    async fn execute_transfer(
+        &self,
+        ctx: &ExecutionContext<'_>,
+        signer: &str,
+        object: &ObjectID,
+        recipient: &str,
+    ) -> ExecutionResult {
+        let mut events = Vec::new();
         let mut gas_used = 100;
 
-        if let Some(store) = &self.object_store {
-            if let Ok(Some(mut obj)) = store.get_object(&object.0).await {
-                obj.owner = Owner::Address(recipient.to_string());
-                obj.version += 1;
-                gas_used += 400;
-                if let Err(e) = store.put_object(obj.clone()).await {
-                    return ExecutionResult {
+        if let Err(abort) = self.check_recipient(ctx, recipient).await {
+            return ExecutionResult::from_abort(gas_used, abort);
+        }
+
+        if ctx.has_store() {
+            let loaded = match ctx.get_object(&object.0).await {
+                Ok(Some(loaded)) => loaded,
+                Ok(None) => {
+                    return ExecutionResult::abort(
                         gas_used,
-                        touched_objects: vec![],
-                        logs: vec![format!("Transfer failed: {}", e)],
-                    };
+                        abort_codes::OBJECT_NOT_FOUND,
+                        format!("object {} not found", object.0),
+                    )
                 }
-                touched_objects.push(obj);
+                Err(abort) => return ExecutionResult::from_abort(gas_used, abort),
+            };
+            if let Err(abort) = authorize_transfer(ctx, signer, &loaded).await {
+                return ExecutionResult::from_abort(gas_used, abort);
+            }
+            gas_used += 400;
+            match Self::set_owner(ctx, loaded, Owner::Address(recipient.to_string())) {
+                Ok(event) => events.push(event),
+                Err(abort) => return ExecutionResult::from_abort(gas_used, abort),
             }
         } else {
             let new_obj = SuiObject::new(
@@ -84,43 +971,181 @@ impl MoveVMExecutor {
                 Owner::Address(recipient.to_string()),
                 ObjectData::Coin { balance: 1 },
             );
-            touched_objects.push(new_obj);
+            if let Err(abort) = ctx.create_object(new_obj).await {
+                return ExecutionResult::from_abort(gas_used, abort);
+            }
             gas_used += 400;
         }
 
         ExecutionResult {
             gas_used,
             logs: vec![format!("Transfer executed: {} -> {}", object.0, recipient)],
-            touched_objects,
+            events,
+            ..Default::default()
+        }
+    }
+
+    async fn check_recipient(&self, ctx: &ExecutionContext<'_>, recipient: &str) -> Result<(), VmAbort> {
+        match self.config.recipient_policy {
+            RecipientPolicy::AnyAddress => Ok(()),
+            RecipientPolicy::MustExist => {
+                if ctx.owns_any(recipient).await? {
+                    Ok(())
+                } else {
+                    Err(VmAbort::new(
+                        abort_codes::UNKNOWN_RECIPIENT,
+                        format!("recipient {} does not own any objects", recipient),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Ownership rules shared by every command that moves objects:
+    ///
+    /// * wrapped objects only move by being unwrapped;
+    /// * immutable objects never change owner;
+    /// * shared objects stay shared;
+    /// * only address-owned objects can be frozen;
+    /// * an object cannot own itself.
+    ///
+    /// An object becoming shared records the version it is shared at as its
+    /// initial shared version.
+    fn check_owner_transition(object: &SuiObject, new_owner: &Owner) -> Result<(), VmAbort> {
+        ensure_not_wrapped(object)?;
+        let invalid = |reason: &str| {
+            Err(VmAbort::new(
+                abort_codes::INVALID_OWNERSHIP_TRANSITION,
+                format!("cannot move {} from {:?} to {:?}: {}", object.id.0, object.owner, new_owner, reason),
+            ))
+        };
+        match (&object.owner, new_owner) {
+            (Owner::Immutable, _) => Err(VmAbort::new(
+                abort_codes::IMMUTABLE_OBJECT,
+                format!("object {} is immutable", object.id.0),
+            )),
+            (Owner::Shared, Owner::Shared) => Ok(()),
+            (Owner::Shared, _) => invalid("shared objects stay shared"),
+            (Owner::Object(_), Owner::Immutable) => invalid("only address-owned objects can be frozen"),
+            (_, Owner::Object(parent)) if *parent == object.id => invalid("an object cannot own itself"),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reassigns an object's owner on behalf of `signer`, bumping its version
+    /// and producing the matching `OwnershipChanged` event. Returns `None`
+    /// when the object does not exist.
+    async fn change_owner(
+        &self,
+        ctx: &ExecutionContext<'_>,
+        signer: &str,
+        object_id: &str,
+        new_owner: Owner,
+    ) -> Result<Option<ObjectEvent>, VmAbort> {
+        match ctx.get_object(object_id).await? {
+            Some(obj) => {
+                authorize_transfer(ctx, signer, &obj).await?;
+                Self::set_owner(ctx, obj, new_owner).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Like [`change_owner`](Self::change_owner), for an object the caller
+    /// has already loaded.
+    fn set_owner(ctx: &ExecutionContext<'_>, mut obj: SuiObject, new_owner: Owner) -> Result<ObjectEvent, VmAbort> {
+        Self::check_owner_transition(&obj, &new_owner)?;
+
+        let event = ObjectEvent::OwnershipChanged {
+            object: obj.id.clone(),
+            from: obj.owner.clone(),
+            to: new_owner.clone(),
+        };
+        obj.version += 1;
+        if new_owner == Owner::Shared && obj.initial_shared_version.is_none() {
+            obj.initial_shared_version = Some(obj.version);
+        }
+        obj.owner = new_owner;
+        ctx.put_object(obj)?;
+        Ok(event)
+    }
+
+    /// Turns call arguments into plain values: objects are loaded from the
+    /// context, and `Result(i)` becomes command `i`'s single return value, or
+    /// an array of them if it returned several.
+    async fn resolve_arguments(
+        &self,
+        ctx: &ExecutionContext<'_>,
+        arguments: &[Argument],
+        prior_outputs: &[Vec<Value>],
+    ) -> Result<Vec<Value>, VmAbort> {
+        let mut resolved = Vec::with_capacity(arguments.len());
+        for argument in arguments {
+            let value = match argument {
+                Argument::Value(value) => value.clone(),
+                Argument::Object(id) => {
+                    let object = ctx.get_object(&id.0).await?.ok_or_else(|| {
+                        VmAbort::new(abort_codes::OBJECT_NOT_FOUND, format!("object {} not found", id.0))
+                    })?;
+                    ensure_not_wrapped(&object)?;
+                    serde_json::to_value(object)
+                        .map_err(|e| VmAbort::new(abort_codes::INVALID_COMMAND, e.to_string()))?
+                }
+                Argument::Result(index) => match prior_outputs.get(*index) {
+                    Some(values) if values.len() == 1 => values[0].clone(),
+                    Some(values) => Value::Array(values.clone()),
+                    None => {
+                        return Err(VmAbort::new(
+                            abort_codes::INVALID_COMMAND,
+                            format!("argument references result {} before it is available", index),
+                        ))
+                    }
+                },
+            };
+            resolved.push(value);
         }
+        Ok(resolved)
     }
 
     async fn execute_move_call(
         &self,
+        ctx: &ExecutionContext<'_>,
+        signer: &str,
         _package: &ObjectID,
         module: &str,
         function: &str,
-        arguments: &[Value],
+        arguments: Vec<Value>,
     ) -> ExecutionResult {
+        let arguments_log = format!("Arguments: {:?}", arguments);
         let bytecode = self.parse_move_call(module, function, arguments);
-        let result = self.interpret_bytecode(&bytecode).await;
+        let result = self.interpret_bytecode(ctx, signer, bytecode).await;
+        if !result.status.is_success() {
+            return ExecutionResult {
+                gas_used: result.gas_used + 200,
+                ..result
+            };
+        }
 
         ExecutionResult {
             gas_used: result.gas_used + 200,
             logs: vec![
                 format!("Move call: {}::{}", module, function),
-                format!("Arguments: {:?}", arguments),
+                arguments_log,
                 result.logs.join("; "),
             ],
-            touched_objects: result.touched_objects,
+            events: result.events,
+            return_values: result.return_values,
+            ..Default::default()
         }
     }
 
-    fn parse_move_call(&self, module: &str, function: &str, args: &[Value]) -> MoveBytecode {
-        let mut instructions = Vec::new();
+    /// Takes the arguments by value so that they move into the bytecode,
+    /// and from there onto the stack, without being cloned.
+    fn parse_move_call(&self, module: &str, function: &str, args: Vec<Value>) -> MoveBytecode {
+        let mut instructions = Vec::with_capacity(args.len() + 2);
 
         for arg in args {
-            instructions.push(MoveInstruction::LoadConst(arg.clone()));
+            instructions.push(MoveInstruction::LoadConst(arg));
         }
 
         instructions.push(MoveInstruction::CallFunction {
@@ -133,79 +1158,1509 @@ impl MoveVMExecutor {
         MoveBytecode { instructions }
     }
 
-    async fn interpret_bytecode(&self, bytecode: &MoveBytecode) -> ExecutionResult {
+    /// Consumes the bytecode: constants move onto the stack instead of being
+    /// copied, which matters for large arguments.
+    async fn interpret_bytecode(&self, ctx: &ExecutionContext<'_>, signer: &str, bytecode: MoveBytecode) -> ExecutionResult {
         let mut stack: Vec<Value> = Vec::new();
         let mut gas_used = 0;
         let mut logs = Vec::new();
-        let mut touched_objects = Vec::new();
+        let mut events = Vec::new();
+        let mut return_values = Vec::new();
 
-        for instruction in &bytecode.instructions {
-            gas_used += 50;
+        for (index, instruction) in bytecode.instructions.into_iter().enumerate() {
+            gas_used += self.config.gas_schedule.instructions.cost(&instruction);
+            let traced = ctx.is_tracing().then(|| format!("{:?}", instruction));
 
             match instruction {
                 MoveInstruction::LoadConst(value) => {
-                    stack.push(value.clone());
                     logs.push(format!("Loaded constant: {:?}", value));
+                    stack.push(value);
                 }
                 MoveInstruction::CallFunction { module, function } => {
-                    let result = self.execute_function(module, function, &stack).await;
-                    logs.extend(result.logs);
-                    touched_objects.extend(result.touched_objects);
+                    let result = self
+                        .execute_function(ctx, signer, &module, &function, &stack)
+                        .await;
                     gas_used += result.gas_used;
+                    if !result.status.is_success() {
+                        if let Some(instruction) = traced {
+                            ctx.record_trace(index, instruction, &stack, gas_used);
+                        }
+                        return ExecutionResult { gas_used, ..result };
+                    }
+                    logs.extend(result.logs);
+                    events.extend(result.events);
+                    stack.extend(result.return_values);
                 }
                 MoveInstruction::Transfer { object_id, recipient } => {
-                    if let Some(store) = &self.object_store {
-                        if let Ok(Some(mut obj)) = store.get_object(object_id).await {
-                            obj.owner = Owner::Address(recipient.clone());
-                            obj.version += 1;
-                            if store.put_object(obj.clone()).await.is_ok() {
-                                touched_objects.push(obj);
-                                logs.push(format!("Transferred {} to {}", object_id, recipient));
-                            }
+                    if let Err(abort) = self.check_recipient(ctx, &recipient).await {
+                        return ExecutionResult::from_abort(gas_used, abort);
+                    }
+                    let loaded = match ctx.get_object(&object_id).await {
+                        Ok(Some(loaded)) => loaded,
+                        Ok(None) => {
+                            return ExecutionResult::abort(
+                                gas_used,
+                                abort_codes::OBJECT_NOT_FOUND,
+                                format!("object {} not found", object_id),
+                            )
+                        }
+                        Err(abort) => return ExecutionResult::from_abort(gas_used, abort),
+                    };
+                    if let Err(abort) = authorize_transfer(ctx, signer, &loaded).await {
+                        return ExecutionResult::from_abort(gas_used, abort);
+                    }
+                    match Self::set_owner(ctx, loaded, Owner::Address(recipient.clone())) {
+                        Ok(event) => {
+                            events.push(event);
+                            logs.push(format!("Transferred {} to {}", object_id, recipient));
                         }
+                        Err(abort) => return ExecutionResult::from_abort(gas_used, abort),
                     }
                 }
                 MoveInstruction::Return => {
+                    if let Some(instruction) = traced {
+                        ctx.record_trace(index, instruction, &stack, gas_used);
+                    }
+                    return_values = std::mem::take(&mut stack);
                     logs.push("Function returned".to_string());
                     break;
                 }
             }
+            if let Some(instruction) = traced {
+                ctx.record_trace(index, instruction, &stack, gas_used);
+            }
         }
 
         ExecutionResult {
             gas_used,
-            touched_objects,
             logs,
+            events,
+            return_values,
+            ..Default::default()
         }
     }
 
-    async fn execute_function(&self, module: &str, function: &str, _stack: &[Value]) -> ExecutionResult {
+    async fn execute_function(
+        &self,
+        ctx: &ExecutionContext<'_>,
+        signer: &str,
+        module: &str,
+        function: &str,
+        stack: &[Value],
+    ) -> ExecutionResult {
         match (module, function) {
             ("coin", "transfer") => ExecutionResult {
                 gas_used: 300,
-                touched_objects: vec![],
                 logs: vec!["Coin transfer executed".to_string()],
+                ..Default::default()
             },
-            ("coin", "mint") => ExecutionResult {
-                gas_used: 200,
-                touched_objects: vec![SuiObject::new(
-                    ObjectID::random(),
+            ("coin", "mint") => {
+                let coin = SuiObject::new(
+                    ctx.fresh_id(),
                     Owner::Address("mint-address".to_string()),
                     ObjectData::Coin { balance: 1000 },
-                )],
-                logs: vec!["Coin minted".to_string()],
-            },
-            _ => ExecutionResult {
-                gas_used: 150,
-                touched_objects: vec![],
-                logs: vec![format!("Executed {module}::{function}")],
+                );
+                let coin_id = coin.id.0.clone();
+                if let Err(abort) = ctx.create_object(coin).await {
+                    return ExecutionResult::from_abort(200, abort);
+                }
+                ctx.record_mint(1000);
+                ExecutionResult {
+                    gas_used: 200,
+                    return_values: vec![Value::String(coin_id)],
+                    logs: vec!["Coin minted".to_string()],
+                    ..Default::default()
+                }
+            }
+            ("coin", "burn") => self.burn_coin(ctx, signer, stack).await,
+            ("object", "approve") => Self::set_approval(ctx, signer, stack, true).await,
+            ("object", "revoke") => Self::set_approval(ctx, signer, stack, false).await,
+            ("transfer", "freeze_object") => {
+                self.change_owner_from_stack(ctx, signer, stack, Owner::Immutable)
+                    .await
+            }
+            ("transfer", "share_object") => {
+                self.change_owner_from_stack(ctx, signer, stack, Owner::Shared)
+                    .await
+            }
+            _ => match self.config.dispatch {
+                DispatchPolicy::Strict => ExecutionResult::abort(
+                    150,
+                    abort_codes::FUNCTION_NOT_FOUND,
+                    format!("function {module}::{function} not found"),
+                ),
+                DispatchPolicy::Lenient => ExecutionResult {
+                    gas_used: 150,
+                    logs: vec![format!("Executed {module}::{function}")],
+                    ..Default::default()
+                },
             },
         }
     }
-}
 
-impl Default for MoveVMExecutor {
-    fn default() -> Self {
-        Self::new()
+    /// Backs `object::approve` and `object::revoke`. Takes the object id, or
+    /// `"*"` for all of the signer's objects, and the operator's address.
+    async fn set_approval(ctx: &ExecutionContext<'_>, signer: &str, stack: &[Value], approve: bool) -> ExecutionResult {
+        let gas_used = 100;
+        let (Some(target), Some(operator)) = (stack.first().and_then(object_id_of), stack.get(1).and_then(Value::as_str))
+        else {
+            return ExecutionResult::abort(gas_used, abort_codes::INVALID_COMMAND, "expected an object and an operator");
+        };
+        let scope = if target == "*" {
+            ApprovalScope::All
+        } else {
+            let id = ObjectID::new(target);
+            if let Err(abort) = Self::load_owned(ctx, signer, &id).await {
+                return ExecutionResult::from_abort(gas_used, abort);
+            }
+            ApprovalScope::Object(id)
+        };
+
+        let (owner, operator) = (signer.to_string(), operator.to_string());
+        let change = if approve {
+            ApprovalChange::Approve { owner, scope, operator }
+        } else {
+            ApprovalChange::Revoke { owner, scope, operator }
+        };
+        let log = format!("{:?}", change);
+        if let Err(abort) = ctx.record_approval(change) {
+            return ExecutionResult::from_abort(gas_used, abort);
+        }
+        ExecutionResult {
+            gas_used,
+            logs: vec![log],
+            ..Default::default()
+        }
+    }
+
+    /// Backs `coin::burn`: deletes a coin owned by the signer, taking its
+    /// balance out of the supply. Returns the burned amount.
+    async fn burn_coin(&self, ctx: &ExecutionContext<'_>, signer: &str, stack: &[Value]) -> ExecutionResult {
+        let gas_used = 200;
+        let Some(coin_id) = stack.first().and_then(object_id_of) else {
+            return ExecutionResult::abort(gas_used, abort_codes::INVALID_COMMAND, "missing coin argument");
+        };
+        let coin = match ctx.get_object(coin_id).await {
+            Ok(Some(coin)) => coin,
+            Ok(None) => {
+                return ExecutionResult::abort(
+                    gas_used,
+                    abort_codes::OBJECT_NOT_FOUND,
+                    format!("object {} not found", coin_id),
+                )
+            }
+            Err(abort) => return ExecutionResult::from_abort(gas_used, abort),
+        };
+        if let Err(abort) = ensure_not_wrapped(&coin) {
+            return ExecutionResult::from_abort(gas_used, abort);
+        }
+        if !coin.owner.matches_address(signer) {
+            return ExecutionResult::abort(
+                gas_used,
+                abort_codes::NOT_OWNER,
+                format!("{} does not own {}", signer, coin_id),
+            );
+        }
+        let ObjectData::Coin { balance } = coin.data else {
+            return ExecutionResult::abort(
+                gas_used,
+                abort_codes::INVALID_OBJECT_TYPE,
+                format!("object {} is not a coin", coin_id),
+            );
+        };
+
+        if let Err(abort) = ctx.delete_object(&coin.id) {
+            return ExecutionResult::from_abort(gas_used, abort);
+        }
+        ctx.record_burn(balance);
+        ExecutionResult {
+            gas_used,
+            return_values: vec![Value::from(balance)],
+            logs: vec![format!("Burned {} ({})", coin_id, balance)],
+            ..Default::default()
+        }
+    }
+
+    /// Backs `transfer::freeze_object` / `transfer::share_object`, which take
+    /// the target object, or its id, as their first argument. Only the
+    /// object's owner may freeze or share it.
+    async fn change_owner_from_stack(
+        &self,
+        ctx: &ExecutionContext<'_>,
+        signer: &str,
+        stack: &[Value],
+        new_owner: Owner,
+    ) -> ExecutionResult {
+        let gas_used = 250;
+        let Some(object_id) = stack.first().and_then(object_id_of) else {
+            return ExecutionResult::abort(gas_used, abort_codes::INVALID_COMMAND, "missing object argument");
+        };
+
+        let obj = match ctx.get_object(object_id).await {
+            Ok(Some(obj)) => obj,
+            Ok(None) => {
+                return ExecutionResult::abort(
+                    gas_used,
+                    abort_codes::OBJECT_NOT_FOUND,
+                    format!("object {} not found", object_id),
+                )
+            }
+            Err(abort) => return ExecutionResult::from_abort(gas_used, abort),
+        };
+        if let Err(abort) = ensure_not_wrapped(&obj) {
+            return ExecutionResult::from_abort(gas_used, abort);
+        }
+        if !obj.owner.matches_address(signer) {
+            return ExecutionResult::abort(
+                gas_used,
+                abort_codes::NOT_OWNER,
+                format!("{} does not own {}", signer, object_id),
+            );
+        }
+
+        match Self::set_owner(ctx, obj, new_owner.clone()) {
+            Ok(event) => ExecutionResult {
+                gas_used,
+                logs: vec![format!("Owner of {} set to {:?}", object_id, new_owner)],
+                events: vec![event],
+                ..Default::default()
+            },
+            Err(abort) => ExecutionResult::from_abort(gas_used, abort),
+        }
+    }
+}
+
+/// Rejects any use of an object that is currently wrapped.
+fn ensure_not_wrapped(object: &SuiObject) -> Result<(), VmAbort> {
+    match &object.wrapped_by {
+        Some(wrapper) => Err(VmAbort::new(
+            abort_codes::OBJECT_WRAPPED,
+            format!("object {} is wrapped in {}", object.id.0, wrapper.0),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// The first condition in `condition` that does not hold against the state
+/// the transaction sees, its own staged writes included, or `None` if all
+/// of them hold.
+async fn failed_condition<'c>(
+    ctx: &ExecutionContext<'_>,
+    condition: &'c StateCondition,
+) -> Result<Option<&'c StateCondition>, VmAbort> {
+    let mut pending = vec![condition];
+    while let Some(condition) = pending.pop() {
+        let holds = match condition {
+            StateCondition::All(conditions) => {
+                pending.extend(conditions.iter().rev());
+                continue;
+            }
+            StateCondition::Exists { object } => ctx.get_object(&object.0).await?.is_some(),
+            StateCondition::Absent { object } => ctx.get_object(&object.0).await?.is_none(),
+            StateCondition::OwnedBy { object, owner } => {
+                ctx.get_object(&object.0).await?.is_some_and(|current| match owner {
+                    Owner::Address(address) => current.owner.matches_address(address),
+                    owner => current.owner == *owner,
+                })
+            }
+            StateCondition::BalanceAtLeast { object, amount } => ctx
+                .get_object(&object.0)
+                .await?
+                .is_some_and(|current| matches!(current.data, ObjectData::Coin { balance } if balance >= *amount)),
+        };
+        if !holds {
+            return Ok(Some(condition));
+        }
+    }
+    Ok(None)
+}
+
+/// Owned objects may only be transferred by their owner or by an operator
+/// the owner has approved. Object-owned objects belong to whoever owns the
+/// top of their chain of parents; a chain ending in a shared or immutable
+/// object has no owner who could authorize the move. Shared and immutable
+/// objects are left to `check_owner_transition`, and missing objects to the
+/// caller.
+async fn authorize_transfer(ctx: &ExecutionContext<'_>, signer: &str, object: &SuiObject) -> Result<(), VmAbort> {
+    let root = match &object.owner {
+        Owner::Address(_) => object.owner.clone(),
+        Owner::Object(_) => root_owner(ctx, object).await?,
+        Owner::Shared | Owner::Immutable => return Ok(()),
+    };
+    match root.as_address() {
+        Some(owner) if root.matches_address(signer) || ctx.is_approved(owner, &object.id, signer) => Ok(()),
+        _ => Err(VmAbort::new(
+            abort_codes::NOT_OWNER,
+            format!("{} neither owns {} nor is an approved operator", signer, object.id.0),
+        )),
+    }
+}
+
+/// The owner at the top of `object`'s chain of parent objects.
+async fn root_owner(ctx: &ExecutionContext<'_>, object: &SuiObject) -> Result<Owner, VmAbort> {
+    let mut owner = object.owner.clone();
+    let mut visited = HashSet::new();
+    while let Owner::Object(parent) = owner {
+        if !visited.insert(parent.0.clone()) {
+            return Err(VmAbort::new(
+                abort_codes::INVALID_OWNERSHIP_TRANSITION,
+                format!("ownership of {} is cyclic", object.id.0),
+            ));
+        }
+        owner = ctx
+            .get_object(&parent.0)
+            .await?
+            .ok_or_else(|| VmAbort::new(abort_codes::OBJECT_NOT_FOUND, format!("object {} not found", parent.0)))?
+            .owner;
+    }
+    Ok(owner)
+}
+
+/// The object id carried by a resolved argument: either the id itself or a
+/// loaded object.
+fn object_id_of(value: &Value) -> Option<&str> {
+    value
+        .as_str()
+        .or_else(|| value.get("id").and_then(Value::as_str))
+}
+
+impl Default for MoveVMExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_core::transaction::{InputObject, SignedTransaction, TransactionDigest};
+    use sui_storage::InMemoryObjectStore;
+    use serde_json::json;
+    use sui_core::messages::CommandResult;
+    use async_trait::async_trait;
+
+    fn coin(id: &str, owner: &str, balance: u64) -> SuiObject {
+        SuiObject::new(ObjectID::new(id), Owner::Address(owner.to_string()), ObjectData::Coin { balance })
+    }
+
+    async fn executor(objects: Vec<SuiObject>) -> MoveVMExecutor {
+        executor_with(objects, VmConfig::default()).await
+    }
+
+    async fn executor_with(objects: Vec<SuiObject>, config: VmConfig) -> MoveVMExecutor {
+        let store = InMemoryObjectStore::new();
+        for object in objects {
+            store.put_object(object).await.unwrap();
+        }
+        MoveVMExecutor::with_object_store(Box::new(store)).with_config(config)
+    }
+
+    fn request(signer: &str, kind: TransactionKind, input_objects: Vec<InputObject>) -> ExecutionRequest {
+        let payload = TransactionPayload {
+            kind,
+            gas_budget: 10_000,
+            input_objects,
+            sequence_number: 0,
+            gas_price: 0,
+            read_only: false,
+            gas_payment: None,
+        };
+        ExecutionRequest {
+            tx: SignedTransaction::new(signer.to_string(), payload),
+            digest: TransactionDigest::random(),
+        }
+    }
+
+    fn call(module: &str, function: &str, arguments: Vec<Argument>) -> TransactionKind {
+        TransactionKind::Call {
+            package: ObjectID::new("0x2"),
+            module: module.to_string(),
+            function: function.to_string(),
+            arguments,
+        }
+    }
+
+    fn ownership_changed(object: &str, from: Owner, to: Owner) -> ObjectEvent {
+        ObjectEvent::OwnershipChanged { object: ObjectID::new(object), from, to }
+    }
+
+    #[tokio::test]
+    async fn transfer_emits_ownership_changed() {
+        let vm = executor(vec![coin("coin-a", "alice", 10)]).await;
+        let kind = TransactionKind::Transfer { object: ObjectID::new("coin-a"), recipient: "bob".to_string() };
+
+        let result = vm.execute(&request("alice", kind, Vec::new())).await;
+
+        assert!(result.status.is_success());
+        assert_eq!(
+            result.events,
+            vec![ownership_changed(
+                "coin-a",
+                Owner::Address("alice".to_string()),
+                Owner::Address("bob".to_string()),
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn freeze_and_share_emit_ownership_changed() {
+        let vm = executor(vec![coin("coin-a", "alice", 10), coin("coin-b", "alice", 10)]).await;
+        let freeze = call("transfer", "freeze_object", vec![Argument::Object(ObjectID::new("coin-a"))]);
+        let share = call("transfer", "share_object", vec![Argument::Object(ObjectID::new("coin-b"))]);
+
+        let frozen = vm.execute(&request("alice", freeze, Vec::new())).await;
+        let shared = vm.execute(&request("alice", share, Vec::new())).await;
+
+        assert_eq!(
+            frozen.events,
+            vec![ownership_changed("coin-a", Owner::Address("alice".to_string()), Owner::Immutable)]
+        );
+        assert_eq!(
+            shared.events,
+            vec![ownership_changed("coin-b", Owner::Address("alice".to_string()), Owner::Shared)]
+        );
+    }
+
+    #[tokio::test]
+    async fn only_the_owner_can_freeze_or_share() {
+        let vm = executor(vec![coin("coin-a", "alice", 10)]).await;
+        for function in ["freeze_object", "share_object"] {
+            let kind = call("transfer", function, vec![Argument::Object(ObjectID::new("coin-a"))]);
+
+            let result = vm.execute(&request("mallory", kind, Vec::new())).await;
+
+            assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::NOT_OWNER, .. }));
+            assert!(result.touched_objects.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn call_returns_the_constants_it_loaded() {
+        let vm = executor(Vec::new()).await;
+        let kind = call(
+            "coin",
+            "transfer",
+            vec![Argument::Value(json!(42)), Argument::Value(json!("memo"))],
+        );
+
+        let result = vm.execute(&request("alice", kind, Vec::new())).await;
+
+        assert!(result.status.is_success());
+        assert_eq!(result.return_values, vec![json!(42), json!("memo")]);
+    }
+
+    fn profile(id: &str, owner: &str) -> SuiObject {
+        SuiObject::new(
+            ObjectID::new(id),
+            Owner::Address(owner.to_string()),
+            ObjectData::MoveStruct { type_name: "game::Profile".to_string(), fields: json!({ "level": 1 }) },
+        )
+    }
+
+    fn migratable(type_name: &str) -> VmConfig {
+        VmConfig {
+            migratable_types: HashSet::from([type_name.to_string()]),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn migrate_replaces_struct_fields_and_bumps_the_version() {
+        let vm = executor_with(vec![profile("hero", "alice")], migratable("game::Profile")).await;
+        let kind = TransactionKind::Migrate { object: ObjectID::new("hero"), new_fields: json!({ "level": 2, "xp": 0 }) };
+
+        let result = vm.execute(&request("alice", kind, Vec::new())).await;
+
+        assert!(result.status.is_success());
+        assert_eq!(result.touched_objects.len(), 1);
+        let migrated = &result.touched_objects[0];
+        assert_eq!(migrated.version, 2);
+        assert!(matches!(&migrated.data, ObjectData::MoveStruct { fields, .. } if *fields == json!({ "level": 2, "xp": 0 })));
+        assert!(result.created.is_empty());
+    }
+
+    #[tokio::test]
+    async fn migrate_rejects_coins_and_unlisted_types() {
+        let vm = executor_with(
+            vec![coin("coin-a", "alice", 10), profile("hero", "alice")],
+            migratable("game::Other"),
+        )
+        .await;
+        let migrate = |object: &str| TransactionKind::Migrate { object: ObjectID::new(object), new_fields: json!({}) };
+
+        let coin = vm.execute(&request("alice", migrate("coin-a"), Vec::new())).await;
+        let unlisted = vm.execute(&request("alice", migrate("hero"), Vec::new())).await;
+
+        assert!(matches!(coin.status, ExecutionStatus::Failure { code: abort_codes::INVALID_OBJECT_TYPE, .. }));
+        assert!(matches!(unlisted.status, ExecutionStatus::Failure { code: abort_codes::TYPE_NOT_MIGRATABLE, .. }));
+    }
+
+    fn transfer(object: &str, recipient: &str) -> TransactionKind {
+        TransactionKind::Transfer { object: ObjectID::new(object), recipient: recipient.to_string() }
+    }
+
+    #[tokio::test]
+    async fn transfer_meters_one_read_and_one_write() {
+        let vm = executor(vec![coin("coin-a", "alice", 10)]).await;
+
+        let result = vm.execute(&request("alice", transfer("coin-a", "bob"), Vec::new())).await;
+
+        assert!(result.status.is_success());
+        assert_eq!((result.reads, result.writes), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn batch_meters_the_sum_of_its_commands() {
+        let vm = executor(vec![coin("coin-a", "alice", 10), coin("coin-b", "alice", 10)]).await;
+        let batch = TransactionKind::Batch { commands: vec![transfer("coin-a", "bob"), transfer("coin-b", "bob")] };
+
+        let result = vm.execute(&request("alice", batch, Vec::new())).await;
+
+        assert!(result.status.is_success());
+        assert_eq!((result.reads, result.writes), (2, 2));
+    }
+
+    #[tokio::test]
+    async fn storage_gas_follows_the_written_object_type_and_size() {
+        let gas_schedule = GasSchedule {
+            write_gas_per_byte: 2,
+            coin: TypeMultipliers { read: 1, write: 1 },
+            move_struct: TypeMultipliers { read: 1, write: 5 },
+            ..Default::default()
+        };
+        let config = VmConfig { gas_schedule, ..Default::default() };
+        let mut storage_gas = Vec::new();
+        for (object, multiplier) in [(coin("hero", "alice", 10), 1), (profile("hero", "alice"), 5)] {
+            let free = executor(vec![object.clone()]).await;
+            let priced = executor_with(vec![object], config.clone()).await;
+
+            let unpriced = free.execute(&request("alice", transfer("hero", "bob"), Vec::new())).await;
+            let result = priced.execute(&request("alice", transfer("hero", "bob"), Vec::new())).await;
+
+            assert!(result.status.is_success());
+            let written = sui_storage::serialized_size(&result.touched_objects[0]) as u64;
+            assert_eq!(result.gas_used - unpriced.gas_used, written * 2 * multiplier);
+            storage_gas.push(result.gas_used - unpriced.gas_used);
+        }
+        assert!(storage_gas[1] > storage_gas[0]);
+    }
+
+    #[tokio::test]
+    async fn exceeding_the_write_limit_aborts() {
+        let config = VmConfig { max_object_writes: Some(1), ..Default::default() };
+        let vm = executor_with(vec![coin("coin-a", "alice", 10), coin("coin-b", "alice", 10)], config).await;
+        let batch = TransactionKind::Batch { commands: vec![transfer("coin-a", "bob"), transfer("coin-b", "bob")] };
+
+        let result = vm.execute(&request("alice", batch, Vec::new())).await;
+
+        assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::RESOURCE_LIMIT_EXCEEDED, .. }));
+        assert!(result.touched_objects.is_empty());
+    }
+
+    #[tokio::test]
+    async fn strict_dispatch_aborts_unknown_functions() {
+        let vm = executor(Vec::new()).await;
+
+        let result = vm.execute(&request("alice", call("game", "missing", Vec::new()), Vec::new())).await;
+
+        assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::FUNCTION_NOT_FOUND, .. }));
+    }
+
+    #[tokio::test]
+    async fn lenient_dispatch_accepts_unknown_functions() {
+        let config = VmConfig { dispatch: DispatchPolicy::Lenient, ..Default::default() };
+        let vm = executor_with(Vec::new(), config).await;
+
+        let result = vm.execute(&request("alice", call("game", "missing", Vec::new()), Vec::new())).await;
+
+        assert!(result.status.is_success());
+        assert!(result.logs.iter().any(|log| log.contains("Executed game::missing")));
+    }
+
+    #[tokio::test]
+    async fn minted_coin_ids_match_across_executors() {
+        let mint = request("alice", call("coin", "mint", Vec::new()), Vec::new());
+
+        let first = executor(Vec::new()).await.execute(&mint).await;
+        let second = executor(Vec::new()).await.execute(&mint).await;
+
+        assert_eq!(first.created, vec![ObjectID::derive(&mint.digest, 0)]);
+        assert_eq!(first.created, second.created);
+    }
+
+    #[tokio::test]
+    async fn must_exist_policy_rejects_unknown_recipients() {
+        let config = VmConfig { recipient_policy: RecipientPolicy::MustExist, ..Default::default() };
+        let vm = executor_with(vec![coin("coin-a", "alice", 10), coin("coin-b", "bob", 10)], config).await;
+
+        let unknown = vm.execute(&request("alice", transfer("coin-a", "carol"), Vec::new())).await;
+        let known = vm.execute(&request("alice", transfer("coin-a", "bob"), Vec::new())).await;
+
+        assert!(matches!(unknown.status, ExecutionStatus::Failure { code: abort_codes::UNKNOWN_RECIPIENT, .. }));
+        assert!(known.status.is_success());
+    }
+
+    #[tokio::test]
+    async fn any_address_policy_accepts_new_recipients() {
+        let vm = executor(vec![coin("coin-a", "alice", 10)]).await;
+
+        let result = vm.execute(&request("alice", transfer("coin-a", "carol"), Vec::new())).await;
+
+        assert!(result.status.is_success());
+    }
+
+    #[tokio::test]
+    async fn object_argument_loads_the_object() {
+        let vm = executor(vec![coin("coin-a", "alice", 10)]).await;
+        let kind = call("coin", "transfer", vec![Argument::Object(ObjectID::new("coin-a"))]);
+
+        let result = vm.execute(&request("alice", kind, Vec::new())).await;
+
+        assert!(result.status.is_success());
+        assert_eq!(result.return_values, vec![serde_json::to_value(coin("coin-a", "alice", 10)).unwrap()]);
+    }
+
+    #[tokio::test]
+    async fn result_argument_chains_batch_outputs() {
+        let vm = executor(Vec::new()).await;
+        let batch = TransactionKind::Batch {
+            commands: vec![
+                call("coin", "mint", Vec::new()),
+                TransactionKind::TransferToSender { object: Argument::Result(0) },
+            ],
+        };
+        let mint = request("alice", batch, Vec::new());
+
+        let result = vm.execute(&mint).await;
+
+        assert!(result.status.is_success());
+        let minted = ObjectID::derive(&mint.digest, 0);
+        let coin = result.touched_objects.iter().find(|object| object.id == minted).unwrap();
+        assert_eq!(coin.owner, Owner::Address("alice".to_string()));
+    }
+
+    #[tokio::test]
+    async fn transfer_to_sender_requires_ownership_of_existing_objects() {
+        let vm = executor(vec![coin("coin-a", "alice", 10)]).await;
+        let kind = TransactionKind::TransferToSender { object: Argument::Object(ObjectID::new("coin-a")) };
+
+        let result = vm.execute(&request("mallory", kind, Vec::new())).await;
+
+        assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::NOT_OWNER, .. }));
+        assert!(result.touched_objects.is_empty());
+    }
+
+    #[tokio::test]
+    async fn result_argument_must_refer_to_an_earlier_command() {
+        let vm = executor(Vec::new()).await;
+        let batch = TransactionKind::Batch {
+            commands: vec![
+                TransactionKind::TransferToSender { object: Argument::Result(1) },
+                call("coin", "mint", Vec::new()),
+            ],
+        };
+
+        let result = vm.execute(&request("alice", batch, Vec::new())).await;
+
+        assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::INVALID_COMMAND, .. }));
+    }
+
+    #[tokio::test]
+    async fn batch_result_pinpoints_the_aborted_command() {
+        let vm = executor(vec![coin("coin-a", "alice", 10), coin("coin-b", "alice", 10)]).await;
+        let batch = TransactionKind::Batch {
+            commands: vec![
+                transfer("coin-a", "bob"),
+                TransactionKind::Migrate { object: ObjectID::new("coin-b"), new_fields: json!({}) },
+                transfer("coin-b", "bob"),
+            ],
+        };
+
+        let result = vm.execute(&request("alice", batch, Vec::new())).await;
+
+        assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::INVALID_OBJECT_TYPE, .. }));
+        assert!(result.touched_objects.is_empty());
+        let batch = result.batch.unwrap();
+        assert_eq!(batch.commands.len(), 3);
+        assert_eq!(batch.commands[0], CommandResult { index: 0, executed: true, status: CommandStatus::RolledBack });
+        assert_eq!(batch.commands[1].index, 1);
+        assert!(batch.commands[1].executed);
+        assert!(matches!(
+            batch.commands[1].status,
+            CommandStatus::Aborted { code: abort_codes::INVALID_OBJECT_TYPE, .. }
+        ));
+        assert_eq!(batch.commands[2], CommandResult { index: 2, executed: false, status: CommandStatus::RolledBack });
+        assert_eq!(batch.failed_command(), Some(&batch.commands[1]));
+    }
+
+    #[tokio::test]
+    async fn successful_batch_reports_every_command() {
+        let vm = executor(vec![coin("coin-a", "alice", 10), coin("coin-b", "alice", 10)]).await;
+        let batch = TransactionKind::Batch { commands: vec![transfer("coin-a", "bob"), transfer("coin-b", "bob")] };
+
+        let result = vm.execute(&request("alice", batch, Vec::new())).await;
+
+        let batch = result.batch.unwrap();
+        assert!(batch.commands.iter().all(|command| command.executed && command.status == CommandStatus::Success));
+        assert!(batch.failed_command().is_none());
+    }
+
+    #[tokio::test]
+    async fn change_owner_by_a_non_owner_aborts() {
+        let vm = executor(vec![coin("coin-a", "alice", 10)]).await;
+        for new_owner in [Owner::Address("mallory".to_string()), Owner::Shared, Owner::Immutable] {
+            let kind = TransactionKind::ChangeOwner { object: ObjectID::new("coin-a"), new_owner };
+
+            let result = vm.execute(&request("mallory", kind, Vec::new())).await;
+
+            assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::NOT_OWNER, .. }));
+            assert!(result.touched_objects.is_empty());
+        }
+    }
+
+    fn owned_by(id: &str, owner: Owner) -> SuiObject {
+        SuiObject::new(ObjectID::new(id), owner, ObjectData::Coin { balance: 10 })
+    }
+
+    #[tokio::test]
+    async fn change_owner_moves_an_object_to_each_kind_of_owner() {
+        let new_owners = [
+            Owner::Address("bob".to_string()),
+            Owner::Object(ObjectID::new("bag")),
+            Owner::Shared,
+            Owner::Immutable,
+        ];
+        for new_owner in new_owners {
+            let vm = executor(vec![coin("coin-a", "alice", 10), coin("bag", "alice", 0)]).await;
+            let kind = TransactionKind::ChangeOwner { object: ObjectID::new("coin-a"), new_owner: new_owner.clone() };
+
+            let result = vm.execute(&request("alice", kind, Vec::new())).await;
+
+            assert!(result.status.is_success());
+            assert_eq!(result.touched_objects[0].owner, new_owner);
+            assert_eq!(result.touched_objects[0].version, 2);
+        }
+    }
+
+    #[tokio::test]
+    async fn immutable_objects_cannot_change_owner() {
+        let vm = executor(vec![owned_by("coin-a", Owner::Immutable)]).await;
+        let kind = TransactionKind::ChangeOwner {
+            object: ObjectID::new("coin-a"),
+            new_owner: Owner::Address("alice".to_string()),
+        };
+
+        let result = vm.execute(&request("alice", kind, Vec::new())).await;
+
+        assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::IMMUTABLE_OBJECT, .. }));
+        assert!(result.touched_objects.is_empty());
+    }
+
+    #[tokio::test]
+    async fn shared_objects_cannot_become_address_owned() {
+        let vm = executor(vec![owned_by("coin-a", Owner::Shared)]).await;
+        let kind = TransactionKind::ChangeOwner {
+            object: ObjectID::new("coin-a"),
+            new_owner: Owner::Address("alice".to_string()),
+        };
+
+        let result = vm.execute(&request("alice", kind, Vec::new())).await;
+
+        assert!(matches!(
+            result.status,
+            ExecutionStatus::Failure { code: abort_codes::INVALID_OWNERSHIP_TRANSITION, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn only_the_root_owner_can_move_a_child_object() {
+        let objects = || {
+            vec![
+                coin("bag", "alice", 0),
+                owned_by("pouch", Owner::Object(ObjectID::new("bag"))),
+                owned_by("coin-a", Owner::Object(ObjectID::new("pouch"))),
+            ]
+        };
+        let thefts = [
+            transfer("coin-a", "mallory"),
+            TransactionKind::ChangeOwner {
+                object: ObjectID::new("coin-a"),
+                new_owner: Owner::Address("mallory".to_string()),
+            },
+            TransactionKind::TransferToSender { object: Argument::Object(ObjectID::new("coin-a")) },
+        ];
+        for kind in thefts {
+            let vm = executor(objects()).await;
+
+            let result = vm.execute(&request("mallory", kind, Vec::new())).await;
+
+            assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::NOT_OWNER, .. }));
+            assert!(result.touched_objects.is_empty());
+        }
+
+        let vm = executor(objects()).await;
+        let result = vm.execute(&request("alice", transfer("coin-a", "bob"), Vec::new())).await;
+        assert!(result.status.is_success());
+        assert_eq!(result.touched_objects[0].owner, Owner::Address("bob".to_string()));
+    }
+
+    #[tokio::test]
+    async fn children_of_shared_objects_cannot_be_taken() {
+        let vm = executor(vec![
+            owned_by("pool", Owner::Shared),
+            owned_by("coin-a", Owner::Object(ObjectID::new("pool"))),
+        ])
+        .await;
+
+        let result = vm.execute(&request("mallory", transfer("coin-a", "mallory"), Vec::new())).await;
+
+        assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::NOT_OWNER, .. }));
+    }
+
+    /// Approvals fixed up front, as `(owner, scope, operator)`.
+    #[derive(Debug)]
+    struct FixedApprovals(Vec<(&'static str, ApprovalScope, &'static str)>);
+
+    impl OperatorApprovals for FixedApprovals {
+        fn is_approved(&self, owner: &str, scope: &ApprovalScope, operator: &str) -> bool {
+            self.0.iter().any(|(o, s, op)| *o == owner && s == scope && *op == operator)
+        }
+    }
+
+    #[tokio::test]
+    async fn approved_operators_can_move_a_child_object() {
+        let scopes = [ApprovalScope::Object(ObjectID::new("coin-a")), ApprovalScope::All];
+        for scope in scopes {
+            let config = VmConfig {
+                approvals: Some(std::sync::Arc::new(FixedApprovals(vec![("alice", scope, "bob")]))),
+                ..Default::default()
+            };
+            let objects = vec![coin("bag", "alice", 0), owned_by("coin-a", Owner::Object(ObjectID::new("bag")))];
+            let vm = executor_with(objects, config).await;
+
+            let approved = vm.execute(&request("bob", transfer("coin-a", "carol"), Vec::new())).await;
+            let unapproved = vm.execute(&request("mallory", transfer("coin-a", "mallory"), Vec::new())).await;
+
+            assert!(approved.status.is_success());
+            assert_eq!(approved.touched_objects[0].owner, Owner::Address("carol".to_string()));
+            assert!(matches!(unapproved.status, ExecutionStatus::Failure { code: abort_codes::NOT_OWNER, .. }));
+        }
+    }
+
+    #[tokio::test]
+    async fn approvals_from_a_non_root_owner_do_not_cover_a_child_object() {
+        let config = VmConfig {
+            approvals: Some(std::sync::Arc::new(FixedApprovals(vec![("mallory", ApprovalScope::All, "bob")]))),
+            ..Default::default()
+        };
+        let objects = vec![coin("bag", "alice", 0), owned_by("coin-a", Owner::Object(ObjectID::new("bag")))];
+        let vm = executor_with(objects, config).await;
+
+        let result = vm.execute(&request("bob", transfer("coin-a", "bob"), Vec::new())).await;
+
+        assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::NOT_OWNER, .. }));
+    }
+
+    fn airdrop(source: &str, distribution: &[(&str, u64)]) -> TransactionKind {
+        TransactionKind::Airdrop {
+            source: ObjectID::new(source),
+            distribution: distribution.iter().map(|(recipient, amount)| (recipient.to_string(), *amount)).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn airdrop_splits_the_source_and_keeps_the_change() {
+        let vm = executor(vec![coin("coin-a", "alice", 100)]).await;
+        let drop = request("alice", airdrop("coin-a", &[("bob", 30), ("carol", 20)]), Vec::new());
+
+        let result = vm.execute(&drop).await;
+
+        assert!(result.status.is_success());
+        let ids = [ObjectID::derive(&drop.digest, 0), ObjectID::derive(&drop.digest, 1)];
+        assert_eq!(result.return_values, ids.iter().map(|id| json!(id.0)).collect::<Vec<_>>());
+        let balance_of = |id: &ObjectID| {
+            result.touched_objects.iter().find(|object| object.id == *id).map(|object| (&object.owner, &object.data))
+        };
+        assert!(matches!(balance_of(&ObjectID::new("coin-a")), Some((_, ObjectData::Coin { balance: 50 }))));
+        assert!(matches!(balance_of(&ids[0]), Some((Owner::Address(owner), ObjectData::Coin { balance: 30 })) if owner == "bob"));
+        assert!(matches!(balance_of(&ids[1]), Some((Owner::Address(owner), ObjectData::Coin { balance: 20 })) if owner == "carol"));
+    }
+
+    #[tokio::test]
+    async fn airdrop_to_a_hundred_recipients_creates_a_coin_each() {
+        let vm = executor(vec![coin("coin-a", "alice", 10_000)]).await;
+        let recipients: Vec<(String, u64)> = (0..100).map(|i| (format!("user-{}", i), i + 1)).collect();
+        let kind = TransactionKind::Airdrop { source: ObjectID::new("coin-a"), distribution: recipients.clone() };
+
+        let result = vm.execute(&request("alice", kind, Vec::new())).await;
+
+        assert!(result.status.is_success());
+        assert_eq!(result.created.len(), 100);
+        let mut airdropped: Vec<(String, u64)> = result
+            .touched_objects
+            .iter()
+            .filter(|object| result.created.contains(&object.id))
+            .map(|object| match (&object.owner, &object.data) {
+                (Owner::Address(owner), ObjectData::Coin { balance }) => (owner.clone(), *balance),
+                _ => panic!("airdropped {} is not an address-owned coin", object.id.0),
+            })
+            .collect();
+        airdropped.sort_by_key(|(_, amount)| *amount);
+        assert_eq!(airdropped, recipients);
+        let source = result.touched_objects.iter().find(|object| object.id.0 == "coin-a").unwrap();
+        assert!(matches!(source.data, ObjectData::Coin { balance: 4_950 }));
+    }
+
+    #[tokio::test]
+    async fn airdrop_beyond_the_balance_aborts() {
+        let vm = executor(vec![coin("coin-a", "alice", 10)]).await;
+
+        let result = vm.execute(&request("alice", airdrop("coin-a", &[("bob", 6), ("carol", 5)]), Vec::new())).await;
+
+        assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::INSUFFICIENT_BALANCE, .. }));
+        assert!(result.touched_objects.is_empty());
+    }
+
+    #[tokio::test]
+    async fn airdrop_respects_the_recipient_policy() {
+        let config = || VmConfig { recipient_policy: RecipientPolicy::MustExist, ..Default::default() };
+        let vm = executor_with(vec![coin("coin-a", "alice", 10), coin("coin-b", "bob", 1)], config()).await;
+
+        let result = vm.execute(&request("alice", airdrop("coin-a", &[("bob", 2), ("nobody", 2)]), Vec::new())).await;
+
+        assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::UNKNOWN_RECIPIENT, .. }));
+        assert!(result.touched_objects.is_empty());
+
+        let result = vm.execute(&request("alice", airdrop("coin-a", &[("bob", 2)]), Vec::new())).await;
+        assert!(result.status.is_success());
+    }
+
+    #[tokio::test]
+    async fn transfer_of_a_missing_object_aborts() {
+        let vm = executor(Vec::new()).await;
+
+        let result = vm.execute(&request("alice", transfer("coin-a", "bob"), Vec::new())).await;
+
+        assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::OBJECT_NOT_FOUND, .. }));
+        assert!(result.events.is_empty());
+    }
+
+    fn read_only(signer: &str, kind: TransactionKind) -> ExecutionRequest {
+        let mut request = request(signer, kind, Vec::new());
+        request.tx.payload.gas_budget = 0;
+        request.tx.payload.read_only = true;
+        request
+    }
+
+    #[tokio::test]
+    async fn read_only_call_reads_without_charging_or_writing() {
+        let vm = executor(vec![coin("coin-a", "alice", 10)]).await;
+        let inspect = call("coin", "transfer", vec![Argument::Object(ObjectID::new("coin-a"))]);
+
+        let result = vm.execute(&read_only("alice", inspect)).await;
+
+        assert!(result.status.is_success());
+        assert_eq!(result.gas_used, 0);
+        assert_eq!(result.reads, 1);
+        assert!(result.touched_objects.is_empty());
+    }
+
+    #[tokio::test]
+    async fn read_only_transaction_attempting_a_write_aborts() {
+        let vm = executor(vec![coin("coin-a", "alice", 10)]).await;
+
+        let result = vm.execute(&read_only("alice", transfer("coin-a", "bob"))).await;
+
+        assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::READ_ONLY_VIOLATION, .. }));
+        assert!(result.touched_objects.is_empty());
+    }
+
+    fn mints(count: usize) -> TransactionKind {
+        TransactionKind::Batch { commands: (0..count).map(|_| call("coin", "mint", Vec::new())).collect() }
+    }
+
+    #[tokio::test]
+    async fn batch_creating_up_to_the_quota_succeeds() {
+        let config = VmConfig { max_objects_created: Some(3), ..Default::default() };
+        let vm = executor_with(Vec::new(), config).await;
+
+        let result = vm.execute(&request("alice", mints(3), Vec::new())).await;
+
+        assert!(result.status.is_success());
+        assert_eq!(result.created.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn batch_exceeding_the_creation_quota_creates_nothing() {
+        let config = VmConfig { max_objects_created: Some(3), ..Default::default() };
+        let vm = executor_with(Vec::new(), config).await;
+
+        let result = vm.execute(&request("alice", mints(4), Vec::new())).await;
+
+        assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::CREATION_QUOTA_EXCEEDED, .. }));
+        assert!(result.created.is_empty());
+        assert!(result.touched_objects.is_empty());
+    }
+
+    #[tokio::test]
+    async fn large_arguments_reach_the_stack_intact_at_a_size_independent_cost() {
+        let vm = executor(Vec::new()).await;
+        let large = json!((0..50_000).collect::<Vec<u64>>());
+        let small = call("coin", "transfer", vec![Argument::Value(json!([0]))]);
+        let small = vm.execute(&request("alice", small, Vec::new())).await;
+
+        let kind = call("coin", "transfer", vec![Argument::Value(large.clone())]);
+        let result = vm.execute(&request("alice", kind, Vec::new())).await;
+
+        assert!(result.status.is_success());
+        assert_eq!(result.return_values, vec![large]);
+        assert_eq!(result.gas_used, small.gas_used);
+    }
+
+    /// `coin-a` wrapped inside alice's `coin-b`.
+    fn wrapped_coins() -> Vec<SuiObject> {
+        let mut inner = owned_by("coin-a", Owner::Object(ObjectID::new("coin-b")));
+        inner.wrapped_by = Some(ObjectID::new("coin-b"));
+        vec![inner, coin("coin-b", "alice", 10)]
+    }
+
+    #[tokio::test]
+    async fn wrapping_marks_the_inner_object() {
+        let vm = executor(vec![coin("coin-a", "alice", 10), coin("coin-b", "alice", 10)]).await;
+        let kind = TransactionKind::Wrap { object: ObjectID::new("coin-a"), wrapper: ObjectID::new("coin-b") };
+
+        let result = vm.execute(&request("alice", kind, Vec::new())).await;
+
+        assert!(result.status.is_success());
+        let inner = result.touched_objects.iter().find(|object| object.id.0 == "coin-a").unwrap();
+        assert_eq!(inner.wrapped_by, Some(ObjectID::new("coin-b")));
+        assert_eq!(inner.owner, Owner::Object(ObjectID::new("coin-b")));
+    }
+
+    #[tokio::test]
+    async fn wrapped_objects_cannot_be_used() {
+        let vm = executor(wrapped_coins()).await;
+        let kinds = vec![
+            transfer("coin-a", "bob"),
+            TransactionKind::Wrap { object: ObjectID::new("coin-a"), wrapper: ObjectID::new("coin-b") },
+            call("coin", "burn", vec![Argument::Object(ObjectID::new("coin-a"))]),
+        ];
+
+        for kind in kinds {
+            let result = vm.execute(&request("alice", kind.clone(), Vec::new())).await;
+
+            assert!(
+                matches!(result.status, ExecutionStatus::Failure { code: abort_codes::OBJECT_WRAPPED, .. }),
+                "{kind:?}: {:?}",
+                result.status
+            );
+            assert!(result.touched_objects.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn unwrapping_clears_the_marker_so_the_object_is_usable_again() {
+        let vm = executor(wrapped_coins()).await;
+        let unwrap = TransactionKind::Unwrap { object: ObjectID::new("coin-a") };
+
+        let result = vm.execute(&request("alice", unwrap, Vec::new())).await;
+
+        assert!(result.status.is_success());
+        let unwrapped = result.touched_objects[0].clone();
+        assert_eq!(unwrapped.wrapped_by, None);
+        assert_eq!(unwrapped.owner, Owner::Address("alice".to_string()));
+        let vm = executor(vec![unwrapped, coin("coin-b", "alice", 10)]).await;
+        let moved = vm.execute(&request("alice", transfer("coin-a", "bob"), Vec::new())).await;
+        assert!(moved.status.is_success());
+    }
+
+    /// Answers every read after `delay`, like a store across the network.
+    struct SlowStore {
+        inner: InMemoryObjectStore,
+        delay: std::time::Duration,
+    }
+
+    #[async_trait]
+    impl ObjectStore for SlowStore {
+        async fn get_object(&self, id: &str) -> anyhow::Result<Option<SuiObject>> {
+            tokio::time::sleep(self.delay).await;
+            self.inner.get_object(id).await
+        }
+
+        async fn put_object(&self, object: SuiObject) -> anyhow::Result<()> {
+            self.inner.put_object(object).await
+        }
+
+        async fn delete_object(&self, id: &str) -> anyhow::Result<()> {
+            self.inner.delete_object(id).await
+        }
+
+        async fn list_objects(&self, owner: Option<&str>) -> anyhow::Result<Vec<SuiObject>> {
+            self.inner.list_objects(owner).await
+        }
+    }
+
+    async fn timed_batch(batch_execution: BatchExecution) -> std::time::Duration {
+        let inner = InMemoryObjectStore::new();
+        inner.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        inner.put_object(coin("coin-b", "alice", 10)).await.unwrap();
+        let store = SlowStore { inner, delay: std::time::Duration::from_millis(50) };
+        let vm = MoveVMExecutor::with_object_store(Box::new(store))
+            .with_config(VmConfig { batch_execution, ..Default::default() });
+        let batch = TransactionKind::Batch { commands: vec![transfer("coin-a", "bob"), transfer("coin-b", "bob")] };
+
+        let started = std::time::Instant::now();
+        let result = vm.execute(&request("alice", batch, Vec::new())).await;
+        let elapsed = started.elapsed();
+
+        assert!(result.status.is_success());
+        assert_eq!(result.touched_objects.len(), 2);
+        elapsed
+    }
+
+    #[tokio::test]
+    async fn disjoint_batch_commands_wait_on_the_store_together() {
+        let sequential = timed_batch(BatchExecution::Sequential).await;
+        let interleaved = timed_batch(BatchExecution::Interleaved).await;
+
+        assert!(sequential >= std::time::Duration::from_millis(100), "sequential took {sequential:?}");
+        assert!(interleaved < std::time::Duration::from_millis(100), "interleaved took {interleaved:?}");
+    }
+
+    #[tokio::test]
+    async fn batch_commands_on_the_same_object_run_in_order() {
+        let config = VmConfig { batch_execution: BatchExecution::Interleaved, ..Default::default() };
+        let vm = executor_with(vec![coin("coin-a", "alice", 10), coin("coin-b", "alice", 10)], config).await;
+        let commands = vec![
+            TransactionKind::Wrap { object: ObjectID::new("coin-a"), wrapper: ObjectID::new("coin-b") },
+            transfer("coin-b", "bob"),
+            TransactionKind::Unwrap { object: ObjectID::new("coin-a") },
+        ];
+
+        let result = vm.execute(&request("alice", TransactionKind::Batch { commands }, Vec::new())).await;
+
+        // The unwrap runs after the wrapper has gone to bob, so alice can no
+        // longer open it.
+        assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::NOT_OWNER, .. }));
+        let batch = result.batch.unwrap();
+        assert!(batch.commands.iter().all(|command| command.executed));
+        assert!(matches!(batch.commands[2].status, CommandStatus::Aborted { .. }));
+
+        let commands = vec![
+            TransactionKind::Wrap { object: ObjectID::new("coin-a"), wrapper: ObjectID::new("coin-b") },
+            transfer("coin-b", "bob"),
+        ];
+        let result = vm.execute(&request("alice", TransactionKind::Batch { commands }, Vec::new())).await;
+
+        assert!(result.status.is_success());
+        let owner = |id: &str| result.touched_objects.iter().find(|object| object.id.0 == id).unwrap().owner.clone();
+        assert_eq!(owner("coin-a"), Owner::Object(ObjectID::new("coin-b")));
+        assert_eq!(owner("coin-b"), Owner::Address("bob".to_string()));
+    }
+
+    fn profile_schemas() -> VmConfig {
+        VmConfig {
+            type_schemas: TypeSchemas::new()
+                .register("game::Profile", StructSchema::new().required("level", FieldType::Number)),
+            ..migratable("game::Profile")
+        }
+    }
+
+    #[tokio::test]
+    async fn created_structs_are_checked_against_their_schema() {
+        let config = profile_schemas();
+        let ctx = ExecutionContext::new(None, &config);
+        let mut missing_level = profile("villain", "alice");
+        missing_level.data = ObjectData::MoveStruct { type_name: "game::Profile".to_string(), fields: json!({}) };
+
+        assert_eq!(ctx.create_object(profile("hero", "alice")).await, Ok(()));
+        let abort = ctx.create_object(missing_level).await.unwrap_err();
+
+        assert_eq!(abort.code, abort_codes::SCHEMA_MISMATCH);
+        assert!(abort.message.contains("missing required field `level`"), "{}", abort.message);
+    }
+
+    #[tokio::test]
+    async fn migrating_to_fields_that_break_the_schema_aborts() {
+        let vm = executor_with(vec![profile("hero", "alice")], profile_schemas()).await;
+        let valid = TransactionKind::Migrate { object: ObjectID::new("hero"), new_fields: json!({ "level": 2 }) };
+        let invalid = TransactionKind::Migrate { object: ObjectID::new("hero"), new_fields: json!({ "xp": 0 }) };
+
+        let migrated = vm.execute(&request("alice", valid, Vec::new())).await;
+        let rejected = vm.execute(&request("alice", invalid, Vec::new())).await;
+
+        assert!(migrated.status.is_success());
+        assert!(matches!(rejected.status, ExecutionStatus::Failure { code: abort_codes::SCHEMA_MISMATCH, .. }));
+        assert!(rejected.touched_objects.is_empty());
+    }
+
+    #[tokio::test]
+    async fn transfer_records_the_pre_mutation_version_of_its_input() {
+        let mut source = coin("coin-a", "alice", 10);
+        source.version = 5;
+        let vm = executor(vec![source, coin("coin-b", "alice", 10)]).await;
+
+        let result = vm.execute(&request("alice", transfer("coin-a", "bob"), Vec::new())).await;
+
+        assert!(result.status.is_success());
+        assert_eq!(result.input_versions, vec![(ObjectID::new("coin-a"), 5)]);
+        assert_eq!(result.touched_objects[0].version, 6);
+    }
+
+    fn burn(coin: &str) -> TransactionKind {
+        call("coin", "burn", vec![Argument::Object(ObjectID::new(coin))])
+    }
+
+    #[tokio::test]
+    async fn burning_deletes_the_coin_and_reports_its_balance() {
+        let vm = executor(vec![coin("coin-a", "alice", 10), coin("coin-b", "alice", 10)]).await;
+
+        let result = vm.execute(&request("alice", burn("coin-a"), Vec::new())).await;
+
+        assert!(result.status.is_success());
+        assert_eq!(result.deleted, vec![ObjectID::new("coin-a")]);
+        assert_eq!((result.minted, result.burned), (0, 10));
+        assert_eq!(result.return_values.last(), Some(&json!(10)));
+    }
+
+    #[tokio::test]
+    async fn only_the_owner_can_burn_a_coin() {
+        let vm = executor(vec![coin("coin-a", "alice", 10), profile("hero", "alice")]).await;
+
+        let stolen = vm.execute(&request("bob", burn("coin-a"), Vec::new())).await;
+        let not_a_coin = vm.execute(&request("alice", burn("hero"), Vec::new())).await;
+
+        assert!(matches!(stolen.status, ExecutionStatus::Failure { code: abort_codes::NOT_OWNER, .. }));
+        assert!(matches!(not_a_coin.status, ExecutionStatus::Failure { code: abort_codes::INVALID_OBJECT_TYPE, .. }));
+        assert_eq!(stolen.burned + not_a_coin.burned, 0);
+        assert!(stolen.deleted.is_empty());
+    }
+
+    /// A transfer of `coin-a` paid for with alice's `gas` coin.
+    fn paid_transfer(gas_budget: u64, gas_price: u64) -> ExecutionRequest {
+        let mut request = request("alice", transfer("coin-a", "bob"), Vec::new());
+        request.tx.payload.gas_payment = Some(ObjectID::new("gas"));
+        (request.tx.payload.gas_budget, request.tx.payload.gas_price) = (gas_budget, gas_price);
+        request
+    }
+
+    fn gas_balance(result: &ExecutionResult) -> u64 {
+        match result.gas_coin.as_ref().map(|coin| &coin.data) {
+            Some(ObjectData::Coin { balance }) => *balance,
+            other => panic!("no gas coin: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn unused_budget_is_refunded_at_the_gas_price() {
+        let vm = executor(vec![coin("coin-a", "alice", 10), coin("gas", "alice", 10_000)]).await;
+
+        let result = vm.execute(&paid_transfer(1_000, 3)).await;
+
+        assert!(result.status.is_success());
+        assert!(result.gas_used < 1_000);
+        assert_eq!(result.gas_refund, (1_000 - result.gas_used) * 3);
+        assert_eq!(gas_balance(&result), 10_000 - 1_000 * 3 + result.gas_refund);
+    }
+
+    #[tokio::test]
+    async fn a_fully_consumed_budget_refunds_nothing() {
+        let vm = executor(vec![coin("coin-a", "alice", 10), coin("gas", "alice", 10_000)]).await;
+        let needed = vm.execute(&paid_transfer(1_000, 1)).await.gas_used;
+
+        for budget in [needed, needed / 2] {
+            let result = vm.execute(&paid_transfer(budget, 3)).await;
+
+            assert_eq!(result.gas_refund, 0);
+            assert_eq!(gas_balance(&result), 10_000 - budget * 3);
+        }
+    }
+
+    fn conditional(condition: StateCondition, then: TransactionKind) -> TransactionKind {
+        TransactionKind::Conditional { condition, then: Box::new(then) }
+    }
+
+    /// Alice's `coin-a` goes to bob provided bob's `coin-b` still holds at
+    /// least `price`.
+    fn swap_leg(price: u64) -> TransactionKind {
+        let condition = StateCondition::All(vec![
+            StateCondition::OwnedBy { object: ObjectID::new("coin-b"), owner: Owner::Address("bob".to_string()) },
+            StateCondition::BalanceAtLeast { object: ObjectID::new("coin-b"), amount: price },
+        ]);
+        conditional(condition, transfer("coin-a", "bob"))
+    }
+
+    #[tokio::test]
+    async fn conditional_transfer_commits_when_the_condition_holds() {
+        let vm = executor(vec![coin("coin-a", "alice", 10), coin("coin-b", "bob", 50)]).await;
+
+        let result = vm.execute(&request("alice", swap_leg(50), Vec::new())).await;
+
+        assert!(result.status.is_success());
+        assert_eq!(result.touched_objects.len(), 1);
+        assert_eq!(result.touched_objects[0].owner, Owner::Address("bob".to_string()));
+    }
+
+    #[tokio::test]
+    async fn conditional_transfer_aborts_without_side_effects_when_the_condition_fails() {
+        let vm = executor(vec![coin("coin-a", "alice", 10), coin("coin-b", "bob", 50)]).await;
+        let absent = conditional(StateCondition::Exists { object: ObjectID::new("coin-z") }, transfer("coin-a", "bob"));
+
+        for kind in [swap_leg(51), absent] {
+            let result = vm.execute(&request("alice", kind, Vec::new())).await;
+
+            assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::CONDITION_FAILED, .. }));
+            assert!(result.touched_objects.is_empty());
+            assert!(result.events.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn conditions_see_earlier_writes_in_the_same_batch() {
+        let vm = executor(vec![coin("coin-a", "alice", 10), coin("coin-c", "alice", 10)]).await;
+        let after_payment = conditional(
+            StateCondition::OwnedBy { object: ObjectID::new("coin-a"), owner: Owner::Address("bob".to_string()) },
+            transfer("coin-c", "carol"),
+        );
+        let commands = vec![transfer("coin-a", "bob"), after_payment];
+
+        let result = vm.execute(&request("alice", TransactionKind::Batch { commands }, Vec::new())).await;
+
+        assert!(result.status.is_success());
+        assert_eq!(result.touched_objects.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn objects_created_shared_record_their_creation_version() {
+        let config = VmConfig::default();
+        let ctx = ExecutionContext::new(None, &config);
+        let mut pool = owned_by("pool", Owner::Shared);
+        (pool.version, pool.initial_shared_version) = (3, None);
+
+        ctx.create_object(pool).await.unwrap();
+
+        let created = ctx.into_changes().written;
+        assert_eq!(created[0].initial_shared_version, Some(3));
+        assert_eq!(created[0].shared_sequence(), Some(0));
+    }
+
+    #[tokio::test]
+    async fn sharing_records_the_initial_shared_version() {
+        let mut owned = coin("coin-a", "alice", 10);
+        owned.version = 4;
+        let vm = executor(vec![owned]).await;
+        let share = call("transfer", "share_object", vec![Argument::Object(ObjectID::new("coin-a"))]);
+
+        let result = vm.execute(&request("alice", share, Vec::new())).await;
+
+        assert!(result.status.is_success());
+        let shared = &result.touched_objects[0];
+        assert_eq!((shared.version, shared.initial_shared_version), (5, Some(5)));
+        assert_eq!(shared.shared_sequence(), Some(0));
+    }
+
+    #[tokio::test]
+    async fn shared_mutations_count_up_from_the_initial_shared_version() {
+        let vm = executor(vec![owned_by("pool", Owner::Shared)]).await;
+        let touch = TransactionKind::ChangeOwner { object: ObjectID::new("pool"), new_owner: Owner::Shared };
+
+        let result = vm.execute(&request("alice", touch, Vec::new())).await;
+
+        assert!(result.status.is_success());
+        let pool = &result.touched_objects[0];
+        assert_eq!((pool.version, pool.initial_shared_version), (2, Some(1)));
+        assert_eq!(pool.shared_sequence(), Some(1));
+    }
+
+    /// Sends the whole balance of alice's 10-coin to bob.
+    fn drain() -> ExecutionRequest {
+        request("alice", airdrop("coin-a", &[("bob", 10)]), Vec::new())
+    }
+
+    #[tokio::test]
+    async fn draining_a_coin_deletes_it_when_cleanup_is_enabled() {
+        let config = VmConfig { delete_empty_coins: true, ..VmConfig::default() };
+        let vm = executor_with(vec![coin("coin-a", "alice", 10)], config).await;
+        let drain = drain();
+
+        let result = vm.execute(&drain).await;
+
+        assert!(result.status.is_success());
+        assert_eq!(result.deleted, vec![ObjectID::new("coin-a")]);
+        assert!(result.touched_objects.iter().all(|object| object.id.0 != "coin-a"));
+        let sent = &result.touched_objects[0];
+        assert_eq!(sent.id, ObjectID::derive(&drain.digest, 0));
+        assert!(matches!(sent.data, ObjectData::Coin { balance: 10 }));
+    }
+
+    #[tokio::test]
+    async fn draining_a_coin_leaves_it_empty_when_cleanup_is_disabled() {
+        let vm = executor(vec![coin("coin-a", "alice", 10)]).await;
+
+        let result = vm.execute(&drain()).await;
+
+        assert!(result.status.is_success());
+        assert!(result.deleted.is_empty());
+        let emptied = result.touched_objects.iter().find(|object| object.id.0 == "coin-a").unwrap();
+        assert!(matches!(emptied.data, ObjectData::Coin { balance: 0 }));
+    }
+
+    /// Loads two constants, calls `coin::transfer` (a flat 300), transfers
+    /// coin-a and returns.
+    fn mixed_bytecode() -> MoveBytecode {
+        MoveBytecode {
+            instructions: vec![
+                MoveInstruction::LoadConst(json!(1)),
+                MoveInstruction::LoadConst(json!(2)),
+                MoveInstruction::CallFunction { module: "coin".to_string(), function: "transfer".to_string() },
+                MoveInstruction::Transfer { object_id: "coin-a".to_string(), recipient: "bob".to_string() },
+                MoveInstruction::Return,
+            ],
+        }
+    }
+
+    async fn bytecode_gas(instructions: InstructionCosts) -> u64 {
+        let store = InMemoryObjectStore::new();
+        store.put_object(coin("coin-a", "alice", 10)).await.unwrap();
+        let config = VmConfig { gas_schedule: GasSchedule { instructions, ..Default::default() }, ..Default::default() };
+        let vm = MoveVMExecutor::new().with_config(config.clone());
+        let ctx = ExecutionContext::new(Some(&store), &config);
+
+        let result = vm.interpret_bytecode(&ctx, "alice", mixed_bytecode()).await;
+
+        assert!(result.status.is_success());
+        result.gas_used
+    }
+
+    #[tokio::test]
+    async fn bytecode_gas_is_the_sum_of_its_instruction_costs() {
+        let costs = InstructionCosts { load_const: 1, call_function: 100, transfer: 20, function_return: 3 };
+
+        assert_eq!(bytecode_gas(costs).await, 2 + 100 + 300 + 20 + 3);
+        assert_eq!(bytecode_gas(InstructionCosts::default()).await, 5 * 50 + 300);
+    }
+
+    #[tokio::test]
+    async fn changing_one_instruction_cost_changes_gas_by_its_count() {
+        let base = InstructionCosts { load_const: 1, call_function: 100, transfer: 20, function_return: 3 };
+        let dearer_constants = InstructionCosts { load_const: 11, ..base };
+        let dearer_transfers = InstructionCosts { transfer: 70, ..base };
+
+        let gas = bytecode_gas(base).await;
+
+        assert_eq!(bytecode_gas(dearer_constants).await, gas + 2 * 10);
+        assert_eq!(bytecode_gas(dearer_transfers).await, gas + 50);
+    }
+
+    #[tokio::test]
+    async fn creating_an_existing_id_aborts() {
+        let mint = request("alice", call("coin", "mint", Vec::new()), Vec::new());
+        let taken = ObjectID::derive(&mint.digest, 0);
+        let vm = executor(vec![coin(&taken.0, "bob", 1)]).await;
+
+        let result = vm.execute(&mint).await;
+
+        assert!(matches!(result.status, ExecutionStatus::Failure { code: abort_codes::OBJECT_ALREADY_EXISTS, .. }));
+        assert!(result.touched_objects.is_empty());
     }
 }
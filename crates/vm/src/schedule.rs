@@ -0,0 +1,122 @@
+use sui_core::transaction::{Argument, TransactionKind};
+
+/// Objects a command may read or write, as far as can be told before it runs.
+struct Footprint {
+    objects: Vec<String>,
+    /// Earlier commands whose results it consumes.
+    results: Vec<usize>,
+    /// Set when the footprint cannot be known up front, e.g. object ids
+    /// passed as plain values; such a command is ordered against everything.
+    barrier: bool,
+}
+
+impl Footprint {
+    fn of(kind: &TransactionKind) -> Self {
+        let mut footprint = Footprint {
+            objects: Vec::new(),
+            results: Vec::new(),
+            barrier: false,
+        };
+        match kind {
+            TransactionKind::Transfer { object, .. }
+            | TransactionKind::Migrate { object, .. }
+            | TransactionKind::ChangeOwner { object, .. } => footprint.objects.push(object.0.clone()),
+            TransactionKind::Airdrop { source, .. } => footprint.objects.push(source.0.clone()),
+            TransactionKind::Wrap { object, wrapper } => {
+                footprint.objects.push(object.0.clone());
+                footprint.objects.push(wrapper.0.clone());
+            }
+            TransactionKind::Call { arguments, .. } => {
+                for argument in arguments {
+                    footprint.add_argument(argument);
+                }
+            }
+            TransactionKind::TransferToSender { object } => footprint.add_argument(object),
+            // The wrapper is only known once the object is loaded.
+            TransactionKind::Unwrap { .. } | TransactionKind::Batch { .. } => footprint.barrier = true,
+            TransactionKind::Conditional { condition, then } => {
+                footprint = Footprint::of(then);
+                footprint
+                    .objects
+                    .extend(condition.objects().into_iter().map(|object| object.0.clone()));
+            }
+        }
+        footprint
+    }
+
+    fn add_argument(&mut self, argument: &Argument) {
+        match argument {
+            Argument::Object(id) => self.objects.push(id.0.clone()),
+            Argument::Result(index) => self.results.push(*index),
+            Argument::Value(_) => self.barrier = true,
+        }
+    }
+
+    fn conflicts_with(&self, other: &Footprint) -> bool {
+        self.barrier
+            || other.barrier
+            || self.objects.iter().any(|object| other.objects.contains(object))
+    }
+}
+
+/// Assigns each command a level such that a command only depends on
+/// commands at lower levels: those it shares an object with, whose result
+/// it uses, or that are barriers. Commands on the same level are
+/// independent and may run concurrently.
+pub(crate) fn execution_levels(commands: &[TransactionKind]) -> Vec<usize> {
+    let footprints: Vec<Footprint> = commands.iter().map(Footprint::of).collect();
+    let mut levels: Vec<usize> = Vec::with_capacity(commands.len());
+    for (i, footprint) in footprints.iter().enumerate() {
+        let level = (0..i)
+            .filter(|&j| footprint.results.contains(&j) || footprint.conflicts_with(&footprints[j]))
+            .map(|j| levels[j] + 1)
+            .max()
+            .unwrap_or(0);
+        levels.push(level);
+    }
+    levels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_core::object::ObjectID;
+
+    fn transfer(object: &str) -> TransactionKind {
+        TransactionKind::Transfer { object: ObjectID::new(object), recipient: "bob".to_string() }
+    }
+
+    fn call(arguments: Vec<Argument>) -> TransactionKind {
+        TransactionKind::Call {
+            package: ObjectID::new("0x2"),
+            module: "coin".to_string(),
+            function: "transfer".to_string(),
+            arguments,
+        }
+    }
+
+    #[test]
+    fn disjoint_commands_share_a_level() {
+        assert_eq!(execution_levels(&[transfer("coin-a"), transfer("coin-b"), transfer("coin-c")]), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn commands_on_the_same_object_are_ordered() {
+        let commands = [transfer("coin-a"), transfer("coin-b"), transfer("coin-a"), transfer("coin-a")];
+
+        assert_eq!(execution_levels(&commands), vec![0, 0, 1, 2]);
+    }
+
+    #[test]
+    fn results_and_unknown_footprints_order_commands() {
+        let commands = [
+            transfer("coin-a"),
+            call(vec![Argument::Result(0)]),
+            transfer("coin-b"),
+            call(vec![Argument::Value(serde_json::json!("coin-c"))]),
+            transfer("coin-d"),
+        ];
+
+        assert_eq!(execution_levels(&commands), vec![0, 1, 0, 2, 3]);
+    }
+}
@@ -0,0 +1,155 @@
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use sui_core::object::ObjectData;
+
+/// JSON type a struct field must hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+    Any,
+}
+
+impl FieldType {
+    fn matches(&self, value: &Value) -> bool {
+        match self {
+            FieldType::Bool => value.is_boolean(),
+            FieldType::Number => value.is_number(),
+            FieldType::String => value.is_string(),
+            FieldType::Array => value.is_array(),
+            FieldType::Object => value.is_object(),
+            FieldType::Any => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FieldSpec {
+    field_type: FieldType,
+    required: bool,
+}
+
+/// Expected shape of a `MoveStruct`'s fields: a JSON object with the
+/// declared fields and no others.
+#[derive(Debug, Clone, Default)]
+pub struct StructSchema {
+    fields: BTreeMap<String, FieldSpec>,
+}
+
+impl StructSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn required(mut self, name: impl Into<String>, field_type: FieldType) -> Self {
+        self.fields.insert(name.into(), FieldSpec { field_type, required: true });
+        self
+    }
+
+    pub fn optional(mut self, name: impl Into<String>, field_type: FieldType) -> Self {
+        self.fields.insert(name.into(), FieldSpec { field_type, required: false });
+        self
+    }
+
+    /// Checks `fields` against the schema, describing the first mismatch.
+    pub fn validate(&self, fields: &Value) -> Result<(), String> {
+        let Some(fields) = fields.as_object() else {
+            return Err("fields must be a JSON object".to_string());
+        };
+        for (name, spec) in &self.fields {
+            match fields.get(name) {
+                Some(value) if !spec.field_type.matches(value) => {
+                    return Err(format!("field `{}` must be {:?}", name, spec.field_type));
+                }
+                None if spec.required => return Err(format!("missing required field `{}`", name)),
+                _ => {}
+            }
+        }
+        if let Some(unknown) = fields.keys().find(|name| !self.fields.contains_key(*name)) {
+            return Err(format!("unknown field `{}`", unknown));
+        }
+        Ok(())
+    }
+}
+
+/// Schemas for `MoveStruct` types, keyed by type name. Types without a
+/// schema accept any fields. Cheap to clone.
+#[derive(Debug, Clone, Default)]
+pub struct TypeSchemas {
+    schemas: Arc<HashMap<String, StructSchema>>,
+}
+
+impl TypeSchemas {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(mut self, type_name: impl Into<String>, schema: StructSchema) -> Self {
+        Arc::make_mut(&mut self.schemas).insert(type_name.into(), schema);
+        self
+    }
+
+    pub fn get(&self, type_name: &str) -> Option<&StructSchema> {
+        self.schemas.get(type_name)
+    }
+
+    /// Validates struct data against its type's schema, if one is
+    /// registered. Other kinds of object data always pass.
+    pub fn validate(&self, data: &ObjectData) -> Result<(), String> {
+        match data {
+            ObjectData::MoveStruct { type_name, fields } => match self.get(type_name) {
+                Some(schema) => schema
+                    .validate(fields)
+                    .map_err(|reason| format!("{} does not match its schema: {}", type_name, reason)),
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn profile_schema() -> StructSchema {
+        StructSchema::new().required("level", FieldType::Number).optional("name", FieldType::String)
+    }
+
+    fn profile(fields: Value) -> ObjectData {
+        ObjectData::MoveStruct { type_name: "game::Profile".to_string(), fields }
+    }
+
+    #[test]
+    fn fields_matching_the_schema_pass() {
+        let schema = profile_schema();
+
+        assert_eq!(schema.validate(&json!({ "level": 1 })), Ok(()));
+        assert_eq!(schema.validate(&json!({ "level": 1, "name": "hero" })), Ok(()));
+    }
+
+    #[test]
+    fn mismatched_fields_are_described() {
+        let schema = profile_schema();
+
+        assert_eq!(schema.validate(&json!({ "name": "hero" })), Err("missing required field `level`".to_string()));
+        assert_eq!(schema.validate(&json!({ "level": "1" })), Err("field `level` must be Number".to_string()));
+        assert_eq!(schema.validate(&json!({ "level": 1, "xp": 0 })), Err("unknown field `xp`".to_string()));
+        assert_eq!(schema.validate(&json!([1])), Err("fields must be a JSON object".to_string()));
+    }
+
+    #[test]
+    fn unregistered_types_and_other_data_are_unconstrained() {
+        let schemas = TypeSchemas::new().register("game::Profile", profile_schema());
+        let other = ObjectData::MoveStruct { type_name: "game::Sword".to_string(), fields: json!("anything") };
+
+        assert!(schemas.validate(&profile(json!({ "xp": 0 }))).is_err());
+        assert_eq!(schemas.validate(&other), Ok(()));
+        assert_eq!(schemas.validate(&ObjectData::Coin { balance: 1 }), Ok(()));
+    }
+}
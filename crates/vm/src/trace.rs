@@ -0,0 +1,32 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// State of the interpreter after one instruction.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TraceEntry {
+    /// Position of the instruction within its function body.
+    pub index: usize,
+    /// The instruction, as written by its `Debug` form.
+    pub instruction: String,
+    /// The operand stack once the instruction finished.
+    pub stack: Vec<Value>,
+    /// Gas consumed by the function so far, this instruction included.
+    pub gas_used: u64,
+}
+
+/// Step-by-step record of the Move bytecode a transaction ran, in execution
+/// order. Collected only by [`MoveVMExecutor::execute_traced`](crate::MoveVMExecutor::execute_traced).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ExecutionTrace {
+    pub entries: Vec<TraceEntry>,
+}
+
+impl ExecutionTrace {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
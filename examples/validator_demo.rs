@@ -49,6 +49,11 @@ async fn main() -> Result<()> {
         println!("📊 Latest checkpoint:");
         println!("   - Sequence: {}", checkpoint.sequence_number);
         println!("   - Transactions: {}", checkpoint.transaction_count);
+        println!("   - Gas used: {}", checkpoint.total_gas_used);
+        println!(
+            "   - Objects created/mutated/deleted: {}/{}/{}",
+            checkpoint.objects_created, checkpoint.objects_mutated, checkpoint.objects_deleted
+        );
         println!("   - Root digest: {}\n", checkpoint.root_digest);
     }
 
@@ -1,4 +1,4 @@
-use nautilus_shared::{EnclaveRequest, EnclaveResponse};
+use nautilus_shared::{EnclavePayload, EnclaveRequest, EnclaveResponse};
 
 pub fn handle_request(attestation_token: &str, request: EnclaveRequest) -> EnclaveResponse {
     if attestation_token.is_empty() {
@@ -8,9 +8,19 @@ pub fn handle_request(attestation_token: &str, request: EnclaveRequest) -> Encla
         };
     }
 
-    let message = match serde_json::from_str::<serde_json::Value>(&request.payload) {
-        Ok(parsed) => format!("Enclave confirmed action: {}", parsed.get("action").and_then(|a| a.as_str()).unwrap_or("unknown")),
-        Err(_) => "Unable to read payload".into(),
+    let payload = match serde_json::from_str::<EnclavePayload>(&request.payload) {
+        Ok(payload) => payload,
+        Err(e) => {
+            return EnclaveResponse {
+                accepted: false,
+                message: format!("Rejected malformed payload: {}", e),
+            }
+        }
+    };
+
+    let message = match payload {
+        EnclavePayload::ProcessEffects { digest } => format!("Enclave processed effects for {}", digest),
+        EnclavePayload::Attest { nonce } => format!("Enclave attested nonce {}", nonce),
     };
 
     EnclaveResponse {
@@ -19,3 +29,37 @@ pub fn handle_request(attestation_token: &str, request: EnclaveRequest) -> Encla
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(payload: &str) -> EnclaveRequest {
+        EnclaveRequest { nonce: 1, payload: payload.to_string() }
+    }
+
+    #[test]
+    fn process_effects_payload_is_handled() {
+        let payload = serde_json::to_string(&EnclavePayload::ProcessEffects { digest: "tx-1".to_string() }).unwrap();
+
+        let response = handle_request("token", request(&payload));
+
+        assert!(response.accepted);
+        assert_eq!(response.message, "Enclave processed effects for tx-1");
+    }
+
+    #[test]
+    fn unknown_action_is_rejected_by_name() {
+        let response = handle_request("token", request(r#"{"action": "self_destruct"}"#));
+
+        assert!(!response.accepted);
+        assert!(response.message.contains("unknown variant `self_destruct`"), "{}", response.message);
+    }
+
+    #[test]
+    fn payload_missing_a_field_is_rejected() {
+        let response = handle_request("token", request(r#"{"action": "attest"}"#));
+
+        assert!(!response.accepted);
+        assert!(response.message.contains("missing field `nonce`"), "{}", response.message);
+    }
+}
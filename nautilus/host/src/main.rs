@@ -1,6 +1,6 @@
 use aws_nautilus_sdk::{EnclaveInfo, NautilusClient};
 use nautilus_enclave::handle_request;
-use nautilus_shared::EnclaveRequest;
+use nautilus_shared::{EnclavePayload, EnclaveRequest};
 use rand::Rng;
 use std::error::Error;
 
@@ -14,11 +14,9 @@ async fn main() -> Result<(), Box<dyn Error>> {
     }).await?;
     let attestation = client.attest(&enclave_id).await?;
 
-    let payload = serde_json::json!({
-        "action": "process_effects",
-        "digest": "example",
-    })
-    .to_string();
+    let payload = serde_json::to_string(&EnclavePayload::ProcessEffects {
+        digest: "example".into(),
+    })?;
 
     let request = EnclaveRequest {
         nonce: rand::thread_rng().gen(),
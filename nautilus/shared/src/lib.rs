@@ -12,3 +12,13 @@ pub struct EnclaveResponse {
     pub message: String,
 }
 
+
+/// The actions a host may ask the enclave to perform, carried in
+/// [`EnclaveRequest::payload`] as JSON tagged by `action`, e.g.
+/// `{"action": "process_effects", "digest": "..."}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum EnclavePayload {
+    ProcessEffects { digest: String },
+    Attest { nonce: u64 },
+}